@@ -0,0 +1,335 @@
+// File: bangk-ico/src/airdrop.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 13:27:14
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+use bangk_macro::pda;
+use bangk_onchain_common::{
+    pda::{BangkPda, PdaType, Seed},
+    Error,
+};
+use borsh::BorshDeserialize;
+use solana_program::{keccak, msg, pubkey::Pubkey};
+
+use crate::{unvesting::UnvestingScheme, WalletType};
+
+/// Maximum number of recipients of a single airdrop.
+///
+/// Keeps the bitmap of the claims (10KB) small enough to be processed during a claim.
+pub const MAX_AIRDROP_RECIPIENTS: u32 = 80_000;
+
+/// PDA storing the definition of a Merkle airdrop.
+#[pda(kind = PdaType::Airdrop, seed = "Airdrop", seed = id)]
+pub struct AirdropPda {
+    /// Identifier of the airdrop.
+    pub id: u8,
+    /// Internal wallet funding the airdrop.
+    pub source: WalletType,
+    /// Root of the Merkle tree of the claims (see `get_airdrop_leaf`).
+    pub root: [u8; 32],
+    /// Total number of tokens that can be claimed.
+    pub total: u64,
+    /// Number of tokens already claimed.
+    pub claimed: u64,
+    /// Number of recipients (leaves of the Merkle tree).
+    pub recipients: u32,
+    /// Timestamp after which the airdrop can no longer be claimed.
+    pub deadline: i64,
+    /// Unvesting scheme applied to the claimed tokens (if any).
+    pub unvesting: Option<UnvestingScheme>,
+    /// Set once the unclaimed tokens were returned to the source wallet.
+    pub reclaimed: bool,
+}
+
+impl<'a> AirdropPda<'a> {
+    /// Create a new airdrop.
+    ///
+    /// # Parameters
+    /// * `bump` - Bump used to derive the PDA address,
+    /// * `id` - Identifier of the airdrop,
+    /// * `source` - Internal wallet funding the airdrop,
+    /// * `root` - Root of the Merkle tree of the claims,
+    /// * `total` - Total number of tokens that can be claimed,
+    /// * `recipients` - Number of recipients,
+    /// * `deadline` - Timestamp after which the airdrop can no longer be claimed,
+    /// * `unvesting` - Unvesting scheme applied to the claimed tokens (if any).
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub const fn new(
+        bump: u8,
+        id: u8,
+        source: WalletType,
+        root: [u8; 32],
+        total: u64,
+        recipients: u32,
+        deadline: i64,
+        unvesting: Option<UnvestingScheme>,
+    ) -> Self {
+        Self {
+            pda_type: Self::PDA_TYPE,
+            bump,
+            account: None,
+            id,
+            source,
+            root,
+            total,
+            claimed: 0,
+            recipients,
+            deadline,
+            unvesting,
+            reclaimed: false,
+        }
+    }
+
+    /// Checks if the airdrop can still be claimed at a given time.
+    #[must_use]
+    pub const fn is_open(&self, now: i64) -> bool {
+        now < self.deadline
+    }
+
+    /// Record a claim on the airdrop.
+    ///
+    /// # Parameters
+    /// * `amount` - Number of tokens claimed.
+    ///
+    /// # Errors
+    /// If the total amount of the airdrop would be exceeded.
+    pub fn claim(&mut self, amount: u64) -> Result<(), Error> {
+        let claimed = self.claimed.saturating_add(amount);
+        if claimed > self.total {
+            msg!(
+                "the airdrop's total would be exceeded ({} vs {})",
+                claimed,
+                self.total
+            );
+            return Err(Error::InvalidAmount);
+        }
+        self.claimed = claimed;
+        Ok(())
+    }
+}
+
+/// PDA storing the bitmap of the claims made on an airdrop.
+#[pda(kind = PdaType::AirdropClaims, seed = "AirdropClaims", seed = id)]
+pub struct AirdropClaimsPda {
+    /// Identifier of the airdrop.
+    pub id: u8,
+    /// One bit per recipient, set once they claimed their tokens.
+    pub bitmap: Vec<u8>,
+}
+
+impl<'a> AirdropClaimsPda<'a> {
+    /// Create a new empty bitmap of claims.
+    ///
+    /// # Parameters
+    /// * `bump` - Bump used to derive the PDA address,
+    /// * `id` - Identifier of the airdrop,
+    /// * `recipients` - Number of recipients of the airdrop.
+    #[must_use]
+    pub fn new(bump: u8, id: u8, recipients: u32) -> Self {
+        Self {
+            pda_type: Self::PDA_TYPE,
+            bump,
+            account: None,
+            id,
+            bitmap: vec![0; usize::try_from(recipients.div_ceil(8)).unwrap_or_default()],
+        }
+    }
+
+    /// Checks if a recipient already claimed their tokens.
+    ///
+    /// # Parameters
+    /// * `index` - Index of the recipient in the Merkle tree.
+    #[must_use]
+    pub fn is_claimed(&self, index: u32) -> bool {
+        let (byte, mask) = Self::position(index);
+        self.bitmap.get(byte).is_some_and(|bits| bits & mask != 0)
+    }
+
+    /// Mark a recipient's tokens as claimed.
+    ///
+    /// # Parameters
+    /// * `index` - Index of the recipient in the Merkle tree.
+    ///
+    /// # Errors
+    /// If the index is out of the airdrop or the tokens were already claimed.
+    pub fn set_claimed(&mut self, index: u32) -> Result<(), Error> {
+        let (byte, mask) = Self::position(index);
+        let Some(bits) = self.bitmap.get_mut(byte) else {
+            msg!("recipient {} is not part of the airdrop", index);
+            return Err(Error::InvalidMerkleProof);
+        };
+        if *bits & mask != 0 {
+            return Err(Error::AirdropAlreadyClaimed);
+        }
+        *bits |= mask;
+        Ok(())
+    }
+
+    /// Get the byte and the bit mask of a recipient in the bitmap.
+    fn position(index: u32) -> (usize, u8) {
+        let byte = usize::try_from(index.wrapping_shr(3)).unwrap_or(usize::MAX);
+        (byte, 1_u8.wrapping_shl(index & 7))
+    }
+}
+
+/// Get the address and bump of the token account holding the tokens of an airdrop.
+///
+/// # Parameters
+/// * `id` - Identifier of the airdrop.
+#[must_use]
+pub fn get_airdrop_vault_pda(id: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"AirdropVault", &[id]], &crate::ID)
+}
+
+/// Get the seeds of the token account holding the tokens of an airdrop.
+///
+/// # Parameters
+/// * `id` - Identifier of the airdrop.
+#[must_use]
+pub fn get_airdrop_vault_seeds(id: u8) -> Vec<Vec<u8>> {
+    let (_address, bump) = get_airdrop_vault_pda(id);
+    let seeds: Vec<Seed> = vec!["AirdropVault".into(), id.into(), bump.into()];
+    seeds.into_iter().map(Into::into).collect()
+}
+
+/// Get the leaf of the Merkle tree of an airdrop for a recipient.
+///
+/// # Parameters
+/// * `index` - Index of the recipient in the tree,
+/// * `claimant` - Wallet of the recipient,
+/// * `amount` - Number of tokens the recipient can claim.
+#[must_use]
+#[allow(clippy::little_endian_bytes)]
+pub fn get_airdrop_leaf(index: u32, claimant: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        &index.to_le_bytes(),
+        claimant.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Hash two nodes of a Merkle tree (sorted, so that proofs don't need the side of each node).
+fn hash_nodes(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    if left <= right {
+        keccak::hashv(&[left, right]).to_bytes()
+    } else {
+        keccak::hashv(&[right, left]).to_bytes()
+    }
+}
+
+/// Checks that a leaf is part of a Merkle tree.
+///
+/// # Parameters
+/// * `leaf` - Leaf to check,
+/// * `proof` - Sibling nodes from the leaf to the root,
+/// * `root` - Root of the tree.
+#[must_use]
+pub fn verify_merkle_proof(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_nodes(&node, sibling))
+        == *root
+}
+
+/// Get the upper level of a Merkle tree (a node without sibling is moved up as is).
+fn merkle_parents(nodes: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    nodes
+        .chunks(2)
+        .filter_map(|pair| match pair {
+            [left, right] => Some(hash_nodes(left, right)),
+            [single] => Some(*single),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Get the root of the Merkle tree built from a list of leaves.
+///
+/// # Parameters
+/// * `leaves` - Leaves of the tree (see `get_airdrop_leaf`).
+#[must_use]
+pub fn get_merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    let mut nodes = leaves.to_vec();
+    while nodes.len() > 1 {
+        nodes = merkle_parents(&nodes);
+    }
+    nodes.first().copied().unwrap_or_default()
+}
+
+/// Get the proof that a leaf is part of the Merkle tree built from a list of leaves.
+///
+/// # Parameters
+/// * `leaves` - Leaves of the tree (see `get_airdrop_leaf`),
+/// * `index` - Index of the leaf.
+#[must_use]
+pub fn get_merkle_proof(leaves: &[[u8; 32]], index: usize) -> Vec<[u8; 32]> {
+    let mut proof = Vec::new();
+    let mut nodes = leaves.to_vec();
+    let mut position = index;
+    while nodes.len() > 1 {
+        if let Some(sibling) = nodes.get(position ^ 1) {
+            proof.push(*sibling);
+        }
+        nodes = merkle_parents(&nodes);
+        position = position.wrapping_shr(1);
+    }
+    proof
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn leaves(count: u32) -> Vec<[u8; 32]> {
+        (0..count)
+            .map(|index| get_airdrop_leaf(index, &Pubkey::new_unique(), u64::from(index) + 1))
+            .collect()
+    }
+
+    #[test]
+    fn proofs_are_valid() {
+        for count in 1..=9 {
+            let tree = leaves(count);
+            let root = get_merkle_root(&tree);
+            for (index, leaf) in tree.iter().enumerate() {
+                let proof = get_merkle_proof(&tree, index);
+                assert!(
+                    verify_merkle_proof(*leaf, &proof, &root),
+                    "invalid proof for leaf {index} of {count}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn forged_leaf() {
+        let tree = leaves(5);
+        let root = get_merkle_root(&tree);
+        let proof = get_merkle_proof(&tree, 2);
+        let forged = get_airdrop_leaf(2, &Pubkey::new_unique(), 1_000);
+        assert!(
+            !verify_merkle_proof(forged, &proof, &root),
+            "a forged leaf was accepted"
+        );
+    }
+
+    #[test]
+    fn claims_bitmap() {
+        let mut claims = AirdropClaimsPda::new(255, 0, 10);
+        assert_eq!(claims.bitmap.len(), 2);
+        assert!(!claims.is_claimed(9), "unexpected claim");
+        claims.set_claimed(9).unwrap();
+        assert!(claims.is_claimed(9), "the claim was not recorded");
+        assert!(!claims.is_claimed(8), "unexpected claim");
+        assert_eq!(claims.set_claimed(9), Err(Error::AirdropAlreadyClaimed));
+        assert_eq!(claims.set_claimed(16), Err(Error::InvalidMerkleProof));
+    }
+}
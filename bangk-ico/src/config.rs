@@ -3,7 +3,7 @@
 // Creation date: Thursday 13 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Monday 12 August 2024 @ 16:48:10
+// Last modified: Sunday 18 October 2026 @ 18:08:28
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -11,11 +11,21 @@
 use std::collections::HashMap;
 
 use bangk_macro::pda;
-use bangk_onchain_common::pda::{BangkPda, PdaType};
-use borsh::BorshDeserialize;
-use solana_program::pubkey::Pubkey;
+use bangk_onchain_common::{
+    pda::{BangkPda, PdaType},
+    Error,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError, pubkey::Pubkey};
 
-use crate::unvesting::{UnvestingScheme, UnvestingType};
+use crate::{
+    investment::{Acceleration, InvestmentCap},
+    position::PositionTransferPolicy,
+    referral::ReferralProgram,
+    sale::{BonusRule, SalePhase},
+    unvesting::{UnvestingScheme, UnvestingType},
+    WalletType, WALLET_INIT_AMOUNT,
+};
 
 /// Configuration PDA of the ICO program.
 #[pda(kind = PdaType::ProgramConfiguration, seed = "Configuration")]
@@ -28,6 +38,25 @@ pub struct ConfigurationPda {
     pub launch_date: i64,
     /// Amount of invested tokens
     pub amount_invested: u64,
+    /// Amount of invested tokens per type of investment.
+    pub invested_per_kind: HashMap<UnvestingType, u64>,
+    /// Caps on the investments per type of investment.
+    pub investment_caps: HashMap<UnvestingType, InvestmentCap>,
+    /// Phases of the token sale.
+    pub sale_phases: Vec<SalePhase>,
+    /// Mint of the currency accepted for the on-chain purchases (set with the sale treasury).
+    pub payment_mint: Option<Pubkey>,
+    /// Set if the token sale has been aborted (the purchases can then be refunded).
+    pub sale_aborted: bool,
+    /// Program rewarding the referrers of the investors (if any).
+    pub referral: Option<ReferralProgram>,
+    /// Bonus granted to the investors depending on the sale phase or the amount invested.
+    pub bonus_rules: Vec<BonusRule>,
+    /// Percentage (x1000 factor) unlocked on top of the unvesting scheme of each type of investment
+    /// by acceleration events.
+    pub accelerated_per_kind: HashMap<UnvestingType, u32>,
+    /// Restrictions on the transfers of the tokenised vesting positions (disabled if `None`).
+    pub position_transfers: Option<PositionTransferPolicy>,
 }
 
 impl<'a> ConfigurationPda<'a> {
@@ -46,6 +75,527 @@ impl<'a> ConfigurationPda<'a> {
             admin_multisig: *admin,
             launch_date: 0,
             amount_invested: 0,
+            invested_per_kind: HashMap::new(),
+            investment_caps: HashMap::new(),
+            sale_phases: Vec::new(),
+            payment_mint: None,
+            sale_aborted: false,
+            referral: None,
+            bonus_rules: Vec::new(),
+            accelerated_per_kind: HashMap::new(),
+            position_transfers: None,
         }
     }
+
+    /// Add tokens to the invested amount.
+    ///
+    /// # Parameters
+    /// * `kind` - Type of investment,
+    /// * `amount` - Number of tokens invested.
+    ///
+    /// # Errors
+    /// If the tokens available in the ICO wallet or the cap of the type of investment would be exceeded.
+    pub fn add_invested(&mut self, kind: UnvestingType, amount: u64) -> Result<(), Error> {
+        self.amount_invested = self.amount_invested.saturating_add(amount);
+
+        let max_amount = WALLET_INIT_AMOUNT
+            .iter()
+            .find(|(wallet, _amount)| *wallet == WalletType::Ico)
+            .map(|(_kind, allocation)| allocation.saturating_mul(1_000_000))
+            .unwrap_or_default();
+        if self.amount_invested > max_amount {
+            msg!(
+                "the maximum amount of available tokens has been exceeded ({} vs {})",
+                self.amount_invested,
+                max_amount
+            );
+            return Err(Error::InvalidAmount);
+        }
+
+        let invested = self.invested_per_kind.entry(kind).or_default();
+        *invested = invested.saturating_add(amount);
+        if let Some(cap) = self.investment_caps.get(&kind).and_then(|cap| cap.total) {
+            if *invested > cap {
+                msg!(
+                    "the cap of the {:?} investments would be exceeded ({} vs {})",
+                    kind,
+                    invested,
+                    cap
+                );
+                return Err(Error::InvestmentCapExceeded);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove tokens from the invested amount (cancelled or clawed back investments).
+    ///
+    /// # Parameters
+    /// * `kind` - Type of investment,
+    /// * `amount` - Number of tokens removed.
+    pub fn remove_invested(&mut self, kind: UnvestingType, amount: u64) {
+        self.amount_invested = self.amount_invested.saturating_sub(amount);
+        if let Some(invested) = self.invested_per_kind.get_mut(&kind) {
+            *invested = invested.saturating_sub(amount);
+        }
+    }
+
+    /// Replace the investment caps.
+    ///
+    /// # Parameters
+    /// * `caps` - New definition of the caps (at most one per type of investment).
+    ///
+    /// # Errors
+    /// If one of the caps is invalid, defined twice or below what was already invested.
+    pub fn set_investment_caps(&mut self, caps: &[InvestmentCap]) -> Result<(), Error> {
+        let mut map = HashMap::new();
+        for cap in caps {
+            if !cap.is_valid() {
+                return Err(Error::InvalidOperation);
+            }
+            let invested = self
+                .invested_per_kind
+                .get(&cap.kind)
+                .copied()
+                .unwrap_or_default();
+            if cap.total.is_some_and(|total| total < invested) {
+                msg!(
+                    "the cap of the {:?} investments is below what was already invested ({})",
+                    cap.kind,
+                    invested
+                );
+                return Err(Error::InvalidOperation);
+            }
+            if map.insert(cap.kind, *cap).is_some() {
+                msg!("the {:?} investments are capped twice", cap.kind);
+                return Err(Error::InvalidOperation);
+            }
+        }
+        self.investment_caps = map;
+        Ok(())
+    }
+
+    /// Check that a user's investment doesn't exceed the cap of its type for a single user.
+    ///
+    /// # Parameters
+    /// * `kind` - Type of investment,
+    /// * `invested` - Number of tokens the user already invested in this type,
+    /// * `amount` - Number of tokens invested.
+    ///
+    /// # Errors
+    /// If the cap would be exceeded.
+    pub fn check_user_cap(
+        &self,
+        kind: UnvestingType,
+        invested: u64,
+        amount: u64,
+    ) -> Result<(), Error> {
+        let Some(cap) = self.investment_caps.get(&kind).and_then(|cap| cap.per_user) else {
+            return Ok(());
+        };
+        let total = invested.saturating_add(amount);
+        if total > cap {
+            msg!(
+                "the cap of the {:?} investments for a single user would be exceeded ({} vs {})",
+                kind,
+                total,
+                cap
+            );
+            return Err(Error::InvestmentCapExceeded);
+        }
+        Ok(())
+    }
+
+    /// Replace the sale phases.
+    ///
+    /// The number of tokens already sold is kept for the phases that are still present
+    /// (same type of investment and same start).
+    ///
+    /// # Parameters
+    /// * `phases` - New definition of the sale phases.
+    ///
+    /// # Errors
+    /// If one of the phases is invalid, or if two phases for the same type of investment overlap.
+    pub fn set_sale_phases(&mut self, phases: &[SalePhase]) -> Result<(), Error> {
+        let mut new_phases: Vec<SalePhase> = Vec::with_capacity(phases.len());
+        for phase in phases {
+            if !phase.is_valid() {
+                return Err(Error::InvalidSalePhase);
+            }
+            if new_phases
+                .iter()
+                .any(|other| other.kind == phase.kind && other.overlaps(phase))
+            {
+                msg!("sale phases for {:?} are overlapping", phase.kind);
+                return Err(Error::InvalidSalePhase);
+            }
+            let sold = self
+                .sale_phases
+                .iter()
+                .find(|old| old.kind == phase.kind && old.start == phase.start)
+                .map_or(0, |old| old.sold);
+            if sold > phase.cap {
+                msg!(
+                    "the new cap of the sale phase is below what was already sold ({} vs {})",
+                    phase.cap,
+                    sold
+                );
+                return Err(Error::InvalidSalePhase);
+            }
+            new_phases.push(SalePhase { sold, ..*phase });
+        }
+        self.sale_phases = new_phases;
+        Ok(())
+    }
+
+    /// Get the sale phase running at a given time for a type of investment.
+    ///
+    /// # Parameters
+    /// * `kind` - Type of investment,
+    /// * `now` - Current timestamp.
+    #[must_use]
+    pub fn active_sale_phase(&self, kind: UnvestingType, now: i64) -> Option<&SalePhase> {
+        self.sale_phases
+            .iter()
+            .find(|phase| phase.kind == kind && phase.is_active(now))
+    }
+
+    /// Record the sale of tokens in the active sale phase.
+    ///
+    /// Types of investments that are not sold (founders, advisers) are not bound to sale phases.
+    ///
+    /// # Parameters
+    /// * `kind` - Type of investment,
+    /// * `amount` - Number of tokens sold,
+    /// * `now` - Current timestamp.
+    ///
+    /// # Returns
+    /// The sale phase in which the tokens were sold, if any.
+    ///
+    /// # Errors
+    /// If there is no running sale phase for the investment type, or if its cap would be exceeded.
+    pub fn record_sale(
+        &mut self,
+        kind: UnvestingType,
+        amount: u64,
+        now: i64,
+    ) -> Result<Option<SalePhase>, Error> {
+        if !kind.is_sale() {
+            return Ok(None);
+        }
+        let Some(phase) = self
+            .sale_phases
+            .iter_mut()
+            .find(|phase| phase.kind == kind && phase.is_active(now))
+        else {
+            msg!("no sale phase is running for {:?}", kind);
+            return Err(Error::NoActiveSalePhase);
+        };
+        phase.sell(amount)?;
+        Ok(Some(*phase))
+    }
+
+    /// Remove cancelled or refunded tokens from the sale phase in which they were sold.
+    ///
+    /// # Parameters
+    /// * `kind` - Type of investment,
+    /// * `amount` - Number of tokens removed,
+    /// * `timestamp` - Timestamp of the sale.
+    pub fn cancel_sale(&mut self, kind: UnvestingType, amount: u64, timestamp: i64) {
+        if let Some(phase) = self
+            .sale_phases
+            .iter_mut()
+            .find(|phase| phase.kind == kind && phase.is_active(timestamp))
+        {
+            phase.sold = phase.sold.saturating_sub(amount);
+        }
+    }
+
+    /// Replace the bonus rules.
+    ///
+    /// # Parameters
+    /// * `rules` - New definition of the bonus rules.
+    ///
+    /// # Errors
+    /// If one of the rules is invalid.
+    pub fn set_bonus_rules(&mut self, rules: &[BonusRule]) -> Result<(), Error> {
+        if !rules.iter().all(BonusRule::is_valid) {
+            return Err(Error::InvalidBonusRule);
+        }
+        self.bonus_rules = rules.to_vec();
+        Ok(())
+    }
+
+    /// Get the bonus rule applying to an investment.
+    ///
+    /// When several rules apply (amount tiers for example), the most generous one is used.
+    ///
+    /// # Parameters
+    /// * `kind` - Type of investment,
+    /// * `phase` - Sale phase in which the investment is made (if any),
+    /// * `amount` - Number of tokens invested.
+    #[must_use]
+    pub fn bonus_rule(
+        &self,
+        kind: UnvestingType,
+        phase: Option<&SalePhase>,
+        amount: u64,
+    ) -> Option<&BonusRule> {
+        self.bonus_rules
+            .iter()
+            .filter(|rule| rule.applies(kind, phase, amount))
+            .max_by_key(|rule| rule.rate)
+    }
+
+    /// Set the amounts invested per type of investment of a configuration converted from the
+    /// v1.0.0 of the program, which only recorded their total.
+    ///
+    /// # Parameters
+    /// * `invested` - Number of tokens invested in each type of investment.
+    ///
+    /// # Errors
+    /// If a type of investment is given twice, or if the amounts don't add up to the amount invested.
+    pub fn seed_invested_per_kind(
+        &mut self,
+        invested: &[(UnvestingType, u64)],
+    ) -> Result<(), Error> {
+        let mut map = HashMap::new();
+        let mut total = 0_u64;
+        for (kind, amount) in invested {
+            if map.insert(*kind, *amount).is_some() {
+                msg!("the {:?} investments are given twice", kind);
+                return Err(Error::InvalidOperation);
+            }
+            total = total.checked_add(*amount).ok_or(Error::IntegerOverflow)?;
+        }
+        if total != self.amount_invested {
+            msg!(
+                "the invested amounts don't add up to the amount invested ({} vs {})",
+                total,
+                self.amount_invested
+            );
+            return Err(Error::InvalidAmount);
+        }
+        self.invested_per_kind = map;
+        Ok(())
+    }
+
+    /// Accelerate the vesting of all the investments of a given type.
+    ///
+    /// # Parameters
+    /// * `kind` - Type of investment,
+    /// * `acceleration` - The acceleration to apply.
+    ///
+    /// # Returns
+    /// `false` if the investments of this type were already fully accelerated.
+    pub fn accelerate(&mut self, kind: UnvestingType, acceleration: Acceleration) -> bool {
+        let accelerated = self.accelerated_per_kind.entry(kind).or_default();
+        if *accelerated >= 100_000 {
+            return false;
+        }
+        *accelerated = match acceleration {
+            Acceleration::Full => 100_000,
+            Acceleration::Extra(rate) => accelerated.saturating_add(rate).min(100_000),
+        };
+        true
+    }
+
+    /// Reads a configuration stored with the layout of the v1.0.0 of the program.
+    ///
+    /// # Parameters
+    /// * `account` - Account of the configuration PDA.
+    ///
+    /// # Errors
+    /// If the data of the account does not match the legacy layout.
+    pub fn from_legacy_account(account: &AccountInfo<'a>) -> Result<Self, ProgramError> {
+        let data = account.try_borrow_data()?;
+        let mut res = Self::from_legacy_slice(&data)?;
+        res.account = Some(account.clone());
+        Ok(res)
+    }
+
+    /// Converts data stored with the layout of the v1.0.0 of the program.
+    ///
+    /// The settings added since are left empty (no caps, sale phases, referral program…).
+    ///
+    /// # Parameters
+    /// * `data` - Data of the account.
+    ///
+    /// # Errors
+    /// If the data does not match the legacy layout.
+    pub fn from_legacy_slice(data: &[u8]) -> Result<Self, Error> {
+        let legacy =
+            LegacyConfigurationPda::try_from_slice(data).map_err(|_err| Error::InvalidRawData)?;
+        if legacy.pda_type != Self::PDA_TYPE {
+            return Err(Error::InvalidPdaType);
+        }
+        let mut res = Self::new(legacy.bump, &[], &legacy.admin_multisig);
+        res.unvesting = legacy.unvesting;
+        res.launch_date = legacy.launch_date;
+        res.amount_invested = legacy.amount_invested;
+        Ok(res)
+    }
+}
+
+/// Layout of the `ConfigurationPda` in the v1.0.0 of the program.
+#[derive(BorshSerialize, BorshDeserialize)]
+struct LegacyConfigurationPda {
+    pda_type: PdaType,
+    bump: u8,
+    unvesting: HashMap<UnvestingType, UnvestingScheme>,
+    admin_multisig: Pubkey,
+    launch_date: i64,
+    amount_invested: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn legacy_data(pda_type: PdaType) -> Vec<u8> {
+        let scheme = UnvestingScheme {
+            kind: UnvestingType::PrivateSells,
+            start: 1,
+            duration: 10,
+            initial_unvesting: 20_000,
+            weekly_unvesting: 10_000,
+            final_unvesting: 0,
+        };
+        let legacy = LegacyConfigurationPda {
+            pda_type,
+            bump: 253,
+            unvesting: HashMap::from([(scheme.kind, scheme)]),
+            admin_multisig: Pubkey::new_from_array([7; 32]),
+            launch_date: 1_720_000_000,
+            amount_invested: 5_000_000,
+        };
+        borsh::to_vec(&legacy).unwrap()
+    }
+
+    #[test]
+    fn legacy_layout_is_migrated() {
+        let data = legacy_data(PdaType::ProgramConfiguration);
+        assert!(ConfigurationPda::try_from_slice(&data).is_err());
+
+        let config = ConfigurationPda::from_legacy_slice(&data).unwrap();
+        assert_eq!(config.bump, 253);
+        assert_eq!(config.admin_multisig, Pubkey::new_from_array([7; 32]));
+        assert_eq!(config.launch_date, 1_720_000_000);
+        assert_eq!(config.amount_invested, 5_000_000);
+        assert_eq!(config.unvesting.len(), 1);
+        assert!(config.unvesting.contains_key(&UnvestingType::PrivateSells));
+        assert!(config.invested_per_kind.is_empty());
+        assert!(config.sale_phases.is_empty());
+        assert!(!config.sale_aborted);
+        assert!(config.referral.is_none());
+        assert!(config.accelerated_per_kind.is_empty());
+        assert!(config.position_transfers.is_none());
+
+        // The migrated PDA can be read back with the current layout
+        let migrated = borsh::to_vec(&config).unwrap();
+        let current = ConfigurationPda::try_from_slice(&migrated).unwrap();
+        assert_eq!(current.amount_invested, config.amount_invested);
+        assert_eq!(current.admin_multisig, config.admin_multisig);
+    }
+
+    #[test]
+    fn seeded_invested_amounts() {
+        let data = legacy_data(PdaType::ProgramConfiguration);
+        let mut config = ConfigurationPda::from_legacy_slice(&data).unwrap();
+
+        assert!(config
+            .seed_invested_per_kind(&[(UnvestingType::PrivateSells, 4_000_000)])
+            .is_err_and(|err| err == Error::InvalidAmount));
+        assert!(config
+            .seed_invested_per_kind(&[
+                (UnvestingType::PrivateSells, 2_500_000),
+                (UnvestingType::PrivateSells, 2_500_000)
+            ])
+            .is_err_and(|err| err == Error::InvalidOperation));
+        assert!(config.invested_per_kind.is_empty());
+
+        config
+            .seed_invested_per_kind(&[
+                (UnvestingType::PrivateSells, 4_000_000),
+                (UnvestingType::TeamFounders, 1_000_000),
+            ])
+            .unwrap();
+        assert_eq!(
+            config
+                .invested_per_kind
+                .get(&UnvestingType::TeamFounders)
+                .copied(),
+            Some(1_000_000)
+        );
+    }
+
+    #[test]
+    fn invalid_legacy_data() {
+        let data = legacy_data(PdaType::IcoInvestment);
+        assert!(ConfigurationPda::from_legacy_slice(&data)
+            .is_err_and(|err| err == Error::InvalidPdaType));
+
+        let current = ConfigurationPda::new(1, &[], &Pubkey::new_unique());
+        let data_current = borsh::to_vec(&current).unwrap();
+        assert!(ConfigurationPda::from_legacy_slice(&data_current).is_err());
+    }
+
+    #[test]
+    fn cancelled_sales() {
+        let phase = |start, end| SalePhase {
+            kind: UnvestingType::PublicSells1,
+            start,
+            end,
+            price: 1,
+            cap: 1_000,
+            sold: 0,
+        };
+        let mut config = ConfigurationPda::new(1, &[], &Pubkey::new_unique());
+        config
+            .set_sale_phases(&[phase(0, 100), phase(100, 200)])
+            .unwrap();
+        config
+            .record_sale(UnvestingType::PublicSells1, 300, 50)
+            .unwrap();
+        config
+            .record_sale(UnvestingType::PublicSells1, 200, 150)
+            .unwrap();
+
+        // The tokens are removed from the phase in which they were sold
+        config.cancel_sale(UnvestingType::PublicSells1, 100, 50);
+        config.cancel_sale(UnvestingType::PublicSells1, 500, 150);
+        config.cancel_sale(UnvestingType::PrivateSells, 100, 50);
+        let sold = config
+            .sale_phases
+            .iter()
+            .map(|phase| phase.sold)
+            .collect::<Vec<_>>();
+        assert_eq!(sold, vec![200, 0]);
+    }
+
+    #[test]
+    fn accelerated_kinds() {
+        let mut config = ConfigurationPda::new(1, &[], &Pubkey::new_unique());
+        assert!(config.accelerate(UnvestingType::TeamFounders, Acceleration::Extra(60_000)));
+        assert!(config.accelerate(UnvestingType::TeamFounders, Acceleration::Extra(60_000)));
+        assert_eq!(
+            config
+                .accelerated_per_kind
+                .get(&UnvestingType::TeamFounders),
+            Some(&100_000)
+        );
+        assert!(!config.accelerate(UnvestingType::TeamFounders, Acceleration::Full));
+
+        assert!(config.accelerate(UnvestingType::AdvisersPartners, Acceleration::Full));
+        assert_eq!(
+            config
+                .accelerated_per_kind
+                .get(&UnvestingType::AdvisersPartners),
+            Some(&100_000)
+        );
+        assert!(!config
+            .accelerated_per_kind
+            .contains_key(&UnvestingType::PrivateSells));
+    }
 }
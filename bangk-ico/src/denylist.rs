@@ -0,0 +1,98 @@
+// File: bangk-ico/src/denylist.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 13:21:40
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+use bangk_macro::pda;
+use bangk_onchain_common::pda::{BangkPda, PdaType};
+use borsh::BorshDeserialize;
+use solana_program::pubkey::Pubkey;
+
+/// Get the shard of the denylist in which a wallet is stored.
+///
+/// The denylist is split in 256 shards, based on the first byte of the wallets' keys.
+///
+/// # Parameters
+/// * `wallet` - The wallet to look for.
+#[must_use]
+pub const fn get_denylist_shard(wallet: &Pubkey) -> u8 {
+    let [shard, ..] = wallet.to_bytes();
+    shard
+}
+
+/// PDA storing one shard of the sanctions denylist.
+///
+/// It is managed by the compliance `MultiSig`.
+#[pda(kind = PdaType::Denylist, seed = "Denylist", seed = shard)]
+pub struct DenylistPda {
+    /// Index of the shard (first byte of the keys of its wallets).
+    pub shard: u8,
+    /// Wallets on the denylist (sorted).
+    pub wallets: Vec<Pubkey>,
+}
+
+impl<'a> DenylistPda<'a> {
+    /// Create a new empty shard of the denylist.
+    ///
+    /// # Parameters
+    /// * `bump` - Bump used to derive the PDA address,
+    /// * `shard` - Index of the shard.
+    #[must_use]
+    pub const fn new(bump: u8, shard: u8) -> Self {
+        Self {
+            pda_type: Self::PDA_TYPE,
+            bump,
+            account: None,
+            shard,
+            wallets: Vec::new(),
+        }
+    }
+
+    /// Checks if a wallet is on the denylist.
+    ///
+    /// # Parameters
+    /// * `wallet` - The wallet to look for.
+    #[must_use]
+    pub fn contains(&self, wallet: &Pubkey) -> bool {
+        self.wallets.binary_search(wallet).is_ok()
+    }
+
+    /// Add a wallet to the denylist.
+    ///
+    /// # Parameters
+    /// * `wallet` - The wallet to add.
+    ///
+    /// # Returns
+    /// `false` if the wallet was already on the denylist.
+    pub fn add(&mut self, wallet: Pubkey) -> bool {
+        match self.wallets.binary_search(&wallet) {
+            Ok(_) => false,
+            Err(index) => {
+                self.wallets.insert(index, wallet);
+                true
+            }
+        }
+    }
+
+    /// Remove a wallet from the denylist.
+    ///
+    /// # Parameters
+    /// * `wallet` - The wallet to remove.
+    ///
+    /// # Returns
+    /// `false` if the wallet was not on the denylist.
+    pub fn remove(&mut self, wallet: &Pubkey) -> bool {
+        match self.wallets.binary_search(wallet) {
+            Ok(index) => {
+                self.wallets.remove(index);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,383 @@
+// File: bangk-ico/src/history.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 16:32:57
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+use bangk_macro::pda;
+use bangk_onchain_common::pda::{BangkPda, PdaType};
+use borsh::{BorshDeserialize, BorshSerialize};
+use chrono::{DateTime, Datelike as _};
+use serde::{Deserialize, Serialize};
+use shank::ShankType;
+use solana_program::pubkey::Pubkey;
+
+use crate::unvesting::UnvestingType;
+
+/// Maximum number of releases kept in a user's release history (the oldest ones are dropped).
+///
+/// Holds more than a year of weekly releases, with some room for the claims made in between.
+pub const MAX_RELEASE_HISTORY: usize = 64;
+
+/// Tag preceding the serialized [`ReleaseEvent`] in the program's data logs.
+pub const RELEASE_EVENT_TAG: &[u8] = b"BangkRelease";
+
+/// Tokens released from one of the user's investments.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub struct ReleasedEntry {
+    /// Position of the investment in the user's investments at the time of the release
+    /// (0 for a vesting position).
+    pub index: u32,
+    /// Type of the investment.
+    pub kind: UnvestingType,
+    /// Number of tokens released from the investment.
+    pub amount: u64,
+}
+
+/// Record of a release of a user's tokens.
+#[derive(
+    BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq, ShankType, Serialize, Deserialize,
+)]
+pub struct ReleaseRecord {
+    /// Timestamp of the release.
+    pub timestamp: i64,
+    /// Number of tokens released.
+    pub amount: u64,
+    /// Investments the tokens were released from.
+    pub entries: Vec<ReleasedEntry>,
+    /// Mint of the vesting position the tokens were released from (`None` for the user's own
+    /// investments).
+    pub position: Option<Pubkey>,
+}
+
+impl ReleaseRecord {
+    /// Create the record of a release.
+    ///
+    /// # Parameters
+    /// * `timestamp` - Timestamp of the release,
+    /// * `entries` - Investments the tokens were released from.
+    #[must_use]
+    pub fn new(timestamp: i64, entries: Vec<ReleasedEntry>) -> Self {
+        Self {
+            timestamp,
+            amount: entries
+                .iter()
+                .fold(0_u64, |total, entry| total.saturating_add(entry.amount)),
+            entries,
+            position: None,
+        }
+    }
+
+    /// Create the record of a release of the tokens of a vesting position.
+    ///
+    /// # Parameters
+    /// * `timestamp` - Timestamp of the release,
+    /// * `mint` - Mint of the NFT representing the position,
+    /// * `kind` - Type of the tokenised investment,
+    /// * `amount` - Number of tokens released.
+    #[must_use]
+    pub fn from_position(timestamp: i64, mint: Pubkey, kind: UnvestingType, amount: u64) -> Self {
+        Self {
+            timestamp,
+            amount,
+            entries: vec![ReleasedEntry {
+                index: 0,
+                kind,
+                amount,
+            }],
+            position: Some(mint),
+        }
+    }
+
+    /// Get the (UTC) year of the release, if the timestamp is valid.
+    #[must_use]
+    pub fn year(&self) -> Option<i32> {
+        get_year(self.timestamp)
+    }
+}
+
+/// Get the (UTC) year of a timestamp, if it is valid.
+fn get_year(timestamp: i64) -> Option<i32> {
+    DateTime::from_timestamp(timestamp, 0).map(|date| date.year())
+}
+
+/// Event logged (as program data, after [`RELEASE_EVENT_TAG`]) for each release.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseEvent {
+    /// Wallet of the user.
+    pub user: Pubkey,
+    /// Wallet that received the tokens (the user or their beneficiary).
+    pub destination: Pubkey,
+    /// Details of the release.
+    pub record: ReleaseRecord,
+}
+
+/// PDA keeping the latest releases of a user's tokens (up to [`MAX_RELEASE_HISTORY`]).
+///
+/// It is optional: the releases of users that don't have one are only logged as events.
+#[pda(kind = PdaType::ReleaseHistory, seed = "History", seed = user)]
+pub struct ReleaseHistoryPda {
+    /// Wallet of the user.
+    pub user: Pubkey,
+    /// Latest releases, from the oldest to the most recent.
+    pub records: Vec<ReleaseRecord>,
+    /// Timestamp of the most recent release dropped from the history (0 if none was).
+    pub last_dropped: i64,
+    /// Wallet that paid for the account's creation (refunded when the account is closed).
+    pub payer: Pubkey,
+}
+
+impl<'a> ReleaseHistoryPda<'a> {
+    /// Create an empty release history.
+    ///
+    /// # Parameters
+    /// * `bump` - Bump used to derive the PDA address,
+    /// * `user` - Wallet of the user,
+    /// * `payer` - Wallet paying for the account's creation.
+    #[must_use]
+    pub const fn new(bump: u8, user: Pubkey, payer: Pubkey) -> Self {
+        Self {
+            pda_type: Self::PDA_TYPE,
+            bump,
+            account: None,
+            user,
+            records: Vec::new(),
+            last_dropped: 0,
+            payer,
+        }
+    }
+
+    /// Add a release to the history, dropping the oldest one if it is full.
+    ///
+    /// # Parameters
+    /// * `record` - The release to add.
+    pub fn record(&mut self, record: ReleaseRecord) {
+        if self.records.len() >= MAX_RELEASE_HISTORY {
+            let dropped = self.records.remove(0);
+            self.last_dropped = self.last_dropped.max(dropped.timestamp);
+        }
+        self.records.push(record);
+    }
+
+    /// Add the releases of another history (after a reassignment of the user's investments),
+    /// keeping only the most recent ones if there are too many.
+    ///
+    /// # Parameters
+    /// * `records` - The releases to add,
+    /// * `last_dropped` - Timestamp of the most recent release dropped from the other history.
+    pub fn merge(&mut self, records: &[ReleaseRecord], last_dropped: i64) {
+        self.last_dropped = self.last_dropped.max(last_dropped);
+        self.records.extend_from_slice(records);
+        self.records.sort_by_key(|record| record.timestamp);
+        let excess = self.records.len().saturating_sub(MAX_RELEASE_HISTORY);
+        for dropped in self.records.drain(..excess) {
+            self.last_dropped = self.last_dropped.max(dropped.timestamp);
+        }
+    }
+
+    /// Get the statement of the user's releases for a given year.
+    ///
+    /// It is marked as truncated if some releases of the year were dropped from the history.
+    ///
+    /// # Parameters
+    /// * `year` - Year of the statement.
+    #[must_use]
+    pub fn yearly_statement(&self, year: i32) -> YearlyStatement {
+        let mut statement = YearlyStatement::new(&self.records, year);
+        statement.truncated =
+            self.last_dropped > 0 && get_year(self.last_dropped).is_some_and(|last| last >= year);
+        statement
+    }
+}
+
+/// Statement of the tokens released to a user during a (UTC) year, for tax purposes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct YearlyStatement {
+    /// Year of the statement.
+    pub year: i32,
+    /// Total number of tokens released during the year.
+    pub total: u64,
+    /// Number of tokens released for each type of investment, in order of appearance.
+    pub by_kind: Vec<(UnvestingType, u64)>,
+    /// Releases made during the year, in chronological order.
+    pub releases: Vec<ReleaseRecord>,
+    /// Set if some releases of the year may be missing (dropped from the release history): the
+    /// statement must then be built from the logged events.
+    pub truncated: bool,
+}
+
+impl YearlyStatement {
+    /// Build the statement of a year from a release history (from the PDA or the logged events).
+    ///
+    /// # Parameters
+    /// * `records` - Releases of the user, in any order,
+    /// * `year` - Year of the statement.
+    #[must_use]
+    pub fn new(records: &[ReleaseRecord], year: i32) -> Self {
+        let mut releases = records
+            .iter()
+            .filter(|record| record.year() == Some(year))
+            .cloned()
+            .collect::<Vec<_>>();
+        releases.sort_by_key(|record| record.timestamp);
+
+        let mut by_kind: Vec<(UnvestingType, u64)> = Vec::new();
+        for entry in releases.iter().flat_map(|record| &record.entries) {
+            match by_kind
+                .iter_mut()
+                .find(|(kind, _amount)| *kind == entry.kind)
+            {
+                Some((_kind, amount)) => *amount = amount.saturating_add(entry.amount),
+                None => by_kind.push((entry.kind, entry.amount)),
+            }
+        }
+
+        Self {
+            year,
+            total: releases
+                .iter()
+                .fold(0_u64, |total, record| total.saturating_add(record.amount)),
+            by_kind,
+            releases,
+            truncated: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2025-06-01 and 2026-01-01 (UTC)
+    const JUNE_2025: i64 = 1_748_736_000;
+    const JANUARY_2026: i64 = 1_767_225_600;
+
+    fn entry(index: u32, kind: UnvestingType, amount: u64) -> ReleasedEntry {
+        ReleasedEntry {
+            index,
+            kind,
+            amount,
+        }
+    }
+
+    #[test]
+    fn ring_buffer() {
+        let mut history = ReleaseHistoryPda::new(0, Pubkey::new_unique(), Pubkey::new_unique());
+        for timestamp in 0..i64::try_from(MAX_RELEASE_HISTORY + 2).unwrap() {
+            history.record(ReleaseRecord::new(timestamp, Vec::new()));
+        }
+        assert_eq!(history.records.len(), MAX_RELEASE_HISTORY);
+        assert_eq!(
+            history.records.first().map(|record| record.timestamp),
+            Some(2)
+        );
+        assert_eq!(
+            history.records.last().map(|record| record.timestamp),
+            Some(i64::try_from(MAX_RELEASE_HISTORY + 1).unwrap())
+        );
+        assert_eq!(history.last_dropped, 1);
+    }
+
+    #[test]
+    fn merged_histories() {
+        let mut history = ReleaseHistoryPda::new(0, Pubkey::new_unique(), Pubkey::new_unique());
+        let mut other = Vec::new();
+        for timestamp in 0..i64::try_from(MAX_RELEASE_HISTORY).unwrap() {
+            if timestamp % 2 == 0 {
+                history.record(ReleaseRecord::new(timestamp, Vec::new()));
+            } else {
+                other.push(ReleaseRecord::new(timestamp, Vec::new()));
+            }
+        }
+        other.push(ReleaseRecord::new(
+            i64::try_from(MAX_RELEASE_HISTORY).unwrap(),
+            Vec::new(),
+        ));
+
+        history.merge(&other, 0);
+        assert_eq!(history.records.len(), MAX_RELEASE_HISTORY);
+        assert_eq!(history.last_dropped, 0);
+        assert!(history
+            .records
+            .iter()
+            .zip(1_i64..)
+            .all(|(record, timestamp)| record.timestamp == timestamp));
+    }
+
+    #[test]
+    fn yearly_statement() {
+        let records = vec![
+            ReleaseRecord::new(
+                JANUARY_2026,
+                vec![entry(0, UnvestingType::PrivateSells, 50)],
+            ),
+            ReleaseRecord::new(
+                JUNE_2025 + 86_400,
+                vec![
+                    entry(0, UnvestingType::PrivateSells, 100),
+                    entry(1, UnvestingType::TeamFounders, 20),
+                ],
+            ),
+            ReleaseRecord::new(JUNE_2025, vec![entry(1, UnvestingType::TeamFounders, 30)]),
+        ];
+        assert_eq!(records[1].amount, 120);
+        assert_eq!(records[0].year(), Some(2026));
+
+        let statement = YearlyStatement::new(&records, 2025);
+        assert_eq!(statement.total, 150);
+        assert_eq!(
+            statement.by_kind,
+            vec![
+                (UnvestingType::TeamFounders, 50),
+                (UnvestingType::PrivateSells, 100)
+            ]
+        );
+        assert_eq!(
+            statement
+                .releases
+                .iter()
+                .map(|record| record.timestamp)
+                .collect::<Vec<_>>(),
+            vec![JUNE_2025, JUNE_2025 + 86_400]
+        );
+
+        let empty = YearlyStatement::new(&records, 2024);
+        assert_eq!(empty.total, 0);
+        assert!(empty.by_kind.is_empty() && empty.releases.is_empty());
+    }
+
+    #[test]
+    fn truncated_statement() {
+        let mut history = ReleaseHistoryPda::new(0, Pubkey::new_unique(), Pubkey::new_unique());
+        for week in 0..=i64::try_from(MAX_RELEASE_HISTORY).unwrap() {
+            history.record(ReleaseRecord::new(
+                JUNE_2025 + week * 7 * 86_400,
+                vec![entry(0, UnvestingType::PrivateSells, 10)],
+            ));
+        }
+
+        // The first release of 2025 was dropped, but all those of 2026 are kept
+        let statement = history.yearly_statement(2025);
+        assert!(statement.truncated);
+        assert_eq!(
+            statement.releases.first().map(|record| record.timestamp),
+            Some(JUNE_2025 + 7 * 86_400)
+        );
+        assert!(!history.yearly_statement(2026).truncated);
+    }
+}
@@ -3,7 +3,7 @@
 // Creation date: Sunday 09 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Thursday 22 August 2024 @ 12:20:23
+// Last modified: Sunday 18 October 2026 @ 16:32:57
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -13,7 +13,15 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use shank::ShankInstruction;
 use solana_program::pubkey::Pubkey;
 use solana_program::{
+    address_lookup_table::{
+        instruction::{create_lookup_table, extend_lookup_table},
+        state::LOOKUP_TABLE_MAX_ADDRESSES,
+        AddressLookupTableAccount,
+    },
+    clock::Slot,
+    hash::Hash,
     instruction::{AccountMeta, Instruction},
+    message::{v0, VersionedMessage},
     program_error::ProgramError,
     system_program,
 };
@@ -22,8 +30,15 @@ use spl_associated_token_account::get_associated_token_address_with_program_id;
 use crate::timelock::TimelockPda;
 use crate::WalletType;
 use crate::{
+    airdrop::{get_airdrop_vault_pda, AirdropClaimsPda, AirdropPda},
     config::ConfigurationPda,
-    investment::UserInvestmentPda,
+    denylist::{get_denylist_shard, DenylistPda},
+    history::ReleaseHistoryPda,
+    investment::{Acceleration, InvestmentCap, LeaverPolicy, PaymentMetadata, UserInvestmentPda},
+    kyc::KycAttestationPda,
+    position::{get_position_mint_pda, PositionTransferPolicy, VestingPositionPda},
+    referral::{ReferralProgram, ReferrerStatsPda},
+    sale::{get_sale_treasury_pda, BonusRule, SalePhase, UserPurchasesPda},
     unvesting::{UnvestingScheme, UnvestingType},
 };
 
@@ -75,6 +90,14 @@ pub struct UserInvestmentArgs {
     pub custom_rule: Option<UnvestingScheme>,
     /// Amount of tokens
     pub amount: u64,
+    /// Referrer of the user, rewarded by the referral program (if any).
+    pub referrer: Option<Pubkey>,
+    /// Idempotency key (external payment reference for example), a second investment with the same key is rejected.
+    pub reference: Option<[u8; 32]>,
+    /// Details of the payment of the investment (if known).
+    pub payment: Option<PaymentMetadata>,
+    /// If set, the vesting can be revoked by a leaver event (team & founders investments only).
+    pub revocable: bool,
 }
 
 /// Arguments to delete a user's investment.
@@ -88,6 +111,27 @@ pub struct CancelInvestmentArgs {
     pub amount: u64,
 }
 
+/// Selection of a single investment of a user.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvestmentSelector {
+    /// Position of the investment in the user's investments
+    Index(u32),
+    /// Idempotency key given when the investment was recorded (or reference of the purchase
+    /// that created it)
+    Reference([u8; 32]),
+}
+
+/// Arguments to cancel a specific investment of a user.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct CancelInvestmentEntryArgs {
+    /// User owning the investment
+    pub user: Pubkey,
+    /// Investment to cancel
+    pub target: InvestmentSelector,
+    /// Amount to cancel (the whole investment if `None`)
+    pub amount: Option<u64>,
+}
+
 /// Arguments to set the BGK launch date.
 #[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
 pub struct LaunchBGKArgs {
@@ -115,6 +159,303 @@ pub struct ExecuteTransferFromInternalWalletArgs {
     pub amount: u64,
 }
 
+/// Arguments to claw back the unvested tokens of a user.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct ClawbackArgs {
+    /// User owning the investments
+    pub user: Pubkey,
+    /// Type of investments to claw back (all of them if `None`)
+    pub kind: Option<UnvestingType>,
+    /// Date as of which the vesting is stopped
+    pub date: i64,
+    /// Internal wallet receiving the clawed back tokens
+    pub target: WalletType,
+}
+
+/// Arguments to migrate the investments of a user to the current layout.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct MigrateUserInvestmentArgs {
+    /// User owning the investments
+    pub user: Pubkey,
+}
+
+/// Arguments to define the phases of the token sale.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct SetSalePhasesArgs {
+    /// Definition of the sale phases (replaces the current ones)
+    pub phases: Vec<SalePhase>,
+}
+
+/// Arguments to purchase BGK tokens during a sale phase.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct PurchaseArgs {
+    /// Type of investment bought (must have a running sale phase)
+    pub kind: UnvestingType,
+    /// Number of tokens bought
+    pub amount: u64,
+}
+
+/// Arguments to define the keys of the compliance `MultiSig`.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct SetComplianceMultisigArgs {
+    /// Keys allowed to issue or revoke KYC attestations (replaces the current ones)
+    pub keys: Vec<Pubkey>,
+}
+
+/// Arguments to issue a user's KYC attestation.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct IssueKycAttestationArgs {
+    /// User who passed the KYC procedure
+    pub user: Pubkey,
+    /// Level of verification reached by the user (must not be zero)
+    pub level: u8,
+    /// Timestamp at which the attestation expires
+    pub expiry: i64,
+    /// Jurisdiction of the user (ISO 3166-1 alpha-2 country code)
+    pub jurisdiction: [u8; 2],
+}
+
+/// Arguments to revoke a user's KYC attestation.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct RevokeKycAttestationArgs {
+    /// User whose attestation is revoked
+    pub user: Pubkey,
+}
+
+/// Arguments to add a wallet to, or remove it from, the sanctions denylist.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct DenylistArgs {
+    /// Wallet concerned
+    pub wallet: Pubkey,
+}
+
+/// Arguments to create a Merkle airdrop.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct CreateAirdropArgs {
+    /// Identifier of the airdrop
+    pub id: u8,
+    /// Internal wallet funding the airdrop
+    pub source: WalletType,
+    /// Root of the Merkle tree of the claims
+    pub root: [u8; 32],
+    /// Total number of tokens that can be claimed (transferred from the source wallet)
+    pub total: u64,
+    /// Number of recipients (leaves of the Merkle tree)
+    pub recipients: u32,
+    /// Timestamp after which the airdrop can no longer be claimed
+    pub deadline: i64,
+    /// Unvesting scheme applied to the claimed tokens (if any)
+    pub unvesting: Option<UnvestingScheme>,
+}
+
+/// Arguments to claim tokens from a Merkle airdrop.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct ClaimAirdropArgs {
+    /// Identifier of the airdrop
+    pub id: u8,
+    /// Index of the claimant in the Merkle tree
+    pub index: u32,
+    /// Number of tokens claimed
+    pub amount: u64,
+    /// Sibling nodes from the claimant's leaf to the root of the Merkle tree
+    pub proof: Vec<[u8; 32]>,
+}
+
+/// Arguments to return the unclaimed tokens of an airdrop to its source wallet.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct ReclaimAirdropArgs {
+    /// Identifier of the airdrop
+    pub id: u8,
+}
+
+/// Arguments to define the referral program.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct SetReferralProgramArgs {
+    /// Definition of the referral program (`None` to stop it)
+    pub program: Option<ReferralProgram>,
+}
+
+/// Arguments to define the bonus granted to the investors.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct SetBonusRulesArgs {
+    /// Definition of the bonus rules (replaces the current ones)
+    pub rules: Vec<BonusRule>,
+}
+
+/// Arguments to close the record of a user's fully released investments.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct CloseUserInvestmentArgs {
+    /// User owning the investments
+    pub user: Pubkey,
+}
+
+/// Arguments to reassign the investments of a user to a new wallet.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct ReassignInvestmentArgs {
+    /// Current owner of the investments
+    pub from: Pubkey,
+    /// New owner of the investments
+    pub to: Pubkey,
+}
+
+/// Arguments for a user to claim their vested tokens.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct ClaimVestedArgs {
+    /// Maximum number of tokens to claim (everything available if `None`)
+    pub max_amount: Option<u64>,
+}
+
+/// Arguments to define the wallet receiving a user's released tokens.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct SetBeneficiaryArgs {
+    /// Wallet receiving the released tokens (the user's own wallet if `None`)
+    pub beneficiary: Option<Pubkey>,
+}
+
+/// Arguments to apply the departure of a team member to their revocable investments.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct LeaverEventArgs {
+    /// User leaving
+    pub user: Pubkey,
+    /// Date of departure
+    pub date: i64,
+    /// What the user keeps of their revocable investments
+    pub policy: LeaverPolicy,
+}
+
+/// Arguments to put the releases of a user's tokens on hold.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct SetComplianceHoldArgs {
+    /// User whose releases are put on hold
+    pub user: Pubkey,
+    /// Reason of the hold (code defined by the compliance team)
+    pub reason: u16,
+    /// Timestamp at which the hold expires
+    pub expiry: i64,
+}
+
+/// Arguments to lift the hold on the releases of a user's tokens.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct ClearComplianceHoldArgs {
+    /// User whose releases were on hold
+    pub user: Pubkey,
+}
+
+/// Arguments to accelerate the vesting of investments.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct AccelerateVestingArgs {
+    /// Type of investments concerned (all of them if `None`)
+    pub kind: Option<UnvestingType>,
+    /// Acceleration applied
+    pub acceleration: Acceleration,
+}
+
+/// Arguments to represent one of a user's investments by an NFT.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct TokenizePositionArgs {
+    /// Position of the investment in the user's investments
+    pub index: u32,
+    /// Identifier of the vesting position among the user's positions
+    pub id: u8,
+}
+
+/// Arguments to define the restrictions on the transfers of the vesting positions.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug)]
+pub struct SetPositionTransferPolicyArgs {
+    /// Definition of the restrictions (`None` to disable the transfers)
+    pub policy: Option<PositionTransferPolicy>,
+}
+
+/// Arguments to define the caps on the investments.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct SetInvestmentCapsArgs {
+    /// Definition of the caps (replaces the current ones)
+    pub caps: Vec<InvestmentCap>,
+}
+
+/// Arguments to convert the configuration stored with the layout of the v1.0.0 of the program.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct MigrateConfigurationArgs {
+    /// Number of tokens invested in each type of investment (not recorded by the v1.0.0), adding
+    /// up to the amount invested
+    pub invested_per_kind: Vec<(UnvestingType, u64)>,
+}
+
+/// Arguments of a batched vesting release.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug)]
+pub struct BatchVestingReleaseArgs {
+    /// For each user, whether the denylist shard of their beneficiary follows their accounts
+    /// (only if they defined a beneficiary whose shard isn't their own).
+    pub beneficiary_shards: Vec<bool>,
+}
+
+/// Number of accounts needed for each user of a batched vesting release (plus the denylist shard
+/// of their beneficiary, if needed).
+pub const BATCH_RELEASE_ACCOUNTS_PER_USER: usize = 6;
+
+/// Maximum number of users in a single batched vesting release sent as a legacy transaction.
+///
+/// Keeps the transaction (with a compute budget instruction) under Solana's size limit, even if
+/// all the users defined a beneficiary.
+pub const MAX_BATCH_RELEASE_USERS: usize = 3;
+
+/// Maximum number of users in a single batched vesting release sent as a v0 transaction using an
+/// address lookup table (see [`batch_vesting_release_messages`]).
+///
+/// Keeps the transaction (with a compute budget instruction) under the limit of 64 accounts.
+pub const MAX_BATCH_RELEASE_USERS_WITH_LOOKUP_TABLE: usize = 7;
+
+/// Maximum number of users in a single acceleration of the vesting.
+pub const MAX_ACCELERATION_USERS: usize = 16;
+
+/// Maximum number of users in a single compaction of the investments.
+pub const MAX_COMPACTION_USERS: usize = 16;
+
+/// A user whose tokens could not be released during a batched vesting release.
+///
+/// The list of skipped users is set as the instruction's return data.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SkippedRelease {
+    /// Wallet of the user.
+    pub user: Pubkey,
+    /// Code of the `ProgramError` that prevented the release.
+    pub reason: u64,
+}
+
+/// Change made by the cancellation of a specific investment.
+///
+/// The record is set as the instruction's return data and logged.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CancelledInvestment {
+    /// Wallet of the user.
+    pub user: Pubkey,
+    /// Position of the investment in the user's investments before the cancellation.
+    pub index: u32,
+    /// Type of the investment.
+    pub kind: UnvestingType,
+    /// Number of tokens of the investment before the cancellation.
+    pub amount_before: u64,
+    /// Number of tokens cancelled.
+    pub cancelled: u64,
+    /// Set if the investment was removed (fully cancelled).
+    pub removed: bool,
+}
+
+/// Summary of a user's investments, kept when their record is closed once fully released.
+///
+/// The record is set as the instruction's return data and logged.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompletedInvestment {
+    /// Wallet of the user.
+    pub user: Pubkey,
+    /// Number of investments of the user.
+    pub investments: u32,
+    /// Total number of tokens released to the user.
+    pub amount: u64,
+    /// Timestamp of the closing of the record.
+    pub closed_at: i64,
+}
+
 /// Global payload for Bangk program.
 #[derive(BorshSerialize, BorshDeserialize, Clone, Debug, ShankInstruction)]
 #[rustfmt::skip]
@@ -161,6 +502,9 @@ pub enum BangkIcoInstruction {
     #[account(2, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
     #[account(3, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
     #[account(4, name="system_program", desc="System Program")]
+    #[account(5, name="kyc_attestation", desc="The PDA attesting that the user passed the KYC procedure")]
+    #[account(6, optional, writable, name="referrer_stats", desc="The PDA in which the statistics of the user's referrer are stored (only with a referrer)")]
+    #[account(7, optional, writable, name="referrer_investment", desc="The PDA in which the details of the referrer's investment are stored (only with a referrer)")]
     UserInvestment(UserInvestmentArgs),
 
     /// Queue a post launch investment
@@ -180,6 +524,7 @@ pub enum BangkIcoInstruction {
     #[account(3, writable, name="timelock", desc="This PDA will hold timelocked instructions to transfer tokens from the reserve")]
     #[account(4, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
     #[account(5, name="system_program", desc="System Program")]
+    #[account(6, name="kyc_attestation", desc="The PDA attesting that the user passed the KYC procedure")]
     ProcessPostLaunchAdvisersInvestment(UserInvestmentArgs),
 
     /// Cancel a user's investment.
@@ -189,6 +534,9 @@ pub enum BangkIcoInstruction {
     #[account(3, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
     #[account(4, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
     #[account(5, name="system_program", desc="System Program")]
+    #[account(6, optional, writable, name="user_purchases", desc="The PDA in which the user's on-chain purchases are stored (if any)")]
+    #[account(7, optional, writable, name="referrer_stats", desc="The PDA in which the statistics of the user's referrer are stored (only with a referrer)")]
+    #[account(8, optional, writable, name="referrer_investment", desc="The PDA in which the details of the referrer's investment are stored (only with a referrer)")]
     CancelInvestment(CancelInvestmentArgs),
 
     /// Set the BGK token launch date.
@@ -208,10 +556,14 @@ pub enum BangkIcoInstruction {
     #[account(4, writable, name="invested_pda", desc="Bangk BGK wallet storing the tokens that will be gradually released to the users")]
     #[account(5, name="user", desc="Wallet of the user for whom the tokens will be released")]
     #[account(6, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
-    #[account(7, writable, name="user_ata", desc="BGK ATA for the user")]
+    #[account(7, writable, name="user_ata", desc="BGK ATA receiving the tokens (the beneficiary's one if the user defined one)")]
     #[account(8, name="system_program", desc="System Program")]
     #[account(9, name="token_program", desc="SPL Token 2022 Program")]
     #[account(10, name="ata_program", desc="Associated Token Account Program")]
+    #[account(11, name="kyc_attestation", desc="The PDA attesting that the user passed the KYC procedure")]
+    #[account(12, name="denylist", desc="The shard of the sanctions denylist in which the user would be stored")]
+    #[account(13, name="beneficiary_denylist", desc="The shard of the sanctions denylist in which the beneficiary would be stored (ignored if the user has none)")]
+    #[account(14, writable, name="release_history", desc="The PDA keeping the history of the user's releases (ignored if the user has none)")]
     VestingRelease,
 
     /// Queues a transfer request from Bangk's reserve ATA.
@@ -234,207 +586,2801 @@ pub enum BangkIcoInstruction {
     #[account(7, name="system_program", desc="System Program")]
     #[account(8, name="token_program", desc="SPL Token 2022 Program")]
     #[account(9, name="ata_program", desc="Associated Token Account Program")]
+    #[account(10, name="denylist", desc="The shard of the sanctions denylist in which the owner of the target ATA would be stored")]
     ExecuteTransferFromInternalWallet(ExecuteTransferFromInternalWalletArgs),
-}
 
-/// Initializes the ICO program's configuration.
-///
-/// # Parameters
-/// * `payer` - Signer & Payer account,
-/// * `unvesting` - Definition of the unvesting scheme,
-/// * `api_key` - Key that will initially be used for routine tasks,
-/// * `admin1` - First key for the admin `MultiSig`
-/// * `admin2` - Second key for the admin `MultiSig`
-/// * `admin3` - Third key for the admin `MultiSig`
-/// * `admin4` - Fourth key for the admin `MultiSig`
-///
-/// # Errors
-/// If instruction's data could not be serialized (so…never?)
-pub fn initialize(
-    payer: &Pubkey,
-    api_key: &Pubkey,
-    admin1: &Pubkey,
-    admin2: &Pubkey,
-    admin3: &Pubkey,
-    admin4: &Pubkey,
-) -> Result<Instruction, ProgramError> {
-    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
-    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
-    let (transfer_timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
+    /// Release tokens (if possible) on the investor's own request.
+    ///
+    /// No admin key signs this instruction: the admin `MultiSig` is only the authority of the transfer.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction (can be the user)")]
+    #[account(1, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(2, name="admin_pda", desc="The PDA of the admin MultiSig, authority of the transfer from the invested wallet")]
+    #[account(3, name="bgk_mint", desc="Mint of the BGK token")]
+    #[account(4, writable, name="invested_pda", desc="Bangk BGK wallet storing the tokens that will be gradually released to the users")]
+    #[account(5, signer, name="user", desc="Wallet of the user claiming their tokens")]
+    #[account(6, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
+    #[account(7, writable, name="user_ata", desc="BGK ATA receiving the tokens (the beneficiary's one if the user defined one)")]
+    #[account(8, name="system_program", desc="System Program")]
+    #[account(9, name="token_program", desc="SPL Token 2022 Program")]
+    #[account(10, name="ata_program", desc="Associated Token Account Program")]
+    #[account(11, name="kyc_attestation", desc="The PDA attesting that the user passed the KYC procedure")]
+    #[account(12, name="denylist", desc="The shard of the sanctions denylist in which the user would be stored")]
+    #[account(13, name="beneficiary_denylist", desc="The shard of the sanctions denylist in which the beneficiary would be stored (ignored if the user has none)")]
+    #[account(14, writable, name="release_history", desc="The PDA keeping the history of the user's releases (ignored if the user has none)")]
+    ClaimVested(ClaimVestedArgs),
 
-    let args = InitializeArgs {
-        api_key: *api_key,
-        admin1: *admin1,
-        admin2: *admin2,
-        admin3: *admin3,
-        admin4: *admin4,
-    };
-    Ok(Instruction {
-        program_id: crate::ID,
-        accounts: vec![
-            AccountMeta::new(*payer, true),
-            AccountMeta::new(config_pda, false),
-            AccountMeta::new(admin_keys_pda, false),
-            AccountMeta::new(transfer_timelock_pda, false),
-            AccountMeta::new_readonly(system_program::ID, false),
-        ],
-        data: borsh::to_vec(&BangkIcoInstruction::Initialize(args))?,
-    })
-}
+    /// Release tokens (if possible) for several users at once.
+    ///
+    /// The fixed accounts are followed by the `user`, `user_investment`, `user_ata`,
+    /// `kyc_attestation`, `denylist` and `release_history` accounts of each user (`user_ata` being
+    /// the beneficiary's one if the user defined one), then by the `beneficiary_denylist` shard
+    /// if the arguments say so (otherwise, the beneficiary is looked for in the user's shard).
+    /// Users that could not be processed are skipped and reported in the return data.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction")]
+    #[account(1, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(2, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(3, name="bgk_mint", desc="Mint of the BGK token")]
+    #[account(4, writable, name="invested_pda", desc="Bangk BGK wallet storing the tokens that will be gradually released to the users")]
+    #[account(5, name="system_program", desc="System Program")]
+    #[account(6, name="token_program", desc="SPL Token 2022 Program")]
+    #[account(7, name="ata_program", desc="Associated Token Account Program")]
+    BatchVestingRelease(BatchVestingReleaseArgs),
 
-/// Create the instruction for the creation of the BGK mint and the initial mint of the tokens.
-///
-/// # Parameters
-/// * `admin1` - Key of the payer and first signer of the instruction,
-/// * `admin2` - Key of the second signer of the instruction,
-/// * `admin2` - Key of the third signer of the instruction,
-///
-/// # Errors
-/// If instruction's data could not be serialized (so…never?)
-pub fn create_mint(
-    admin1: &Pubkey,
-    admin2: &Pubkey,
-    admin3: &Pubkey,
-) -> Result<Instruction, ProgramError> {
-    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
-    let (mint_address, mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    /// Queue the clawback of a user's unvested tokens.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, writable, name="timelock", desc="This PDA will hold timelocked instructions")]
+    #[account(6, name="system_program", desc="System Program")]
+    QueueClawback(ClawbackArgs),
 
-    Ok(Instruction {
-        program_id: crate::ID,
-        accounts: vec![
-            AccountMeta::new(*admin1, true),
-            AccountMeta::new_readonly(*admin2, true),
-            AccountMeta::new_readonly(*admin3, true),
-            AccountMeta::new_readonly(admin_keys_pda, false),
-            AccountMeta::new(mint_address, false),
-            AccountMeta::new(WalletType::Community.get_pda().0, false),
-            AccountMeta::new(WalletType::DeFiIncentives.get_pda().0, false),
-            AccountMeta::new(WalletType::Foundation.get_pda().0, false),
-            AccountMeta::new(WalletType::Ico.get_pda().0, false),
-            AccountMeta::new(WalletType::Liquidity.get_pda().0, false),
-            AccountMeta::new(WalletType::Marketing.get_pda().0, false),
-            AccountMeta::new(WalletType::Partners.get_pda().0, false),
-            AccountMeta::new(WalletType::ResearchDevelopmentFund.get_pda().0, false),
-            AccountMeta::new(WalletType::Reserve.get_pda().0, false),
-            AccountMeta::new(WalletType::TeamsAdvisers.get_pda().0, false),
-            AccountMeta::new_readonly(system_program::ID, false),
-            AccountMeta::new_readonly(spl_token_2022::ID, false),
-        ],
-        data: borsh::to_vec(&BangkIcoInstruction::MintBGK(MintCreationArgs {
-            bump: mint_bump,
-        }))?,
+    /// Execute the clawback of a user's unvested tokens.
+    ///
+    /// The fixed accounts are followed by the `vesting_position` PDA of each outstanding vesting
+    /// position tokenised from the user's investments.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction")]
+    #[account(1, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(2, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(3, writable, name="timelock", desc="This PDA will hold timelocked instructions")]
+    #[account(4, name="bgk_mint", desc="Mint of the BGK token")]
+    #[account(5, writable, name="invested_pda", desc="Bangk BGK wallet storing the tokens that will be gradually released to the users")]
+    #[account(6, writable, name="target_pda", desc="Bangk BGK wallet receiving the clawed back tokens")]
+    #[account(7, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
+    #[account(8, name="system_program", desc="System Program")]
+    #[account(9, name="token_program", desc="SPL Token 2022 Program")]
+    ExecuteClawback(ClawbackArgs),
+
+    /// Convert the investments of a user stored with the layout of the v1.0.0 of the program.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction")]
+    #[account(1, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(2, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
+    #[account(3, name="system_program", desc="System Program")]
+    MigrateUserInvestment(MigrateUserInvestmentArgs),
+
+    /// Convert the configuration of the program stored with the layout of the v1.0.0 of the program.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction")]
+    #[account(1, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(2, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(3, name="system_program", desc="System Program")]
+    MigrateConfiguration(MigrateConfigurationArgs),
+
+    /// Define the phases of the token sale.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, name="system_program", desc="System Program")]
+    SetSalePhases(SetSalePhasesArgs),
+
+    /// Create the treasury receiving the payments of the on-chain purchases.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, name="payment_mint", desc="Mint of the currency accepted for the purchases")]
+    #[account(6, writable, name="treasury", desc="Token account receiving the payments")]
+    #[account(7, name="system_program", desc="System Program")]
+    #[account(8, name="token_program", desc="SPL Token or SPL Token 2022 Program (owner of the payment mint)")]
+    InitializeSaleTreasury,
+
+    /// Purchase BGK tokens at the price of the running sale phase.
+    #[account(0, signer, writable, name="buyer", desc="Buyer of the tokens and fee payer for the instruction")]
+    #[account(1, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(2, writable, name="user_investment", desc="The PDA in which the details of the buyer's investment are stored")]
+    #[account(3, name="payment_mint", desc="Mint of the currency accepted for the purchases")]
+    #[account(4, writable, name="buyer_payment_account", desc="Buyer's token account from which the payment is made")]
+    #[account(5, writable, name="treasury", desc="Token account receiving the payments")]
+    #[account(6, name="system_program", desc="System Program")]
+    #[account(7, name="token_program", desc="SPL Token or SPL Token 2022 Program (owner of the payment mint)")]
+    #[account(8, writable, name="user_purchases", desc="The PDA in which the buyer's on-chain purchases are stored")]
+    #[account(9, name="kyc_attestation", desc="The PDA attesting that the buyer passed the KYC procedure")]
+    Purchase(PurchaseArgs),
+
+    /// Abort the token sale, all the purchases can then be refunded.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, name="system_program", desc="System Program")]
+    AbortSale,
+
+    /// Refund the payments of a user's cancelled purchases from the sale treasury.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction (the user or an admin)")]
+    #[account(1, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(2, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(3, name="user", desc="User being refunded")]
+    #[account(4, writable, name="user_purchases", desc="The PDA in which the user's on-chain purchases are stored")]
+    #[account(5, name="payment_mint", desc="Mint of the currency accepted for the purchases")]
+    #[account(6, writable, name="user_payment_account", desc="User's ATA receiving the refund")]
+    #[account(7, writable, name="treasury", desc="Token account which received the payments")]
+    #[account(8, name="system_program", desc="System Program")]
+    #[account(9, name="token_program", desc="SPL Token or SPL Token 2022 Program (owner of the payment mint)")]
+    #[account(10, name="ata_program", desc="Associated Token Account Program")]
+    Refund,
+
+    /// Create or update the keys of the compliance `MultiSig`.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(4, writable, name="compliance_pda", desc="The PDA in which keys allowed to issue or revoke KYC attestations are stored")]
+    #[account(5, name="system_program", desc="System Program")]
+    SetComplianceMultisig(SetComplianceMultisigArgs),
+
+    /// Issue (or renew) a user's KYC attestation.
+    #[account(0, signer, writable, name="compliance", desc="Compliance key signing and paying for the instruction")]
+    #[account(1, name="compliance_pda", desc="The PDA in which keys allowed to issue or revoke KYC attestations are stored")]
+    #[account(2, writable, name="kyc_attestation", desc="The PDA attesting that the user passed the KYC procedure")]
+    #[account(3, name="system_program", desc="System Program")]
+    IssueKycAttestation(IssueKycAttestationArgs),
+
+    /// Revoke a user's KYC attestation.
+    #[account(0, signer, writable, name="compliance", desc="Compliance key signing the instruction and receiving the rent of the attestation")]
+    #[account(1, name="compliance_pda", desc="The PDA in which keys allowed to issue or revoke KYC attestations are stored")]
+    #[account(2, writable, name="kyc_attestation", desc="The PDA attesting that the user passed the KYC procedure")]
+    #[account(3, name="system_program", desc="System Program")]
+    RevokeKycAttestation(RevokeKycAttestationArgs),
+
+    /// Add a wallet to the sanctions denylist.
+    #[account(0, signer, writable, name="compliance", desc="Compliance key signing and paying for the instruction")]
+    #[account(1, name="compliance_pda", desc="The PDA in which keys allowed to manage the KYC attestations and the denylist are stored")]
+    #[account(2, writable, name="denylist", desc="The shard of the sanctions denylist in which the wallet is stored")]
+    #[account(3, name="system_program", desc="System Program")]
+    AddToDenylist(DenylistArgs),
+
+    /// Remove a wallet from the sanctions denylist.
+    #[account(0, signer, writable, name="compliance", desc="Compliance key signing and paying for the instruction")]
+    #[account(1, name="compliance_pda", desc="The PDA in which keys allowed to manage the KYC attestations and the denylist are stored")]
+    #[account(2, writable, name="denylist", desc="The shard of the sanctions denylist in which the wallet is stored")]
+    #[account(3, name="system_program", desc="System Program")]
+    RemoveFromDenylist(DenylistArgs),
+
+    /// Create a Merkle airdrop funded from an internal wallet.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(4, writable, name="airdrop", desc="The PDA in which the definition of the airdrop is stored")]
+    #[account(5, writable, name="airdrop_claims", desc="The PDA in which the claims made on the airdrop are stored")]
+    #[account(6, name="bgk_mint", desc="Mint of the BGK token")]
+    #[account(7, writable, name="source_pda", desc="Bangk's BGK wallet funding the airdrop")]
+    #[account(8, writable, name="vault", desc="Token account holding the tokens of the airdrop")]
+    #[account(9, name="system_program", desc="System Program")]
+    #[account(10, name="token_program", desc="SPL Token 2022 Program")]
+    CreateAirdrop(CreateAirdropArgs),
+
+    /// Claim tokens from a Merkle airdrop.
+    #[account(0, signer, writable, name="claimant", desc="Recipient of the airdrop and fee payer for the instruction")]
+    #[account(1, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(2, writable, name="airdrop", desc="The PDA in which the definition of the airdrop is stored")]
+    #[account(3, writable, name="airdrop_claims", desc="The PDA in which the claims made on the airdrop are stored")]
+    #[account(4, name="bgk_mint", desc="Mint of the BGK token")]
+    #[account(5, writable, name="vault", desc="Token account holding the tokens of the airdrop")]
+    #[account(6, writable, name="claimant_ata", desc="BGK ATA of the claimant (receiving the tokens if they are not vested)")]
+    #[account(7, writable, name="invested_pda", desc="Bangk BGK wallet storing the tokens that will be gradually released to the users")]
+    #[account(8, writable, name="user_investment", desc="The PDA in which the details of the claimant's investment are stored")]
+    #[account(9, name="system_program", desc="System Program")]
+    #[account(10, name="token_program", desc="SPL Token 2022 Program")]
+    #[account(11, name="ata_program", desc="Associated Token Account Program")]
+    #[account(12, name="denylist", desc="The shard of the sanctions denylist in which the claimant would be stored")]
+    #[account(13, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    ClaimAirdrop(ClaimAirdropArgs),
+
+    /// Return the unclaimed tokens of an airdrop to its source wallet after its deadline.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(3, writable, name="airdrop", desc="The PDA in which the definition of the airdrop is stored")]
+    #[account(4, name="bgk_mint", desc="Mint of the BGK token")]
+    #[account(5, writable, name="vault", desc="Token account holding the tokens of the airdrop")]
+    #[account(6, writable, name="source_pda", desc="Bangk's BGK wallet which funded the airdrop")]
+    #[account(7, name="token_program", desc="SPL Token 2022 Program")]
+    ReclaimAirdrop(ReclaimAirdropArgs),
+
+    /// Define (or stop) the program rewarding the referrers of the investors.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, name="system_program", desc="System Program")]
+    SetReferralProgram(SetReferralProgramArgs),
+
+    /// Transfer the referral rewards granted so far from their source wallet to the `Ico` wallet.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction")]
+    #[account(1, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(2, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(3, name="bgk_mint", desc="Mint of the BGK token")]
+    #[account(4, writable, name="source_pda", desc="Bangk's BGK wallet funding the referral rewards")]
+    #[account(5, writable, name="invested_pda", desc="Bangk BGK wallet storing the tokens that will be gradually released to the users")]
+    #[account(6, name="token_program", desc="SPL Token 2022 Program")]
+    FundReferralRewards,
+
+    /// Define the bonus granted to the investors depending on the sale phase or the amount invested.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, name="system_program", desc="System Program")]
+    SetBonusRules(SetBonusRulesArgs),
+
+    /// Define the caps on the investments, per type of investment and per user.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, name="system_program", desc="System Program")]
+    SetInvestmentCaps(SetInvestmentCapsArgs),
+
+    /// Cancel a specific investment of a user, entirely or partially.
+    #[account(0, signer, writable, name="admin1", desc="Signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(3, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(4, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
+    #[account(5, name="system_program", desc="System Program")]
+    #[account(6, optional, writable, name="user_purchases", desc="The PDA in which the user's on-chain purchases are stored (if any)")]
+    #[account(7, optional, writable, name="referrer_stats", desc="The PDA in which the statistics of the user's referrer are stored (only with a referrer)")]
+    #[account(8, optional, writable, name="referrer_investment", desc="The PDA in which the details of the referrer's investment are stored (only with a referrer)")]
+    CancelInvestmentEntry(CancelInvestmentEntryArgs),
+
+    /// Close the record of a user's investments once they are fully released, refunding its rent.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction")]
+    #[account(1, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(2, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
+    #[account(3, writable, name="rent_payer", desc="Wallet that paid for the creation of the user's investment PDA")]
+    #[account(4, name="system_program", desc="System Program")]
+    CloseUserInvestment(CloseUserInvestmentArgs),
+
+    /// Queue the reassignment of a user's investments to a new wallet (account recovery).
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(4, name="user_investment", desc="The PDA in which the details of the current owner's investment are stored")]
+    #[account(5, writable, name="timelock", desc="This PDA will hold timelocked instructions")]
+    #[account(6, name="system_program", desc="System Program")]
+    QueueReassignInvestment(ReassignInvestmentArgs),
+
+    /// Execute the reassignment of a user's investments to a new wallet (account recovery).
+    ///
+    /// The purchases and the release history of the current owner are moved to the new wallet, but not
+    /// their beneficiary, and if both wallets are on hold, the hold expiring last is kept.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction")]
+    #[account(1, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(2, writable, name="timelock", desc="This PDA will hold timelocked instructions")]
+    #[account(3, writable, name="user_investment", desc="The PDA in which the details of the current owner's investment are stored")]
+    #[account(4, writable, name="new_user_investment", desc="The PDA in which the details of the new owner's investment are stored")]
+    #[account(5, writable, name="rent_payer", desc="Wallet that paid for the creation of the current owner's investment PDA")]
+    #[account(6, name="system_program", desc="System Program")]
+    #[account(7, writable, name="user_purchases", desc="The PDA in which the current owner's on-chain purchases are stored (if any)")]
+    #[account(8, writable, name="new_user_purchases", desc="The PDA in which the new owner's on-chain purchases are stored")]
+    #[account(9, writable, name="owner", desc="Current owner of the investments, who paid for the creation of their purchases PDA")]
+    #[account(10, writable, name="release_history", desc="The PDA in which the latest releases of the current owner are stored (if enabled)")]
+    #[account(11, writable, name="new_release_history", desc="The PDA in which the latest releases of the new owner are stored")]
+    #[account(12, writable, name="history_rent_payer", desc="Wallet that paid for the creation of the current owner's release history (if enabled)")]
+    ExecuteReassignInvestment(ReassignInvestmentArgs),
+
+    /// Define (or remove) the wallet receiving the user's released tokens.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction (can be the user)")]
+    #[account(1, signer, name="user", desc="Wallet of the user owning the investments")]
+    #[account(2, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
+    #[account(3, name="beneficiary", desc="Wallet receiving the released tokens (the user's if removing the beneficiary)")]
+    #[account(4, writable, name="beneficiary_ata", desc="BGK ATA of the beneficiary (created if needed)")]
+    #[account(5, name="bgk_mint", desc="Mint of the BGK token")]
+    #[account(6, name="denylist", desc="The shard of the sanctions denylist in which the beneficiary would be stored")]
+    #[account(7, name="system_program", desc="System Program")]
+    #[account(8, name="token_program", desc="SPL Token 2022 Program")]
+    #[account(9, name="ata_program", desc="Associated Token Account Program")]
+    SetBeneficiary(SetBeneficiaryArgs),
+
+    /// Apply the departure of a team member, stopping the vesting of their revocable investments.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, name="bgk_mint", desc="Mint of the BGK token")]
+    #[account(6, writable, name="invested_pda", desc="Bangk BGK wallet storing the tokens that will be gradually released to the users")]
+    #[account(7, writable, name="target_pda", desc="Bangk BGK wallet of the teams and advisers receiving the forfeited tokens")]
+    #[account(8, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
+    #[account(9, name="system_program", desc="System Program")]
+    #[account(10, name="token_program", desc="SPL Token 2022 Program")]
+    ApplyLeaverEvent(LeaverEventArgs),
+
+    /// Put the releases of a user's tokens on hold for a compliance investigation (the vesting goes on).
+    #[account(0, signer, writable, name="authority", desc="Compliance key (or first admin key) signing and paying for the instruction")]
+    #[account(1, name="multisig_pda", desc="The compliance MultiSig PDA, or the admin one for a Sensitive operation")]
+    #[account(2, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
+    #[account(3, name="system_program", desc="System Program")]
+    #[account(4, optional, signer, name="admin2", desc="Second signer for the instruction (only with the admin MultiSig)")]
+    SetComplianceHold(SetComplianceHoldArgs),
+
+    /// Lift the hold on the releases of a user's tokens.
+    #[account(0, signer, writable, name="authority", desc="Compliance key (or first admin key) signing and paying for the instruction")]
+    #[account(1, name="multisig_pda", desc="The compliance MultiSig PDA, or the admin one for a Sensitive operation")]
+    #[account(2, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
+    #[account(3, name="system_program", desc="System Program")]
+    #[account(4, optional, signer, name="admin2", desc="Second signer for the instruction (only with the admin MultiSig)")]
+    ClearComplianceHold(ClearComplianceHoldArgs),
+
+    /// Accelerate the vesting of the investments of some users, or of a whole type of investment.
+    ///
+    /// The fixed accounts are followed by the `user_investment` PDA of each user concerned, or the
+    /// `vesting_position` PDA of each tokenised investment concerned. Without any of them, the acceleration applies to all the investments of the given type
+    /// (including the tokenised ones) and is recorded in the configuration.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, name="system_program", desc="System Program")]
+    AccelerateVesting(AccelerateVestingArgs),
+
+    /// Start keeping the history of the user's releases in a dedicated PDA.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction (can be the user)")]
+    #[account(1, signer, name="user", desc="Wallet of the user owning the investments")]
+    #[account(2, writable, name="release_history", desc="The PDA keeping the history of the user's releases")]
+    #[account(3, name="system_program", desc="System Program")]
+    EnableReleaseHistory,
+
+    /// Merge the investment entries of some users that can be merged (same type, scheme and
+    /// vesting state), the totals being kept.
+    ///
+    /// The fixed accounts are followed by the `user_investment` PDA of each user concerned.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction")]
+    #[account(1, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(2, name="system_program", desc="System Program")]
+    CompactInvestments,
+
+    /// Move one of a user's investments to a vesting position represented by an NFT.
+    ///
+    /// The NFT can only be transferred with `TransferPosition`, and its holder can claim
+    /// the position's tokens with `ClaimPosition`.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction")]
+    #[account(1, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(2, signer, name="user", desc="Wallet of the user owning the investment")]
+    #[account(3, writable, name="user_investment", desc="The PDA in which the details of a user's investment are stored")]
+    #[account(4, writable, name="position", desc="The PDA storing the vesting position")]
+    #[account(5, writable, name="position_mint", desc="Mint of the NFT representing the position")]
+    #[account(6, writable, name="user_nft_ata", desc="ATA of the user receiving the NFT")]
+    #[account(7, name="system_program", desc="System Program")]
+    #[account(8, name="token_program", desc="SPL Token 2022 Program")]
+    #[account(9, name="ata_program", desc="Associated Token Account Program")]
+    TokenizePosition(TokenizePositionArgs),
+
+    /// Release the vested tokens of a vesting position to the holder of its NFT.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction (can be the holder)")]
+    #[account(1, signer, name="holder", desc="Wallet holding the NFT of the position")]
+    #[account(2, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(3, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(4, name="bgk_mint", desc="Mint of the BGK token")]
+    #[account(5, writable, name="invested_pda", desc="Bangk BGK wallet storing the tokens that will be gradually released to the users")]
+    #[account(6, writable, name="position", desc="The PDA storing the vesting position")]
+    #[account(7, name="holder_nft_ata", desc="ATA of the holder storing the NFT")]
+    #[account(8, writable, name="holder_ata", desc="BGK ATA of the holder receiving the tokens")]
+    #[account(9, name="system_program", desc="System Program")]
+    #[account(10, name="token_program", desc="SPL Token 2022 Program")]
+    #[account(11, name="ata_program", desc="Associated Token Account Program")]
+    #[account(12, name="kyc_attestation", desc="The PDA attesting that the holder passed the KYC procedure")]
+    #[account(13, name="denylist", desc="The shard of the sanctions denylist in which the holder would be stored")]
+    #[account(14, writable, name="user_investment", desc="The PDA storing the investments of the user whose investment was tokenised (for the compliance holds)")]
+    #[account(15, name="holder_investment", desc="The PDA storing the investments of the holder (for the compliance holds)")]
+    #[account(16, writable, name="release_history", desc="The PDA keeping the history of the holder's releases (if they enabled it)")]
+    ClaimPosition(ClaimVestedArgs),
+
+    /// Define (or disable) the transfers of the vesting positions.
+    #[account(0, signer, writable, name="admin1", desc="First signer and fee payer for the instruction")]
+    #[account(1, signer, name="admin2", desc="Second signer for the instruction")]
+    #[account(2, signer, name="admin3", desc="Third signer for the instruction")]
+    #[account(3, writable, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, name="system_program", desc="System Program")]
+    SetPositionTransferPolicy(SetPositionTransferPolicyArgs),
+
+    /// Transfer the NFT of a vesting position, following the restrictions on the transfers.
+    #[account(0, signer, writable, name="payer", desc="Signer and fee payer for the instruction (can be the holder)")]
+    #[account(1, signer, name="holder", desc="Wallet holding the NFT of the position")]
+    #[account(2, name="recipient", desc="Wallet receiving the NFT")]
+    #[account(3, name="config_pda", desc="The PDA in which the program's configuration is stored")]
+    #[account(4, name="admin_pda", desc="The PDA in which keys allowed to perform administration or routine tasks are stored")]
+    #[account(5, writable, name="position", desc="The PDA storing the vesting position")]
+    #[account(6, name="position_mint", desc="Mint of the NFT representing the position")]
+    #[account(7, writable, name="holder_nft_ata", desc="ATA of the holder storing the NFT")]
+    #[account(8, writable, name="recipient_nft_ata", desc="ATA of the recipient receiving the NFT (created if needed)")]
+    #[account(9, name="kyc_attestation", desc="The PDA attesting that the recipient passed the KYC procedure")]
+    #[account(10, name="denylist", desc="The shard of the sanctions denylist in which the recipient would be stored")]
+    #[account(11, name="system_program", desc="System Program")]
+    #[account(12, name="token_program", desc="SPL Token 2022 Program")]
+    #[account(13, name="ata_program", desc="Associated Token Account Program")]
+    #[account(14, name="user_investment", desc="The PDA storing the investments of the user whose investment was tokenised (for the compliance holds)")]
+    #[account(15, name="holder_investment", desc="The PDA storing the investments of the holder (for the compliance holds)")]
+    TransferPosition,
+}
+
+/// Initializes the ICO program's configuration.
+///
+/// # Parameters
+/// * `payer` - Signer & Payer account,
+/// * `unvesting` - Definition of the unvesting scheme,
+/// * `api_key` - Key that will initially be used for routine tasks,
+/// * `admin1` - First key for the admin `MultiSig`
+/// * `admin2` - Second key for the admin `MultiSig`
+/// * `admin3` - Third key for the admin `MultiSig`
+/// * `admin4` - Fourth key for the admin `MultiSig`
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn initialize(
+    payer: &Pubkey,
+    api_key: &Pubkey,
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    admin4: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (transfer_timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
+
+    let args = InitializeArgs {
+        api_key: *api_key,
+        admin1: *admin1,
+        admin2: *admin2,
+        admin3: *admin3,
+        admin4: *admin4,
+    };
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new(admin_keys_pda, false),
+            AccountMeta::new(transfer_timelock_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::Initialize(args))?,
+    })
+}
+
+/// Create the instruction for the creation of the BGK mint and the initial mint of the tokens.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin2` - Key of the third signer of the instruction,
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn create_mint(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (mint_address, mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(mint_address, false),
+            AccountMeta::new(WalletType::Community.get_pda().0, false),
+            AccountMeta::new(WalletType::DeFiIncentives.get_pda().0, false),
+            AccountMeta::new(WalletType::Foundation.get_pda().0, false),
+            AccountMeta::new(WalletType::Ico.get_pda().0, false),
+            AccountMeta::new(WalletType::Liquidity.get_pda().0, false),
+            AccountMeta::new(WalletType::Marketing.get_pda().0, false),
+            AccountMeta::new(WalletType::Partners.get_pda().0, false),
+            AccountMeta::new(WalletType::ResearchDevelopmentFund.get_pda().0, false),
+            AccountMeta::new(WalletType::Reserve.get_pda().0, false),
+            AccountMeta::new(WalletType::TeamsAdvisers.get_pda().0, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::MintBGK(MintCreationArgs {
+            bump: mint_bump,
+        }))?,
+    })
+}
+
+/// Create the instruction for the creation of the BGK mint and the initial mint of the tokens.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin2` - Key of the third signer of the instruction,
+/// * `new_api_key` - Key that will initially be used for routine tasks,
+/// * `new_admin1` - First key for the admin `MultiSig`
+/// * `new_admin2` - Second key for the admin `MultiSig`
+/// * `new_admin3` - Third key for the admin `MultiSig`
+/// * `new_admin4` - Fourth key for the admin `MultiSig`
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+#[allow(clippy::too_many_arguments)]
+pub fn update_admin_multisig(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    new_api_key: &Pubkey,
+    new_admin1: &Pubkey,
+    new_admin2: &Pubkey,
+    new_admin3: &Pubkey,
+    new_admin4: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(admin_keys_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::UpdateAdminMultisig(
+            UpdateAdminMultisigArgs {
+                api_key: *new_api_key,
+                admin1: *new_admin1,
+                admin2: *new_admin2,
+                admin3: *new_admin3,
+                admin4: *new_admin4,
+            },
+        ))?,
+    })
+}
+
+/// Create an instruction to update or create a user's investment.
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `user` - User for whom the investment will be created / updated,
+/// * `invest_kind` - Type of investment (private sell, public sells, etc.),
+/// * `custom_rule` - Custom rule of unvesting if necessary for Advisers and Partners,
+/// * `amount` - Number of tokens bought.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn user_investment(
+    payer: &Pubkey,
+    user: &Pubkey,
+    invest_kind: UnvestingType,
+    custom_rule: Option<UnvestingScheme>,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    user_investment_with_args(
+        payer,
+        UserInvestmentArgs {
+            user: *user,
+            invest_kind,
+            custom_rule,
+            amount,
+            referrer: None,
+            reference: None,
+            payment: None,
+            revocable: false,
+        },
+    )
+}
+
+/// Create an instruction to update or create the investment of a user, with an idempotency key
+/// making the retries safe.
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `user` - User for whom the investment will be created / updated,
+/// * `invest_kind` - Type of investment (private sell, public sells, etc.),
+/// * `custom_rule` - Custom rule of unvesting if necessary for Advisers and Partners,
+/// * `amount` - Number of tokens bought,
+/// * `reference` - Idempotency key of the investment (external payment reference for example).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn user_investment_with_reference(
+    payer: &Pubkey,
+    user: &Pubkey,
+    invest_kind: UnvestingType,
+    custom_rule: Option<UnvestingScheme>,
+    amount: u64,
+    reference: [u8; 32],
+) -> Result<Instruction, ProgramError> {
+    user_investment_with_args(
+        payer,
+        UserInvestmentArgs {
+            user: *user,
+            invest_kind,
+            custom_rule,
+            amount,
+            referrer: None,
+            reference: Some(reference),
+            payment: None,
+            revocable: false,
+        },
+    )
+}
+
+/// Create an instruction to update or create the revocable investment of a team member.
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `user` - User for whom the investment will be created / updated,
+/// * `invest_kind` - Type of investment (team & founders, etc.),
+/// * `custom_rule` - Custom rule of unvesting if necessary,
+/// * `amount` - Number of tokens granted.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn revocable_user_investment(
+    payer: &Pubkey,
+    user: &Pubkey,
+    invest_kind: UnvestingType,
+    custom_rule: Option<UnvestingScheme>,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    user_investment_with_args(
+        payer,
+        UserInvestmentArgs {
+            user: *user,
+            invest_kind,
+            custom_rule,
+            amount,
+            referrer: None,
+            reference: None,
+            payment: None,
+            revocable: true,
+        },
+    )
+}
+
+/// Create an instruction to update or create the investment of a user brought by a referrer.
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `user` - User for whom the investment will be created / updated,
+/// * `invest_kind` - Type of investment (private sell, public sells, etc.),
+/// * `custom_rule` - Custom rule of unvesting if necessary for Advisers and Partners,
+/// * `amount` - Number of tokens bought,
+/// * `referrer` - Referrer of the user, rewarded by the referral program.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn referred_user_investment(
+    payer: &Pubkey,
+    user: &Pubkey,
+    invest_kind: UnvestingType,
+    custom_rule: Option<UnvestingScheme>,
+    amount: u64,
+    referrer: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    user_investment_with_args(
+        payer,
+        UserInvestmentArgs {
+            user: *user,
+            invest_kind,
+            custom_rule,
+            amount,
+            referrer: Some(*referrer),
+            reference: None,
+            payment: None,
+            revocable: false,
+        },
+    )
+}
+
+/// Create an instruction to update or create the investment of a user from its full arguments
+/// (referrer, idempotency key, payment metadata), with the referrer's accounts if needed.
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `args` - Definition of the investment.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn user_investment_with_args(
+    payer: &Pubkey,
+    args: UserInvestmentArgs,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(args.user, &crate::ID);
+    let (kyc_pda, _kyc_bump) = KycAttestationPda::get_address(args.user, &crate::ID);
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(config_pda, false),
+        AccountMeta::new_readonly(admin_keys_pda, false),
+        AccountMeta::new(investment_pda, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(kyc_pda, false),
+    ];
+    if let Some(referrer) = args.referrer {
+        let (stats_pda, _stats_bump) = ReferrerStatsPda::get_address(referrer, &crate::ID);
+        let (referrer_investment_pda, _referrer_investment_bump) =
+            UserInvestmentPda::get_address(referrer, &crate::ID);
+        accounts.extend([
+            AccountMeta::new(stats_pda, false),
+            AccountMeta::new(referrer_investment_pda, false),
+        ]);
+    }
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts,
+        data: borsh::to_vec(&BangkIcoInstruction::UserInvestment(args))?,
+    })
+}
+
+/// Create an instruction to update or create an adviser or partner's investment after the launch.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `user` - User for whom the investment will be created / updated,
+/// * `custom_rule` - Custom rule of unvesting if necessary,
+/// * `amount` - Number of tokens bought.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn queue_adviser_post_launch_investment(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    user: &Pubkey,
+    custom_rule: Option<UnvestingScheme>,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(timelock_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::QueuePostLaunchAdvisersInvestment(
+            UserInvestmentArgs {
+                user: *user,
+                invest_kind: UnvestingType::AdvisersPartners,
+                custom_rule,
+                amount,
+                referrer: None,
+                reference: None,
+                payment: None,
+                revocable: false,
+            },
+        ))?,
+    })
+}
+
+/// Create an instruction to update or create an adviser or partner's investment after the launch.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `user` - User for whom the investment will be created / updated,
+/// * `custom_rule` - Custom rule of unvesting if necessary,
+/// * `amount` - Number of tokens bought.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn process_adviser_post_launch_investment(
+    admin1: &Pubkey,
+    user: &Pubkey,
+    custom_rule: Option<UnvestingScheme>,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+    let (kyc_pda, _kyc_bump) = KycAttestationPda::get_address(user, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(timelock_pda, false),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(kyc_pda, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::ProcessPostLaunchAdvisersInvestment(
+            UserInvestmentArgs {
+                user: *user,
+                invest_kind: UnvestingType::AdvisersPartners,
+                custom_rule,
+                amount,
+                referrer: None,
+                reference: None,
+                payment: None,
+                revocable: false,
+            },
+        ))?,
+    })
+}
+
+/// Cancel a user's investment.
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `admin` - Admin key signing the instruction,
+/// * `user` - User for whom the investment will be created / updated,
+/// * `kind` - Investment type to cancel,
+/// * `amount` - Amount to cancel.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn cancel_investment(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    user: &Pubkey,
+    kind: UnvestingType,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    build_cancel_investment(
+        payer,
+        admin,
+        user,
+        None,
+        &BangkIcoInstruction::CancelInvestment(CancelInvestmentArgs {
+            user: *user,
+            kind,
+            amount,
+        }),
+    )
+}
+
+/// Cancel the investment of a user brought by a referrer, along with the referrer's reward.
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `admin` - Admin key signing the instruction,
+/// * `user` - User for whom the investment will be created / updated,
+/// * `kind` - Investment type to cancel,
+/// * `amount` - Amount to cancel,
+/// * `referrer` - Referrer of the user, rewarded by the referral program.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn cancel_referred_investment(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    user: &Pubkey,
+    kind: UnvestingType,
+    amount: u64,
+    referrer: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    build_cancel_investment(
+        payer,
+        admin,
+        user,
+        Some(referrer),
+        &BangkIcoInstruction::CancelInvestment(CancelInvestmentArgs {
+            user: *user,
+            kind,
+            amount,
+        }),
+    )
+}
+
+/// Build an instruction cancelling investments, with the referrer's accounts if needed.
+fn build_cancel_investment(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    user: &Pubkey,
+    referrer: Option<&Pubkey>,
+    instruction: &BangkIcoInstruction,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+    let (purchases_pda, _purchases_bump) = UserPurchasesPda::get_address(user, &crate::ID);
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(*admin, true),
+        AccountMeta::new(config_pda, false),
+        AccountMeta::new_readonly(admin_keys_pda, false),
+        AccountMeta::new(investment_pda, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new(purchases_pda, false),
+    ];
+    if let Some(referrer) = referrer {
+        let (stats_pda, _stats_bump) = ReferrerStatsPda::get_address(referrer, &crate::ID);
+        let (referrer_investment_pda, _referrer_investment_bump) =
+            UserInvestmentPda::get_address(referrer, &crate::ID);
+        accounts.extend([
+            AccountMeta::new(stats_pda, false),
+            AccountMeta::new(referrer_investment_pda, false),
+        ]);
+    }
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts,
+        data: borsh::to_vec(instruction)?,
+    })
+}
+
+/// Create the instruction to set the BGK token launch date.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `timestamp` - Timestamp of the launch,
+/// * `amount` - Number of tokens to be released during the unvesting.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn launch_bgk(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    timestamp: i64,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::LaunchBGK(LaunchBGKArgs { timestamp }))?,
+    })
+}
+
+/// Create the instruction to release vested tokens.
+///
+/// # Parameters
+/// * `payer` - Wallet signing and paying the transaction.
+/// * `user` - User for whom the tokens will be released.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn vesting_release(payer: &Pubkey, user: &Pubkey) -> Result<Instruction, ProgramError> {
+    build_vesting_release(payer, user, user, None)
+}
+
+/// Create the instruction to release vested tokens to the beneficiary defined by a user.
+///
+/// # Parameters
+/// * `payer` - Wallet signing and paying the transaction.
+/// * `user` - User for whom the tokens will be released.
+/// * `beneficiary` - Wallet receiving the tokens.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn vesting_release_to_beneficiary(
+    payer: &Pubkey,
+    user: &Pubkey,
+    beneficiary: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    build_vesting_release(payer, user, beneficiary, None)
+}
+
+/// Create the instruction for a user to claim their vested tokens.
+///
+/// Only the user (and the payer, if it's another wallet) needs to sign: no admin key is involved.
+///
+/// # Parameters
+/// * `payer` - Wallet paying the transaction and the ATA's creation (can be the user).
+/// * `user` - User claiming their tokens, must sign the transaction.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn claim_vested(payer: &Pubkey, user: &Pubkey) -> Result<Instruction, ProgramError> {
+    build_vesting_release(
+        payer,
+        user,
+        user,
+        Some(ClaimVestedArgs { max_amount: None }),
+    )
+}
+
+/// Create the instruction for a user to claim their vested tokens to their beneficiary.
+///
+/// # Parameters
+/// * `payer` - Wallet paying the transaction (can be the user).
+/// * `user` - User claiming their tokens, must sign the transaction.
+/// * `beneficiary` - Wallet receiving the tokens.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn claim_vested_to_beneficiary(
+    payer: &Pubkey,
+    user: &Pubkey,
+    beneficiary: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    build_vesting_release(
+        payer,
+        user,
+        beneficiary,
+        Some(ClaimVestedArgs { max_amount: None }),
+    )
+}
+
+/// Create the instruction for a user to claim part of their vested tokens.
+///
+/// The oldest investments are released first.
+///
+/// # Parameters
+/// * `payer` - Wallet paying the transaction and the ATA's creation (can be the user).
+/// * `user` - User claiming their tokens, must sign the transaction.
+/// * `beneficiary` - Wallet receiving the tokens if the user defined one.
+/// * `max_amount` - Maximum number of tokens to claim.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn claim_vested_partially(
+    payer: &Pubkey,
+    user: &Pubkey,
+    beneficiary: Option<&Pubkey>,
+    max_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    build_vesting_release(
+        payer,
+        user,
+        beneficiary.unwrap_or(user),
+        Some(ClaimVestedArgs {
+            max_amount: Some(max_amount),
+        }),
+    )
+}
+
+/// Create a `VestingRelease` or `ClaimVested` instruction (if `claim` is given).
+fn build_vesting_release(
+    payer: &Pubkey,
+    user: &Pubkey,
+    destination: &Pubkey,
+    claim: Option<ClaimVestedArgs>,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+    let invested_pda = WalletType::Ico.get_pda().0;
+    let destination_ata = get_associated_token_address_with_program_id(
+        destination,
+        &mint_address,
+        &spl_token_2022::ID,
+    );
+    let (kyc_pda, _kyc_bump) = KycAttestationPda::get_address(user, &crate::ID);
+    let (denylist_pda, _denylist_bump) =
+        DenylistPda::get_address(get_denylist_shard(user), &crate::ID);
+    let (destination_denylist_pda, _destination_denylist_bump) =
+        DenylistPda::get_address(get_denylist_shard(destination), &crate::ID);
+    let (history_pda, _history_bump) = ReleaseHistoryPda::get_address(user, &crate::ID);
+    let instruction = claim.map_or(
+        BangkIcoInstruction::VestingRelease,
+        BangkIcoInstruction::ClaimVested,
+    );
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(mint_address, false),
+            AccountMeta::new(invested_pda, false),
+            AccountMeta::new_readonly(*user, claim.is_some()),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new(destination_ata, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            AccountMeta::new_readonly(kyc_pda, false),
+            AccountMeta::new_readonly(denylist_pda, false),
+            AccountMeta::new_readonly(destination_denylist_pda, false),
+            AccountMeta::new(history_pda, false),
+        ],
+        data: borsh::to_vec(&instruction)?,
+    })
+}
+
+/// Create the instructions to release vested tokens for a list of users.
+///
+/// The users are split in as many instructions as needed, each holding at most
+/// `MAX_BATCH_RELEASE_USERS` users. Each instruction should be sent in its own transaction
+/// with a raised compute budget.
+///
+/// # Parameters
+/// * `payer` - Wallet signing and paying the transactions.
+/// * `users` - Users for whom the tokens will be released.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn batch_vesting_release(
+    payer: &Pubkey,
+    users: &[Pubkey],
+) -> Result<Vec<Instruction>, ProgramError> {
+    let releases = users.iter().map(|user| (*user, *user)).collect::<Vec<_>>();
+    batch_vesting_release_to_beneficiaries(payer, &releases)
+}
+
+/// Create the instructions to release vested tokens for a list of users and their beneficiaries.
+///
+/// Same as [`batch_vesting_release`], for users that may have defined a beneficiary.
+///
+/// # Parameters
+/// * `payer` - Wallet signing and paying the transactions.
+/// * `releases` - Users for whom the tokens will be released, with the wallet receiving them
+///   (their beneficiary, or themselves if they have none).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn batch_vesting_release_to_beneficiaries(
+    payer: &Pubkey,
+    releases: &[(Pubkey, Pubkey)],
+) -> Result<Vec<Instruction>, ProgramError> {
+    releases
+        .chunks(MAX_BATCH_RELEASE_USERS)
+        .map(|batch| build_batch_vesting_release(payer, batch))
+        .collect()
+}
+
+/// Create the messages of the v0 transactions releasing vested tokens for a list of users and
+/// their beneficiaries, their accounts being loaded from an address lookup table.
+///
+/// The users are split in as many messages as needed, each holding at most
+/// `MAX_BATCH_RELEASE_USERS_WITH_LOOKUP_TABLE` users. The lookup table can be created with
+/// [`batch_release_lookup_table`].
+///
+/// # Parameters
+/// * `payer` - Wallet signing and paying the transactions,
+/// * `releases` - Users for whom the tokens will be released, with the wallet receiving them
+///   (their beneficiary, or themselves if they have none),
+/// * `preamble` - Instructions put before each release (to raise the compute budget),
+/// * `lookup_table` - Address lookup table holding the accounts of the releases,
+/// * `recent_blockhash` - Recent blockhash of the transactions.
+///
+/// # Errors
+/// If instruction's data could not be serialized, or the messages could not be compiled.
+pub fn batch_vesting_release_messages(
+    payer: &Pubkey,
+    releases: &[(Pubkey, Pubkey)],
+    preamble: &[Instruction],
+    lookup_table: &AddressLookupTableAccount,
+    recent_blockhash: Hash,
+) -> Result<Vec<VersionedMessage>, ProgramError> {
+    releases
+        .chunks(MAX_BATCH_RELEASE_USERS_WITH_LOOKUP_TABLE)
+        .map(|batch| {
+            let mut instructions = preamble.to_vec();
+            instructions.push(build_batch_vesting_release(payer, batch)?);
+            let message = v0::Message::try_compile(
+                payer,
+                &instructions,
+                &[lookup_table.clone()],
+                recent_blockhash,
+            )
+            .map_err(|_err| ProgramError::InvalidArgument)?;
+            Ok(VersionedMessage::V0(message))
+        })
+        .collect()
+}
+
+/// Maximum number of addresses added to a lookup table by a single instruction (keeps the
+/// transaction under Solana's size limit).
+const MAX_LOOKUP_TABLE_EXTENSION: usize = 24;
+
+/// Create the instructions creating and filling the address lookup table used by
+/// [`batch_vesting_release_messages`].
+///
+/// The table must be activated (one slot after its last extension) before being used. Each
+/// instruction should be sent in its own transaction.
+///
+/// # Parameters
+/// * `authority` - Authority of the lookup table, must sign the extensions,
+/// * `payer` - Wallet paying the transactions and the table's rent,
+/// * `recent_slot` - Recent slot, used to derive the table's address,
+/// * `releases` - Users for whom the tokens will be released, with the wallet receiving them.
+///
+/// # Returns
+/// The address lookup table (its address and the addresses it holds), and the instructions.
+///
+/// # Errors
+/// If there are too many addresses for a single lookup table.
+pub fn batch_release_lookup_table(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: Slot,
+    releases: &[(Pubkey, Pubkey)],
+) -> Result<(AddressLookupTableAccount, Vec<Instruction>), ProgramError> {
+    let mut addresses = Vec::new();
+    for batch in releases.chunks(MAX_BATCH_RELEASE_USERS_WITH_LOOKUP_TABLE) {
+        let instruction = build_batch_vesting_release(payer, batch)?;
+        for account in instruction.accounts {
+            // The signers can't be loaded from a lookup table
+            if !account.is_signer && !addresses.contains(&account.pubkey) {
+                addresses.push(account.pubkey);
+            }
+        }
+    }
+    if addresses.len() > LOOKUP_TABLE_MAX_ADDRESSES {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let (create, table) = create_lookup_table(*authority, *payer, recent_slot);
+    let mut instructions = vec![create];
+    instructions.extend(
+        addresses
+            .chunks(MAX_LOOKUP_TABLE_EXTENSION)
+            .map(|batch| extend_lookup_table(table, *authority, Some(*payer), batch.to_vec())),
+    );
+    Ok((
+        AddressLookupTableAccount {
+            key: table,
+            addresses,
+        },
+        instructions,
+    ))
+}
+
+/// Create a `BatchVestingRelease` instruction for a batch of users.
+///
+/// The denylist shard of a beneficiary is only given if it isn't the user's one.
+fn build_batch_vesting_release(
+    payer: &Pubkey,
+    batch: &[(Pubkey, Pubkey)],
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let invested_pda = WalletType::Ico.get_pda().0;
+
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(config_pda, false),
+        AccountMeta::new_readonly(admin_keys_pda, false),
+        AccountMeta::new_readonly(mint_address, false),
+        AccountMeta::new(invested_pda, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(spl_token_2022::ID, false),
+        AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+    ];
+    let mut beneficiary_shards = Vec::with_capacity(batch.len());
+    for (user, destination) in batch {
+        let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+        let destination_ata = get_associated_token_address_with_program_id(
+            destination,
+            &mint_address,
+            &spl_token_2022::ID,
+        );
+        let (kyc_pda, _kyc_bump) = KycAttestationPda::get_address(user, &crate::ID);
+        let (denylist_pda, _denylist_bump) =
+            DenylistPda::get_address(get_denylist_shard(user), &crate::ID);
+        let (history_pda, _history_bump) = ReleaseHistoryPda::get_address(user, &crate::ID);
+        accounts.extend([
+            AccountMeta::new_readonly(*user, false),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new(destination_ata, false),
+            AccountMeta::new_readonly(kyc_pda, false),
+            AccountMeta::new_readonly(denylist_pda, false),
+            AccountMeta::new(history_pda, false),
+        ]);
+
+        let beneficiary_shard = get_denylist_shard(destination) != get_denylist_shard(user);
+        if beneficiary_shard {
+            let (destination_denylist_pda, _destination_denylist_bump) =
+                DenylistPda::get_address(get_denylist_shard(destination), &crate::ID);
+            accounts.push(AccountMeta::new_readonly(destination_denylist_pda, false));
+        }
+        beneficiary_shards.push(beneficiary_shard);
+    }
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts,
+        data: borsh::to_vec(&BangkIcoInstruction::BatchVestingRelease(
+            BatchVestingReleaseArgs { beneficiary_shards },
+        ))?,
+    })
+}
+
+/// Queues the clawback of a user's unvested tokens.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `user` - User whose tokens are clawed back,
+/// * `kind` - Type of the investments concerned (all of them if `None`),
+/// * `date` - Date as of which the vesting is stopped,
+/// * `target` - Internal wallet receiving the clawed back tokens.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn queue_clawback(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    user: &Pubkey,
+    kind: Option<UnvestingType>,
+    date: i64,
+    target: WalletType,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new_readonly(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(timelock_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::QueueClawback(ClawbackArgs {
+            user: *user,
+            kind,
+            date,
+            target,
+        }))?,
+    })
+}
+
+/// Executes a time-locked clawback of a user's unvested tokens.
+///
+/// # Parameters
+/// * `payer` - Key of the payer and signer of the instruction,
+/// * `user` - User whose tokens are clawed back,
+/// * `kind` - Type of the investments concerned (all of them if `None`),
+/// * `date` - Date as of which the vesting is stopped,
+/// * `target` - Internal wallet receiving the clawed back tokens.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn execute_clawback(
+    payer: &Pubkey,
+    user: &Pubkey,
+    kind: Option<UnvestingType>,
+    date: i64,
+    target: WalletType,
+) -> Result<Instruction, ProgramError> {
+    execute_clawback_with_positions(payer, user, kind, date, target, &[])
+}
+
+/// Executes a time-locked clawback of the unvested tokens of a user who tokenised some of their
+/// investments.
+///
+/// # Parameters
+/// * `payer` - Key of the payer and signer of the instruction,
+/// * `user` - User whose tokens are clawed back,
+/// * `kind` - Type of the investments concerned (all of them if `None`),
+/// * `date` - Date as of which the vesting is stopped,
+/// * `target` - Internal wallet receiving the clawed back tokens,
+/// * `positions` - Mints of the user's vesting positions that are not fully released.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn execute_clawback_with_positions(
+    payer: &Pubkey,
+    user: &Pubkey,
+    kind: Option<UnvestingType>,
+    date: i64,
+    target: WalletType,
+    positions: &[Pubkey],
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+    let invested_pda = WalletType::Ico.get_pda().0;
+    let target_pda = target.get_pda().0;
+
+    let mut accounts = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(config_pda, false),
+        AccountMeta::new_readonly(admin_keys_pda, false),
+        AccountMeta::new(timelock_pda, false),
+        AccountMeta::new_readonly(mint_address, false),
+        AccountMeta::new(invested_pda, false),
+        AccountMeta::new(target_pda, false),
+        AccountMeta::new(investment_pda, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+        AccountMeta::new_readonly(spl_token_2022::ID, false),
+    ];
+    accounts.extend(positions.iter().map(|mint| {
+        let (position_pda, _position_bump) = VestingPositionPda::get_address(mint, &crate::ID);
+        AccountMeta::new(position_pda, false)
+    }));
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts,
+        data: borsh::to_vec(&BangkIcoInstruction::ExecuteClawback(ClawbackArgs {
+            user: *user,
+            kind,
+            date,
+            target,
+        }))?,
+    })
+}
+
+/// Queues an instruction to transfer tokens from the reserve.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin2` - Key of the third signer of the instruction,
+/// * `target` - Target ATA (created if doesn't exist yet),
+/// * `source` - The source internal wallet (community, marketing, etc.)
+/// * `amount` - Number of tokens to transfer.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn queue_transfer_from_internal_wallet(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    target: &Pubkey,
+    source: WalletType,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
+    let target_ata =
+        get_associated_token_address_with_program_id(target, &mint_address, &spl_token_2022::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(timelock_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::QueueTransferFromInternalWallet(
+            QueueTransferFromInternalWalletArgs {
+                target: target_ata,
+                amount,
+                source,
+            },
+        ))?,
+    })
+}
+
+/// Create the instruction to execute a time-locked transfer
+///
+/// # Parameters
+/// * `target` - Target ATA (created if doesn't exist yet),
+/// * `source` - The source internal wallet (community, marketing, etc.)
+/// * `amount` - Number of tokens to be released during the unvesting.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn execute_transfer_from_internal_wallet(
+    payer: &Pubkey,
+    target: &Pubkey,
+    source: WalletType,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
+    let source_pda = source.get_pda().0;
+    let target_ata =
+        get_associated_token_address_with_program_id(target, &mint_address, &spl_token_2022::ID);
+    let (denylist_pda, _denylist_bump) =
+        DenylistPda::get_address(get_denylist_shard(target), &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(timelock_pda, false),
+            AccountMeta::new(mint_address, false),
+            AccountMeta::new(source_pda, false),
+            AccountMeta::new_readonly(*target, false),
+            AccountMeta::new(target_ata, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            AccountMeta::new_readonly(denylist_pda, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::ExecuteTransferFromInternalWallet(
+            ExecuteTransferFromInternalWalletArgs { source, amount },
+        ))?,
+    })
+}
+
+/// Create the instruction to convert the investments of a user stored with the layout of the
+/// v1.0.0 of the program.
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `user` - User owning the investments.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn migrate_user_investment(payer: &Pubkey, user: &Pubkey) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::MigrateUserInvestment(
+            MigrateUserInvestmentArgs { user: *user },
+        ))?,
+    })
+}
+
+/// Create the instruction to convert the configuration of the program stored with the layout of
+/// the v1.0.0 of the program.
+///
+/// The amounts invested per type of investment weren't recorded by the v1.0.0: they must be
+/// computed from the users' investments.
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `invested_per_kind` - Number of tokens invested in each type of investment.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn migrate_configuration(
+    payer: &Pubkey,
+    invested_per_kind: &[(UnvestingType, u64)],
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::MigrateConfiguration(
+            MigrateConfigurationArgs {
+                invested_per_kind: invested_per_kind.to_vec(),
+            },
+        ))?,
+    })
+}
+
+/// Create the instruction to define the phases of the token sale.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `phases` - Definition of the sale phases (replaces the current ones).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn set_sale_phases(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    phases: Vec<SalePhase>,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::SetSalePhases(SetSalePhasesArgs {
+            phases,
+        }))?,
+    })
+}
+
+/// Create the instruction to create the treasury receiving the payments of the on-chain purchases.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `payment_mint` - Mint of the currency accepted for the purchases,
+/// * `token_program` - Token program owning the mint (SPL Token or SPL Token 2022).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn initialize_sale_treasury(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    payment_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (treasury, _treasury_bump) = get_sale_treasury_pda(payment_mint);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(*payment_mint, false),
+            AccountMeta::new(treasury, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::InitializeSaleTreasury)?,
+    })
+}
+
+/// Create the instruction to purchase BGK tokens at the price of the running sale phase.
+///
+/// The payment is made from the buyer's associated token account.
+///
+/// # Parameters
+/// * `buyer` - Wallet buying the tokens, signing and paying the transaction,
+/// * `payment_mint` - Mint of the currency accepted for the purchases,
+/// * `token_program` - Token program owning the mint (SPL Token or SPL Token 2022),
+/// * `kind` - Type of investment bought,
+/// * `amount` - Number of tokens bought.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn purchase(
+    buyer: &Pubkey,
+    payment_mint: &Pubkey,
+    token_program: &Pubkey,
+    kind: UnvestingType,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(buyer, &crate::ID);
+    let (treasury, _treasury_bump) = get_sale_treasury_pda(payment_mint);
+    let (purchases_pda, _purchases_bump) = UserPurchasesPda::get_address(buyer, &crate::ID);
+    let (kyc_pda, _kyc_bump) = KycAttestationPda::get_address(buyer, &crate::ID);
+    let buyer_account =
+        get_associated_token_address_with_program_id(buyer, payment_mint, token_program);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*buyer, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new_readonly(*payment_mint, false),
+            AccountMeta::new(buyer_account, false),
+            AccountMeta::new(treasury, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new(purchases_pda, false),
+            AccountMeta::new_readonly(kyc_pda, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::Purchase(PurchaseArgs {
+            kind,
+            amount,
+        }))?,
+    })
+}
+
+/// Create the instruction to abort the token sale.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn abort_sale(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::AbortSale)?,
+    })
+}
+
+/// Create the instruction to refund the payments of a user's cancelled purchases.
+///
+/// # Parameters
+/// * `payer` - Wallet signing and paying the transaction (the user or an admin key),
+/// * `user` - User being refunded,
+/// * `payment_mint` - Mint of the currency accepted for the purchases,
+/// * `token_program` - Token program owning the mint (SPL Token or SPL Token 2022).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn refund(
+    payer: &Pubkey,
+    user: &Pubkey,
+    payment_mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (purchases_pda, _purchases_bump) = UserPurchasesPda::get_address(user, &crate::ID);
+    let (treasury, _treasury_bump) = get_sale_treasury_pda(payment_mint);
+    let user_account =
+        get_associated_token_address_with_program_id(user, payment_mint, token_program);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(*user, payer == user),
+            AccountMeta::new(purchases_pda, false),
+            AccountMeta::new_readonly(*payment_mint, false),
+            AccountMeta::new(user_account, false),
+            AccountMeta::new(treasury, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::Refund)?,
+    })
+}
+
+/// Create the instruction to create or update the keys of the compliance `MultiSig`.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `keys` - Keys allowed to issue or revoke KYC attestations (replaces the current ones).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn set_compliance_multisig(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    keys: Vec<Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (compliance_keys_pda, _compliance_bump) =
+        MultiSigPda::get_address(MultiSigType::Compliance, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(compliance_keys_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::SetComplianceMultisig(
+            SetComplianceMultisigArgs { keys },
+        ))?,
+    })
+}
+
+/// Create the instruction to issue (or renew) a user's KYC attestation.
+///
+/// # Parameters
+/// * `compliance` - Compliance key signing and paying the transaction,
+/// * `user` - User who passed the KYC procedure,
+/// * `level` - Level of verification reached by the user,
+/// * `expiry` - Timestamp at which the attestation expires,
+/// * `jurisdiction` - Jurisdiction of the user (ISO 3166-1 alpha-2 country code).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn issue_kyc_attestation(
+    compliance: &Pubkey,
+    user: &Pubkey,
+    level: u8,
+    expiry: i64,
+    jurisdiction: [u8; 2],
+) -> Result<Instruction, ProgramError> {
+    let (compliance_keys_pda, _compliance_bump) =
+        MultiSigPda::get_address(MultiSigType::Compliance, &crate::ID);
+    let (kyc_pda, _kyc_bump) = KycAttestationPda::get_address(user, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*compliance, true),
+            AccountMeta::new_readonly(compliance_keys_pda, false),
+            AccountMeta::new(kyc_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::IssueKycAttestation(
+            IssueKycAttestationArgs {
+                user: *user,
+                level,
+                expiry,
+                jurisdiction,
+            },
+        ))?,
+    })
+}
+
+/// Create the instruction to revoke a user's KYC attestation.
+///
+/// # Parameters
+/// * `compliance` - Compliance key signing the transaction (receives the attestation's rent),
+/// * `user` - User whose attestation is revoked.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn revoke_kyc_attestation(
+    compliance: &Pubkey,
+    user: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (compliance_keys_pda, _compliance_bump) =
+        MultiSigPda::get_address(MultiSigType::Compliance, &crate::ID);
+    let (kyc_pda, _kyc_bump) = KycAttestationPda::get_address(user, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*compliance, true),
+            AccountMeta::new_readonly(compliance_keys_pda, false),
+            AccountMeta::new(kyc_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::RevokeKycAttestation(
+            RevokeKycAttestationArgs { user: *user },
+        ))?,
+    })
+}
+
+/// Create the instruction to add a wallet to the sanctions denylist.
+///
+/// # Parameters
+/// * `compliance` - Compliance key signing and paying for the transaction,
+/// * `wallet` - Wallet under sanctions.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn add_to_denylist(compliance: &Pubkey, wallet: &Pubkey) -> Result<Instruction, ProgramError> {
+    let (compliance_keys_pda, _compliance_bump) =
+        MultiSigPda::get_address(MultiSigType::Compliance, &crate::ID);
+    let (denylist_pda, _denylist_bump) =
+        DenylistPda::get_address(get_denylist_shard(wallet), &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*compliance, true),
+            AccountMeta::new_readonly(compliance_keys_pda, false),
+            AccountMeta::new(denylist_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::AddToDenylist(DenylistArgs {
+            wallet: *wallet,
+        }))?,
+    })
+}
+
+/// Create the instruction to remove a wallet from the sanctions denylist.
+///
+/// # Parameters
+/// * `compliance` - Compliance key signing and paying for the transaction,
+/// * `wallet` - Wallet no longer under sanctions.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn remove_from_denylist(
+    compliance: &Pubkey,
+    wallet: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (compliance_keys_pda, _compliance_bump) =
+        MultiSigPda::get_address(MultiSigType::Compliance, &crate::ID);
+    let (denylist_pda, _denylist_bump) =
+        DenylistPda::get_address(get_denylist_shard(wallet), &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*compliance, true),
+            AccountMeta::new_readonly(compliance_keys_pda, false),
+            AccountMeta::new(denylist_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::RemoveFromDenylist(DenylistArgs {
+            wallet: *wallet,
+        }))?,
+    })
+}
+
+/// Create the instruction to create a Merkle airdrop.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `args` - Definition of the airdrop.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn create_airdrop(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    args: CreateAirdropArgs,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let (airdrop_pda, _airdrop_bump) = AirdropPda::get_address(args.id, &crate::ID);
+    let (claims_pda, _claims_bump) = AirdropClaimsPda::get_address(args.id, &crate::ID);
+    let (vault, _vault_bump) = get_airdrop_vault_pda(args.id);
+    let source_pda = args.source.get_pda().0;
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(airdrop_pda, false),
+            AccountMeta::new(claims_pda, false),
+            AccountMeta::new_readonly(mint_address, false),
+            AccountMeta::new(source_pda, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::CreateAirdrop(args))?,
+    })
+}
+
+/// Create the instruction to claim tokens from a Merkle airdrop.
+///
+/// # Parameters
+/// * `claimant` - Recipient of the airdrop, signing and paying for the transaction,
+/// * `id` - Identifier of the airdrop,
+/// * `index` - Index of the claimant in the Merkle tree,
+/// * `amount` - Number of tokens claimed,
+/// * `proof` - Sibling nodes from the claimant's leaf to the root of the Merkle tree.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn claim_airdrop(
+    claimant: &Pubkey,
+    id: u8,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let (airdrop_pda, _airdrop_bump) = AirdropPda::get_address(id, &crate::ID);
+    let (claims_pda, _claims_bump) = AirdropClaimsPda::get_address(id, &crate::ID);
+    let (vault, _vault_bump) = get_airdrop_vault_pda(id);
+    let claimant_ata =
+        get_associated_token_address_with_program_id(claimant, &mint_address, &spl_token_2022::ID);
+    let invested_pda = WalletType::Ico.get_pda().0;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(claimant, &crate::ID);
+    let (denylist_pda, _denylist_bump) =
+        DenylistPda::get_address(get_denylist_shard(claimant), &crate::ID);
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*claimant, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(airdrop_pda, false),
+            AccountMeta::new(claims_pda, false),
+            AccountMeta::new_readonly(mint_address, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(claimant_ata, false),
+            AccountMeta::new(invested_pda, false),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            AccountMeta::new_readonly(denylist_pda, false),
+            AccountMeta::new(config_pda, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::ClaimAirdrop(ClaimAirdropArgs {
+            id,
+            index,
+            amount,
+            proof,
+        }))?,
+    })
+}
+
+/// Create the instruction to return the unclaimed tokens of an airdrop to its source wallet.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `id` - Identifier of the airdrop,
+/// * `source` - Internal wallet which funded the airdrop.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn reclaim_airdrop(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    id: u8,
+    source: WalletType,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let (airdrop_pda, _airdrop_bump) = AirdropPda::get_address(id, &crate::ID);
+    let (vault, _vault_bump) = get_airdrop_vault_pda(id);
+    let source_pda = source.get_pda().0;
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(airdrop_pda, false),
+            AccountMeta::new_readonly(mint_address, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(source_pda, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::ReclaimAirdrop(ReclaimAirdropArgs {
+            id,
+        }))?,
+    })
+}
+
+/// Create the instruction to define (or stop) the referral program.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `program` - Definition of the referral program (`None` to stop it).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn set_referral_program(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    program: Option<ReferralProgram>,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::SetReferralProgram(
+            SetReferralProgramArgs { program },
+        ))?,
+    })
+}
+
+/// Create the instruction to transfer the referral rewards granted so far to the `Ico` wallet.
+///
+/// # Parameters
+/// * `payer` - Wallet signing and paying the transaction,
+/// * `source` - Internal wallet funding the referral rewards.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn fund_referral_rewards(
+    payer: &Pubkey,
+    source: WalletType,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let source_pda = source.get_pda().0;
+    let invested_pda = WalletType::Ico.get_pda().0;
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(mint_address, false),
+            AccountMeta::new(source_pda, false),
+            AccountMeta::new(invested_pda, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::FundReferralRewards)?,
+    })
+}
+
+/// Create the instruction to define the bonus granted to the investors.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `rules` - Definition of the bonus rules (replaces the current ones).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn set_bonus_rules(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    rules: Vec<BonusRule>,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::SetBonusRules(SetBonusRulesArgs {
+            rules,
+        }))?,
+    })
+}
+
+/// Create the instruction to define the caps on the investments.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `caps` - Definition of the caps (replaces the current ones).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn set_investment_caps(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    caps: Vec<InvestmentCap>,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::SetInvestmentCaps(
+            SetInvestmentCapsArgs { caps },
+        ))?,
+    })
+}
+
+/// Cancel a specific investment of a user.
+///
+/// The change made is set as the instruction's return data (see [`CancelledInvestment`]).
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `admin` - Admin key signing the instruction,
+/// * `user` - User owning the investment,
+/// * `target` - Investment to cancel,
+/// * `amount` - Amount to cancel (the whole investment if `None`).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn cancel_investment_entry(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    user: &Pubkey,
+    target: InvestmentSelector,
+    amount: Option<u64>,
+) -> Result<Instruction, ProgramError> {
+    build_cancel_investment(
+        payer,
+        admin,
+        user,
+        None,
+        &BangkIcoInstruction::CancelInvestmentEntry(CancelInvestmentEntryArgs {
+            user: *user,
+            target,
+            amount,
+        }),
+    )
+}
+
+/// Cancel a specific investment of a user brought by a referrer, along with the referrer's reward.
+///
+/// The change made is set as the instruction's return data (see [`CancelledInvestment`]).
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `admin` - Admin key signing the instruction,
+/// * `user` - User owning the investment,
+/// * `target` - Investment to cancel,
+/// * `amount` - Amount to cancel (the whole investment if `None`),
+/// * `referrer` - Referrer of the user, rewarded by the referral program.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn cancel_referred_investment_entry(
+    payer: &Pubkey,
+    admin: &Pubkey,
+    user: &Pubkey,
+    target: InvestmentSelector,
+    amount: Option<u64>,
+    referrer: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    build_cancel_investment(
+        payer,
+        admin,
+        user,
+        Some(referrer),
+        &BangkIcoInstruction::CancelInvestmentEntry(CancelInvestmentEntryArgs {
+            user: *user,
+            target,
+            amount,
+        }),
+    )
+}
+
+/// Close the record of a user's investments once they are fully released.
+///
+/// A summary of the investments is set as the instruction's return data
+/// (see [`CompletedInvestment`]).
+///
+/// # Parameters
+/// * `payer` - Transaction signer & fee payer,
+/// * `user` - User owning the investments,
+/// * `rent_payer` - Wallet that paid for the creation of the record (receives its rent).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn close_user_investment(
+    payer: &Pubkey,
+    user: &Pubkey,
+    rent_payer: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new(*rent_payer, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::CloseUserInvestment(
+            CloseUserInvestmentArgs { user: *user },
+        ))?,
+    })
+}
+
+/// Queues the reassignment of all the investments of a user to a new wallet.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `from` - Current owner of the investments,
+/// * `to` - New owner of the investments.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn queue_reassign_investment(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    from: &Pubkey,
+    to: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(from, &crate::ID);
+    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(investment_pda, false),
+            AccountMeta::new(timelock_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::QueueReassignInvestment(
+            ReassignInvestmentArgs {
+                from: *from,
+                to: *to,
+            },
+        ))?,
+    })
+}
+
+/// Executes a time-locked reassignment of all the investments of a user to a new wallet.
+///
+/// # Parameters
+/// * `payer` - Key of the payer and signer of the instruction,
+/// * `from` - Current owner of the investments,
+/// * `to` - New owner of the investments,
+/// * `rent_payer` - Wallet that paid for the creation of the current owner's record (receives its rent),
+/// * `history_rent_payer` - Wallet that paid for the creation of the current owner's release history
+///   (receives its rent, ignored if they have none).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn execute_reassign_investment(
+    payer: &Pubkey,
+    from: &Pubkey,
+    to: &Pubkey,
+    rent_payer: &Pubkey,
+    history_rent_payer: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(from, &crate::ID);
+    let (new_investment_pda, _new_investment_bump) = UserInvestmentPda::get_address(to, &crate::ID);
+    let (purchases_pda, _purchases_bump) = UserPurchasesPda::get_address(from, &crate::ID);
+    let (new_purchases_pda, _new_purchases_bump) = UserPurchasesPda::get_address(to, &crate::ID);
+    let (history_pda, _history_bump) = ReleaseHistoryPda::get_address(from, &crate::ID);
+    let (new_history_pda, _new_history_bump) = ReleaseHistoryPda::get_address(to, &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new(timelock_pda, false),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new(new_investment_pda, false),
+            AccountMeta::new(*rent_payer, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new(purchases_pda, false),
+            AccountMeta::new(new_purchases_pda, false),
+            AccountMeta::new(*from, false),
+            AccountMeta::new(history_pda, false),
+            AccountMeta::new(new_history_pda, false),
+            AccountMeta::new(*history_rent_payer, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::ExecuteReassignInvestment(
+            ReassignInvestmentArgs {
+                from: *from,
+                to: *to,
+            },
+        ))?,
+    })
+}
+
+/// Define (or remove) the wallet receiving a user's released tokens.
+///
+/// # Parameters
+/// * `payer` - Wallet paying the transaction and the ATA's creation (can be the user),
+/// * `user` - User owning the investments, must sign the transaction,
+/// * `beneficiary` - Wallet receiving the released tokens (the user's own wallet if `None`).
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn set_beneficiary(
+    payer: &Pubkey,
+    user: &Pubkey,
+    beneficiary: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let wallet = beneficiary.unwrap_or(user);
+    let beneficiary_ata =
+        get_associated_token_address_with_program_id(wallet, &mint_address, &spl_token_2022::ID);
+    let (denylist_pda, _denylist_bump) =
+        DenylistPda::get_address(get_denylist_shard(wallet), &crate::ID);
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new(beneficiary_ata, false),
+            AccountMeta::new_readonly(mint_address, false),
+            AccountMeta::new_readonly(denylist_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::SetBeneficiary(SetBeneficiaryArgs {
+            beneficiary: beneficiary.copied(),
+        }))?,
+    })
+}
+
+/// Apply the departure of a team member to their revocable investments.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `user` - User leaving,
+/// * `date` - Date of departure,
+/// * `policy` - What the user keeps of their revocable investments.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn apply_leaver_event(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    user: &Pubkey,
+    date: i64,
+    policy: LeaverPolicy,
+) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+    let invested_pda = WalletType::Ico.get_pda().0;
+    let target_pda = WalletType::TeamsAdvisers.get_pda().0;
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts: vec![
+            AccountMeta::new(*admin1, true),
+            AccountMeta::new_readonly(*admin2, true),
+            AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(config_pda, false),
+            AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(mint_address, false),
+            AccountMeta::new(invested_pda, false),
+            AccountMeta::new(target_pda, false),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+        ],
+        data: borsh::to_vec(&BangkIcoInstruction::ApplyLeaverEvent(LeaverEventArgs {
+            user: *user,
+            date,
+            policy,
+        }))?,
+    })
+}
+
+/// Put the releases of a user's tokens on hold for a compliance investigation.
+///
+/// # Parameters
+/// * `compliance` - Compliance key signing and paying for the instruction,
+/// * `user` - User whose releases are put on hold,
+/// * `reason` - Reason of the hold,
+/// * `expiry` - Timestamp at which the hold expires.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn set_compliance_hold(
+    compliance: &Pubkey,
+    user: &Pubkey,
+    reason: u16,
+    expiry: i64,
+) -> Result<Instruction, ProgramError> {
+    build_compliance_hold(
+        compliance,
+        None,
+        MultiSigType::Compliance,
+        user,
+        &BangkIcoInstruction::SetComplianceHold(SetComplianceHoldArgs {
+            user: *user,
+            reason,
+            expiry,
+        }),
+    )
+}
+
+/// Put the releases of a user's tokens on hold, signed by the admin `MultiSig`.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `user` - User whose releases are put on hold,
+/// * `reason` - Reason of the hold,
+/// * `expiry` - Timestamp at which the hold expires.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn admin_set_compliance_hold(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    user: &Pubkey,
+    reason: u16,
+    expiry: i64,
+) -> Result<Instruction, ProgramError> {
+    build_compliance_hold(
+        admin1,
+        Some(admin2),
+        MultiSigType::Admin,
+        user,
+        &BangkIcoInstruction::SetComplianceHold(SetComplianceHoldArgs {
+            user: *user,
+            reason,
+            expiry,
+        }),
+    )
+}
+
+/// Lift the hold on the releases of a user's tokens.
+///
+/// # Parameters
+/// * `compliance` - Compliance key signing and paying for the instruction,
+/// * `user` - User whose releases were on hold.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn clear_compliance_hold(
+    compliance: &Pubkey,
+    user: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    build_compliance_hold(
+        compliance,
+        None,
+        MultiSigType::Compliance,
+        user,
+        &BangkIcoInstruction::ClearComplianceHold(ClearComplianceHoldArgs { user: *user }),
+    )
+}
+
+/// Lift the hold on the releases of a user's tokens, signed by the admin `MultiSig`.
+///
+/// # Parameters
+/// * `admin1` - Key of the payer and first signer of the instruction,
+/// * `admin2` - Key of the second signer of the instruction,
+/// * `user` - User whose releases were on hold.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn admin_clear_compliance_hold(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    user: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    build_compliance_hold(
+        admin1,
+        Some(admin2),
+        MultiSigType::Admin,
+        user,
+        &BangkIcoInstruction::ClearComplianceHold(ClearComplianceHoldArgs { user: *user }),
+    )
+}
+
+/// Create a `SetComplianceHold` or `ClearComplianceHold` instruction.
+fn build_compliance_hold(
+    authority: &Pubkey,
+    cosigner: Option<&Pubkey>,
+    multisig: MultiSigType,
+    user: &Pubkey,
+    instruction: &BangkIcoInstruction,
+) -> Result<Instruction, ProgramError> {
+    let (multisig_pda, _multisig_bump) = MultiSigPda::get_address(multisig, &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+
+    let mut accounts = vec![
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(multisig_pda, false),
+        AccountMeta::new(investment_pda, false),
+        AccountMeta::new_readonly(system_program::ID, false),
+    ];
+    if let Some(cosigner) = cosigner {
+        accounts.push(AccountMeta::new_readonly(*cosigner, true));
+    }
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts,
+        data: borsh::to_vec(instruction)?,
     })
 }
 
-/// Create the instruction for the creation of the BGK mint and the initial mint of the tokens.
+/// Create the instructions to accelerate the vesting of the investments of a list of users.
+///
+/// The users are split in as many instructions as needed, each holding at most
+/// `MAX_ACCELERATION_USERS` users.
 ///
 /// # Parameters
-/// * `admin1` - Key of the payer and first signer of the instruction,
-/// * `admin2` - Key of the second signer of the instruction,
-/// * `admin2` - Key of the third signer of the instruction,
-/// * `new_api_key` - Key that will initially be used for routine tasks,
-/// * `new_admin1` - First key for the admin `MultiSig`
-/// * `new_admin2` - Second key for the admin `MultiSig`
-/// * `new_admin3` - Third key for the admin `MultiSig`
-/// * `new_admin4` - Fourth key for the admin `MultiSig`
+/// * `admin1` - Key of the payer and first signer of the instructions,
+/// * `admin2` - Key of the second signer of the instructions,
+/// * `admin3` - Key of the third signer of the instructions,
+/// * `users` - Users whose investments are accelerated,
+/// * `kind` - Type of investments concerned (all of them if `None`),
+/// * `acceleration` - Acceleration applied.
 ///
 /// # Errors
 /// If instruction's data could not be serialized (so…never?)
-#[allow(clippy::too_many_arguments)]
-pub fn update_admin_multisig(
+pub fn accelerate_vesting(
     admin1: &Pubkey,
     admin2: &Pubkey,
     admin3: &Pubkey,
-    new_api_key: &Pubkey,
-    new_admin1: &Pubkey,
-    new_admin2: &Pubkey,
-    new_admin3: &Pubkey,
-    new_admin4: &Pubkey,
-) -> Result<Instruction, ProgramError> {
-    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
-    Ok(Instruction {
-        program_id: crate::ID,
-        accounts: vec![
-            AccountMeta::new(*admin1, true),
-            AccountMeta::new_readonly(*admin2, true),
-            AccountMeta::new_readonly(*admin3, true),
-            AccountMeta::new(admin_keys_pda, false),
-            AccountMeta::new_readonly(system_program::ID, false),
-        ],
-        data: borsh::to_vec(&BangkIcoInstruction::UpdateAdminMultisig(
-            UpdateAdminMultisigArgs {
-                api_key: *new_api_key,
-                admin1: *new_admin1,
-                admin2: *new_admin2,
-                admin3: *new_admin3,
-                admin4: *new_admin4,
-            },
-        ))?,
-    })
+    users: &[Pubkey],
+    kind: Option<UnvestingType>,
+    acceleration: Acceleration,
+) -> Result<Vec<Instruction>, ProgramError> {
+    let investments = users
+        .iter()
+        .map(|user| UserInvestmentPda::get_address(user, &crate::ID).0)
+        .collect::<Vec<_>>();
+    build_accelerate_vesting(admin1, admin2, admin3, &investments, kind, acceleration)
 }
 
-/// Create an instruction to update or create a user's investment.
+/// Create the instructions to accelerate the vesting of some tokenised investments.
+///
+/// The positions are split in as many instructions as needed, each holding at most
+/// `MAX_ACCELERATION_USERS` positions.
 ///
 /// # Parameters
-/// * `payer` - Transaction signer & fee payer,
-/// * `user` - User for whom the investment will be created / updated,
-/// * `invest_kind` - Type of investment (private sell, public sells, etc.),
-/// * `custom_rule` - Custom rule of unvesting if necessary for Advisers and Partners,
-/// * `amount` - Number of tokens bought.
+/// * `admin1` - Key of the payer and first signer of the instructions,
+/// * `admin2` - Key of the second signer of the instructions,
+/// * `admin3` - Key of the third signer of the instructions,
+/// * `positions` - Mints of the vesting positions accelerated,
+/// * `kind` - Type of investments concerned (all of them if `None`),
+/// * `acceleration` - Acceleration applied.
 ///
 /// # Errors
 /// If instruction's data could not be serialized (so…never?)
-pub fn user_investment(
-    payer: &Pubkey,
-    user: &Pubkey,
-    invest_kind: UnvestingType,
-    custom_rule: Option<UnvestingScheme>,
-    amount: u64,
-) -> Result<Instruction, ProgramError> {
+pub fn accelerate_positions(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    positions: &[Pubkey],
+    kind: Option<UnvestingType>,
+    acceleration: Acceleration,
+) -> Result<Vec<Instruction>, ProgramError> {
+    let positions = positions
+        .iter()
+        .map(|mint| VestingPositionPda::get_address(mint, &crate::ID).0)
+        .collect::<Vec<_>>();
+    build_accelerate_vesting(admin1, admin2, admin3, &positions, kind, acceleration)
+}
+
+/// Create the `AccelerateVesting` instructions for a list of investment or position PDAs.
+fn build_accelerate_vesting(
+    admin1: &Pubkey,
+    admin2: &Pubkey,
+    admin3: &Pubkey,
+    pdas: &[Pubkey],
+    kind: Option<UnvestingType>,
+    acceleration: Acceleration,
+) -> Result<Vec<Instruction>, ProgramError> {
     let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
     let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
-    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
-    Ok(Instruction {
-        program_id: crate::ID,
-        accounts: vec![
-            AccountMeta::new(*payer, true),
-            AccountMeta::new(config_pda, false),
-            AccountMeta::new_readonly(admin_keys_pda, false),
-            AccountMeta::new(investment_pda, false),
-            AccountMeta::new_readonly(system_program::ID, false),
-        ],
-        data: borsh::to_vec(&BangkIcoInstruction::UserInvestment(UserInvestmentArgs {
-            user: *user,
-            invest_kind,
-            custom_rule,
-            amount,
-        }))?,
-    })
+
+    pdas.chunks(MAX_ACCELERATION_USERS)
+        .map(|batch| {
+            let mut accounts = vec![
+                AccountMeta::new(*admin1, true),
+                AccountMeta::new_readonly(*admin2, true),
+                AccountMeta::new_readonly(*admin3, true),
+                AccountMeta::new(config_pda, false),
+                AccountMeta::new_readonly(admin_keys_pda, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ];
+            accounts.extend(batch.iter().map(|pda| AccountMeta::new(*pda, false)));
+
+            Ok(Instruction {
+                program_id: crate::ID,
+                accounts,
+                data: borsh::to_vec(&BangkIcoInstruction::AccelerateVesting(
+                    AccelerateVestingArgs { kind, acceleration },
+                ))?,
+            })
+        })
+        .collect()
 }
 
-/// Create an instruction to update or create an adviser or partner's investment after the launch.
+/// Create the instruction to accelerate the vesting of all the investments of a given type.
 ///
 /// # Parameters
 /// * `admin1` - Key of the payer and first signer of the instruction,
 /// * `admin2` - Key of the second signer of the instruction,
 /// * `admin3` - Key of the third signer of the instruction,
-/// * `user` - User for whom the investment will be created / updated,
-/// * `custom_rule` - Custom rule of unvesting if necessary,
-/// * `amount` - Number of tokens bought.
+/// * `kind` - Type of investments accelerated,
+/// * `acceleration` - Acceleration applied.
 ///
 /// # Errors
 /// If instruction's data could not be serialized (so…never?)
-pub fn queue_adviser_post_launch_investment(
+pub fn accelerate_vesting_type(
     admin1: &Pubkey,
     admin2: &Pubkey,
     admin3: &Pubkey,
-    user: &Pubkey,
-    custom_rule: Option<UnvestingScheme>,
-    amount: u64,
+    kind: UnvestingType,
+    acceleration: Acceleration,
 ) -> Result<Instruction, ProgramError> {
     let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
     let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
-    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
 
     Ok(Instruction {
         program_id: crate::ID,
@@ -444,200 +3390,246 @@ pub fn queue_adviser_post_launch_investment(
             AccountMeta::new_readonly(*admin3, true),
             AccountMeta::new(config_pda, false),
             AccountMeta::new_readonly(admin_keys_pda, false),
-            AccountMeta::new(timelock_pda, false),
             AccountMeta::new_readonly(system_program::ID, false),
         ],
-        data: borsh::to_vec(&BangkIcoInstruction::QueuePostLaunchAdvisersInvestment(
-            UserInvestmentArgs {
-                user: *user,
-                invest_kind: UnvestingType::AdvisersPartners,
-                custom_rule,
-                amount,
+        data: borsh::to_vec(&BangkIcoInstruction::AccelerateVesting(
+            AccelerateVestingArgs {
+                kind: Some(kind),
+                acceleration,
             },
         ))?,
     })
 }
 
-/// Create an instruction to update or create an adviser or partner's investment after the launch.
+/// Start keeping the history of a user's releases in a dedicated PDA.
 ///
 /// # Parameters
-/// * `admin1` - Key of the payer and first signer of the instruction,
-/// * `admin2` - Key of the second signer of the instruction,
-/// * `admin3` - Key of the third signer of the instruction,
-/// * `user` - User for whom the investment will be created / updated,
-/// * `custom_rule` - Custom rule of unvesting if necessary,
-/// * `amount` - Number of tokens bought.
+/// * `payer` - Wallet paying the transaction and the PDA's creation (can be the user),
+/// * `user` - User owning the investments, must sign the transaction.
 ///
 /// # Errors
 /// If instruction's data could not be serialized (so…never?)
-pub fn process_adviser_post_launch_investment(
-    admin1: &Pubkey,
-    user: &Pubkey,
-    custom_rule: Option<UnvestingScheme>,
-    amount: u64,
-) -> Result<Instruction, ProgramError> {
-    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
-    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
-    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
-    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+pub fn enable_release_history(payer: &Pubkey, user: &Pubkey) -> Result<Instruction, ProgramError> {
+    let (history_pda, _history_bump) = ReleaseHistoryPda::get_address(user, &crate::ID);
 
     Ok(Instruction {
         program_id: crate::ID,
         accounts: vec![
-            AccountMeta::new(*admin1, true),
-            AccountMeta::new(config_pda, false),
-            AccountMeta::new_readonly(admin_keys_pda, false),
-            AccountMeta::new(timelock_pda, false),
-            AccountMeta::new(investment_pda, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*user, true),
+            AccountMeta::new(history_pda, false),
             AccountMeta::new_readonly(system_program::ID, false),
         ],
-        data: borsh::to_vec(&BangkIcoInstruction::ProcessPostLaunchAdvisersInvestment(
-            UserInvestmentArgs {
-                user: *user,
-                invest_kind: UnvestingType::AdvisersPartners,
-                custom_rule,
-                amount,
-            },
-        ))?,
+        data: borsh::to_vec(&BangkIcoInstruction::EnableReleaseHistory)?,
     })
 }
 
-/// Cancel a user's investment.
+/// Create the instructions to compact the investment entries of a list of users.
+///
+/// The users are split in as many instructions as needed, each holding at most
+/// `MAX_COMPACTION_USERS` users.
 ///
 /// # Parameters
-/// * `payer` - Transaction signer & fee payer,
-/// * `admin` - Admin key signing the instruction,
-/// * `user` - User for whom the investment will be created / updated,
-/// * `kind` - Investment type to cancel,
-/// * `amount` - Amount to cancel.
+/// * `payer` - Wallet signing and paying the transactions,
+/// * `users` - Users whose investments are compacted.
 ///
 /// # Errors
 /// If instruction's data could not be serialized (so…never?)
-pub fn cancel_investment(
+pub fn compact_investments(
+    payer: &Pubkey,
+    users: &[Pubkey],
+) -> Result<Vec<Instruction>, ProgramError> {
+    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
+
+    users
+        .chunks(MAX_COMPACTION_USERS)
+        .map(|batch| {
+            let mut accounts = vec![
+                AccountMeta::new(*payer, true),
+                AccountMeta::new_readonly(admin_keys_pda, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ];
+            accounts.extend(batch.iter().map(|user| {
+                let (investment_pda, _investment_bump) =
+                    UserInvestmentPda::get_address(user, &crate::ID);
+                AccountMeta::new(investment_pda, false)
+            }));
+
+            Ok(Instruction {
+                program_id: crate::ID,
+                accounts,
+                data: borsh::to_vec(&BangkIcoInstruction::CompactInvestments)?,
+            })
+        })
+        .collect()
+}
+
+/// Create the instruction to move one of a user's investments to a vesting position
+/// represented by an NFT.
+///
+/// # Parameters
+/// * `payer` - Key of the payer and signer of the instruction (Bangk's API),
+/// * `user` - User owning the investment, must sign the transaction,
+/// * `index` - Position of the investment in the user's investments,
+/// * `id` - Identifier of the vesting position among the user's positions.
+///
+/// # Errors
+/// If instruction's data could not be serialized (so…never?)
+pub fn tokenize_position(
     payer: &Pubkey,
-    admin: &Pubkey,
     user: &Pubkey,
-    kind: UnvestingType,
-    amount: u64,
+    index: u32,
+    id: u8,
 ) -> Result<Instruction, ProgramError> {
-    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
     let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
     let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+    let (mint_address, _mint_bump) = get_position_mint_pda(user, id);
+    let (position_pda, _position_bump) = VestingPositionPda::get_address(mint_address, &crate::ID);
+    let user_nft_ata =
+        get_associated_token_address_with_program_id(user, &mint_address, &spl_token_2022::ID);
+
     Ok(Instruction {
         program_id: crate::ID,
         accounts: vec![
             AccountMeta::new(*payer, true),
-            AccountMeta::new_readonly(*admin, true),
-            AccountMeta::new(config_pda, false),
             AccountMeta::new_readonly(admin_keys_pda, false),
+            AccountMeta::new_readonly(*user, true),
             AccountMeta::new(investment_pda, false),
+            AccountMeta::new(position_pda, false),
+            AccountMeta::new(mint_address, false),
+            AccountMeta::new(user_nft_ata, false),
             AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token_2022::ID, false),
+            AccountMeta::new_readonly(spl_associated_token_account::ID, false),
         ],
-        data: borsh::to_vec(&BangkIcoInstruction::CancelInvestment(
-            CancelInvestmentArgs {
-                user: *user,
-                kind,
-                amount,
-            },
+        data: borsh::to_vec(&BangkIcoInstruction::TokenizePosition(
+            TokenizePositionArgs { index, id },
         ))?,
     })
 }
 
-/// Create the instruction to set the BGK token launch date.
+/// Create the instruction to release the vested tokens of a vesting position to its holder.
 ///
 /// # Parameters
-/// * `admin1` - Key of the payer and first signer of the instruction,
-/// * `admin2` - Key of the second signer of the instruction,
-/// * `admin3` - Key of the third signer of the instruction,
-/// * `timestamp` - Timestamp of the launch,
-/// * `amount` - Number of tokens to be released during the unvesting.
+/// * `payer` - Wallet paying the transaction (can be the holder),
+/// * `holder` - Wallet holding the NFT of the position, must sign the transaction,
+/// * `user` - User whose investment was tokenised (their compliance hold applies to the position),
+/// * `position_mint` - Mint of the NFT representing the position.
 ///
 /// # Errors
 /// If instruction's data could not be serialized (so…never?)
-pub fn launch_bgk(
-    admin1: &Pubkey,
-    admin2: &Pubkey,
-    admin3: &Pubkey,
-    timestamp: i64,
+pub fn claim_position(
+    payer: &Pubkey,
+    holder: &Pubkey,
+    user: &Pubkey,
+    position_mint: &Pubkey,
 ) -> Result<Instruction, ProgramError> {
-    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
-    let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
-
-    Ok(Instruction {
-        program_id: crate::ID,
-        accounts: vec![
-            AccountMeta::new(*admin1, true),
-            AccountMeta::new_readonly(*admin2, true),
-            AccountMeta::new_readonly(*admin3, true),
-            AccountMeta::new(config_pda, false),
-            AccountMeta::new_readonly(admin_keys_pda, false),
-            AccountMeta::new_readonly(system_program::ID, false),
-        ],
-        data: borsh::to_vec(&BangkIcoInstruction::LaunchBGK(LaunchBGKArgs { timestamp }))?,
-    })
+    build_claim_position(
+        payer,
+        holder,
+        user,
+        position_mint,
+        ClaimVestedArgs { max_amount: None },
+    )
 }
 
-/// Create the instruction to release vested tokens.
+/// Create the instruction to release part of the vested tokens of a vesting position to its holder.
 ///
 /// # Parameters
-/// * `payer` - Wallet signing and paying the transaction.
-/// * `user` - User for whom the tokens will be released.
+/// * `payer` - Wallet paying the transaction (can be the holder),
+/// * `holder` - Wallet holding the NFT of the position, must sign the transaction,
+/// * `user` - User whose investment was tokenised (their compliance hold applies to the position),
+/// * `position_mint` - Mint of the NFT representing the position,
+/// * `max_amount` - Maximum number of tokens to claim.
 ///
 /// # Errors
 /// If instruction's data could not be serialized (so…never?)
-pub fn vesting_release(payer: &Pubkey, user: &Pubkey) -> Result<Instruction, ProgramError> {
+pub fn claim_position_partially(
+    payer: &Pubkey,
+    holder: &Pubkey,
+    user: &Pubkey,
+    position_mint: &Pubkey,
+    max_amount: u64,
+) -> Result<Instruction, ProgramError> {
+    build_claim_position(
+        payer,
+        holder,
+        user,
+        position_mint,
+        ClaimVestedArgs {
+            max_amount: Some(max_amount),
+        },
+    )
+}
+
+/// Create a `ClaimPosition` instruction.
+fn build_claim_position(
+    payer: &Pubkey,
+    holder: &Pubkey,
+    user: &Pubkey,
+    position_mint: &Pubkey,
+    args: ClaimVestedArgs,
+) -> Result<Instruction, ProgramError> {
     let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
     let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
     let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
-    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
     let invested_pda = WalletType::Ico.get_pda().0;
-    let user_ata =
-        get_associated_token_address_with_program_id(user, &mint_address, &spl_token_2022::ID);
+    let (position_pda, _position_bump) = VestingPositionPda::get_address(position_mint, &crate::ID);
+    let holder_nft_ata =
+        get_associated_token_address_with_program_id(holder, position_mint, &spl_token_2022::ID);
+    let holder_ata =
+        get_associated_token_address_with_program_id(holder, &mint_address, &spl_token_2022::ID);
+    let (kyc_pda, _kyc_bump) = KycAttestationPda::get_address(holder, &crate::ID);
+    let (denylist_pda, _denylist_bump) =
+        DenylistPda::get_address(get_denylist_shard(holder), &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+    let (holder_investment_pda, _holder_investment_bump) =
+        UserInvestmentPda::get_address(holder, &crate::ID);
+    let (history_pda, _history_bump) = ReleaseHistoryPda::get_address(holder, &crate::ID);
 
     Ok(Instruction {
         program_id: crate::ID,
         accounts: vec![
             AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*holder, true),
             AccountMeta::new_readonly(config_pda, false),
             AccountMeta::new_readonly(admin_keys_pda, false),
             AccountMeta::new_readonly(mint_address, false),
             AccountMeta::new(invested_pda, false),
-            AccountMeta::new_readonly(*user, false),
-            AccountMeta::new(investment_pda, false),
-            AccountMeta::new(user_ata, false),
+            AccountMeta::new(position_pda, false),
+            AccountMeta::new_readonly(holder_nft_ata, false),
+            AccountMeta::new(holder_ata, false),
             AccountMeta::new_readonly(system_program::ID, false),
             AccountMeta::new_readonly(spl_token_2022::ID, false),
             AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            AccountMeta::new_readonly(kyc_pda, false),
+            AccountMeta::new_readonly(denylist_pda, false),
+            AccountMeta::new(investment_pda, false),
+            AccountMeta::new_readonly(holder_investment_pda, false),
+            AccountMeta::new(history_pda, false),
         ],
-        data: borsh::to_vec(&BangkIcoInstruction::VestingRelease)?,
+        data: borsh::to_vec(&BangkIcoInstruction::ClaimPosition(args))?,
     })
 }
 
-/// Queues an instruction to transfer tokens from the reserve.
+/// Create the instruction to define (or disable) the transfers of the vesting positions.
 ///
 /// # Parameters
 /// * `admin1` - Key of the payer and first signer of the instruction,
 /// * `admin2` - Key of the second signer of the instruction,
-/// * `admin2` - Key of the third signer of the instruction,
-/// * `target` - Target ATA (created if doesn't exist yet),
-/// * `source` - The source internal wallet (community, marketing, etc.)
-/// * `amount` - Number of tokens to transfer.
+/// * `admin3` - Key of the third signer of the instruction,
+/// * `policy` - Restrictions on the transfers (`None` to disable them).
 ///
 /// # Errors
 /// If instruction's data could not be serialized (so…never?)
-pub fn queue_transfer_from_internal_wallet(
+pub fn set_position_transfer_policy(
     admin1: &Pubkey,
     admin2: &Pubkey,
     admin3: &Pubkey,
-    target: &Pubkey,
-    source: WalletType,
-    amount: u64,
+    policy: Option<PositionTransferPolicy>,
 ) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
     let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
-    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
-    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
-    let target_ata =
-        get_associated_token_address_with_program_id(target, &mint_address, &spl_token_2022::ID);
 
     Ok(Instruction {
         program_id: crate::ID,
@@ -645,58 +3637,68 @@ pub fn queue_transfer_from_internal_wallet(
             AccountMeta::new(*admin1, true),
             AccountMeta::new_readonly(*admin2, true),
             AccountMeta::new_readonly(*admin3, true),
+            AccountMeta::new(config_pda, false),
             AccountMeta::new_readonly(admin_keys_pda, false),
-            AccountMeta::new(timelock_pda, false),
             AccountMeta::new_readonly(system_program::ID, false),
         ],
-        data: borsh::to_vec(&BangkIcoInstruction::QueueTransferFromInternalWallet(
-            QueueTransferFromInternalWalletArgs {
-                target: target_ata,
-                amount,
-                source,
-            },
+        data: borsh::to_vec(&BangkIcoInstruction::SetPositionTransferPolicy(
+            SetPositionTransferPolicyArgs { policy },
         ))?,
     })
 }
 
-/// Create the instruction to execute a time-locked transfer
+/// Create the instruction to transfer the NFT of a vesting position.
 ///
 /// # Parameters
-/// * `target` - Target ATA (created if doesn't exist yet),
-/// * `source` - The source internal wallet (community, marketing, etc.)
-/// * `amount` - Number of tokens to be released during the unvesting.
+/// * `payer` - Wallet paying the transaction and the recipient's ATA (can be the holder),
+/// * `holder` - Wallet holding the NFT of the position, must sign the transaction,
+/// * `recipient` - Wallet receiving the NFT,
+/// * `user` - User whose investment was tokenised (their compliance hold applies to the position),
+/// * `position_mint` - Mint of the NFT representing the position.
 ///
 /// # Errors
 /// If instruction's data could not be serialized (so…never?)
-pub fn execute_transfer_from_internal_wallet(
+pub fn transfer_position(
     payer: &Pubkey,
-    target: &Pubkey,
-    source: WalletType,
-    amount: u64,
+    holder: &Pubkey,
+    recipient: &Pubkey,
+    user: &Pubkey,
+    position_mint: &Pubkey,
 ) -> Result<Instruction, ProgramError> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&crate::ID);
     let (admin_keys_pda, _admin_bump) = MultiSigPda::get_address(MultiSigType::Admin, &crate::ID);
-    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
-    let (timelock_pda, _timelock_bump) = TimelockPda::get_address(&crate::ID);
-    let source_pda = source.get_pda().0;
-    let target_ata =
-        get_associated_token_address_with_program_id(target, &mint_address, &spl_token_2022::ID);
+    let (position_pda, _position_bump) = VestingPositionPda::get_address(position_mint, &crate::ID);
+    let holder_nft_ata =
+        get_associated_token_address_with_program_id(holder, position_mint, &spl_token_2022::ID);
+    let recipient_nft_ata =
+        get_associated_token_address_with_program_id(recipient, position_mint, &spl_token_2022::ID);
+    let (kyc_pda, _kyc_bump) = KycAttestationPda::get_address(recipient, &crate::ID);
+    let (denylist_pda, _denylist_bump) =
+        DenylistPda::get_address(get_denylist_shard(recipient), &crate::ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &crate::ID);
+    let (holder_investment_pda, _holder_investment_bump) =
+        UserInvestmentPda::get_address(holder, &crate::ID);
 
     Ok(Instruction {
         program_id: crate::ID,
         accounts: vec![
             AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*holder, true),
+            AccountMeta::new_readonly(*recipient, false),
+            AccountMeta::new_readonly(config_pda, false),
             AccountMeta::new_readonly(admin_keys_pda, false),
-            AccountMeta::new(timelock_pda, false),
-            AccountMeta::new(mint_address, false),
-            AccountMeta::new(source_pda, false),
-            AccountMeta::new_readonly(*target, false),
-            AccountMeta::new(target_ata, false),
+            AccountMeta::new(position_pda, false),
+            AccountMeta::new_readonly(*position_mint, false),
+            AccountMeta::new(holder_nft_ata, false),
+            AccountMeta::new(recipient_nft_ata, false),
+            AccountMeta::new_readonly(kyc_pda, false),
+            AccountMeta::new_readonly(denylist_pda, false),
             AccountMeta::new_readonly(system_program::ID, false),
             AccountMeta::new_readonly(spl_token_2022::ID, false),
             AccountMeta::new_readonly(spl_associated_token_account::ID, false),
+            AccountMeta::new_readonly(investment_pda, false),
+            AccountMeta::new_readonly(holder_investment_pda, false),
         ],
-        data: borsh::to_vec(&BangkIcoInstruction::ExecuteTransferFromInternalWallet(
-            ExecuteTransferFromInternalWalletArgs { source, amount },
-        ))?,
+        data: borsh::to_vec(&BangkIcoInstruction::TransferPosition)?,
     })
 }
@@ -3,23 +3,120 @@
 // Creation date: Monday 17 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Wednesday 28 August 2024 @ 19:22:48
+// Last modified: Sunday 18 October 2026 @ 16:28:19
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
 
+use std::collections::HashMap;
+
 use bangk_macro::pda;
 use bangk_onchain_common::{
-    get_timestamp,
+    debug, get_timestamp,
     pda::{BangkPda, PdaType},
     Error,
 };
 use borsh::{BorshDeserialize, BorshSerialize};
 use serde::{Deserialize, Serialize};
 use shank::ShankType;
-use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use solana_program::{
+    account_info::AccountInfo, keccak, msg, program_error::ProgramError, pubkey::Pubkey,
+};
+
+use crate::{
+    history::ReleasedEntry,
+    unvesting::{UnvestingScheme, UnvestingType},
+};
+
+/// Details of the payment of an investment, kept for the accounting and the tax reporting.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub struct PaymentMetadata {
+    /// ISO 4217 code of the fiat currency of the payment (`*b"EUR"` for example).
+    pub currency: [u8; 3],
+    /// Price of one BGK (1e6 tokens) in the smallest unit of the currency.
+    pub unit_price: u64,
+    /// Total paid in the smallest unit of the currency.
+    pub total_paid: u64,
+    /// Hash of the external reference of the payment (bank transfer, invoice, etc.).
+    pub reference_hash: [u8; 32],
+}
 
-use crate::unvesting::{UnvestingScheme, UnvestingType};
+impl PaymentMetadata {
+    /// Checks if payment metadata seem valid.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if !self.currency.iter().all(u8::is_ascii_uppercase) {
+            msg!("invalid currency code for the payment: {:?}", self.currency);
+            return false;
+        }
+        true
+    }
+}
+
+/// Link between an investment and the tokens granted for it (bonus, referral reward), so that
+/// they are cancelled together.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub enum InvestmentLink {
+    /// Tokens were granted for this investment, which is identified by its reference.
+    Origin {
+        /// Referrer rewarded for the investment (if any).
+        referrer: Option<Pubkey>,
+    },
+    /// Tokens granted for the investment of another entry.
+    Granted {
+        /// User who made the investment.
+        user: Pubkey,
+        /// Reference of the investment.
+        reference: [u8; 32],
+    },
+}
+
+/// Get the reference of an investment recorded without one, so that the tokens granted for it
+/// can be linked to it.
+///
+/// # Parameters
+/// * `user` - User who made the investment,
+/// * `timestamp` - Timestamp at which the investment has been done,
+/// * `index` - Position of the investment among the user's investments.
+#[must_use]
+#[allow(clippy::little_endian_bytes)]
+pub fn get_investment_reference(user: &Pubkey, timestamp: i64, index: u32) -> [u8; 32] {
+    keccak::hashv(&[
+        b"Investment",
+        user.as_ref(),
+        &timestamp.to_le_bytes(),
+        &index.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Maximum number of investment entries a user can have.
+///
+/// The entries are compacted when it is reached, so it only limits the entries that can't be
+/// merged together.
+pub const MAX_INVESTMENT_ENTRIES: usize = 64;
 
 /// Definition of a user's ICO investment.
 #[derive(
@@ -45,6 +142,334 @@ pub struct Investment {
     pub amount_bought: u64,
     /// Number of tokens already released.
     pub amount_released: u64,
+    /// If set, the whole amount bought can be released regardless of the unvesting scheme
+    /// (after its vesting was stopped by a clawback for example).
+    pub fully_vested: bool,
+    /// Idempotency key given when recording the investment (external payment reference for example),
+    /// or reference of the on-chain purchase that created it (generated if tokens were granted for it).
+    pub reference: Option<[u8; 32]>,
+    /// Details of the payment of the investment (if known).
+    pub payment: Option<PaymentMetadata>,
+    /// If set, the vesting can be revoked by a leaver event (team members' contracts).
+    pub revocable: bool,
+    /// Percentage (x1000 factor) unlocked on top of the unvesting scheme by acceleration events.
+    pub accelerated: u32,
+    /// Link with the tokens granted for the investment, or with the investment they were granted for.
+    pub link: Option<InvestmentLink>,
+}
+
+impl Investment {
+    /// Create a new investment.
+    ///
+    /// # Parameters
+    /// * `kind` - Type of unvesting for the investment,
+    /// * `amount` - Amount of tokens bought,
+    /// * `custom_rule` - Custom unvesting rule if necessary.
+    ///
+    /// # Errors
+    /// If the custom rule is given but invalid, or if the timestamp couldn't be retrieved.
+    pub fn new(
+        kind: UnvestingType,
+        amount: u64,
+        custom_rule: Option<UnvestingScheme>,
+    ) -> Result<Self, Error> {
+        match custom_rule {
+            None => (),
+            Some(rule) => {
+                if rule.kind != kind || !rule.is_valid().unwrap_or(false) {
+                    return Err(Error::InvalidUnvestingDefinition);
+                }
+            }
+        }
+        Ok(Self {
+            kind,
+            timestamp: get_timestamp()?,
+            custom_rule,
+            amount_bought: amount,
+            amount_released: 0,
+            fully_vested: false,
+            reference: None,
+            payment: None,
+            revocable: false,
+            accelerated: 0,
+            link: None,
+        })
+    }
+
+    /// Get the number of tokens of this investment that are unvested at a given time.
+    ///
+    /// # Parameters
+    /// * `unvesting` - The default unvesting schemes,
+    /// * `accelerated_per_kind` - Acceleration of the vesting of each type of investment,
+    /// * `launch` - Timestamp of the BGK launch,
+    /// * `now` - Timestamp at which the computation is done.
+    ///
+    /// # Errors
+    /// If there is no unvesting scheme for the investment or the computation failed.
+    pub fn unvested(
+        &self,
+        unvesting: &HashMap<UnvestingType, UnvestingScheme>,
+        accelerated_per_kind: &HashMap<UnvestingType, u32>,
+        launch: i64,
+        now: i64,
+    ) -> Result<u64, Error> {
+        if self.fully_vested {
+            return Ok(self.amount_bought);
+        }
+        if now < launch {
+            return Ok(0);
+        }
+        let rule = match self.custom_rule {
+            Some(rule) => rule,
+            None => *unvesting
+                .get(&self.kind)
+                .ok_or(Error::InvalidUnvestingDefinition)?,
+        };
+        Ok(rule
+            .unvested(launch, now)?
+            .saturating_add(u64::from(self.accelerated))
+            .saturating_add(u64::from(
+                accelerated_per_kind
+                    .get(&self.kind)
+                    .copied()
+                    .unwrap_or_default(),
+            ))
+            .min(100_000)
+            .saturating_mul(self.amount_bought)
+            .saturating_div(100_000))
+    }
+
+    /// Checks if another investment can be merged into this one without changing the vesting.
+    ///
+    /// Both must follow the same unvesting scheme in the same state, and carry no idempotency
+    /// key, payment details or link, which would be lost by the merge.
+    ///
+    /// # Parameters
+    /// * `other` - The investment to merge.
+    #[must_use]
+    pub fn can_merge(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.custom_rule == other.custom_rule
+            && self.fully_vested == other.fully_vested
+            && self.revocable == other.revocable
+            && self.accelerated == other.accelerated
+            && self.reference.is_none()
+            && other.reference.is_none()
+            && self.payment.is_none()
+            && other.payment.is_none()
+            && self.link.is_none()
+            && other.link.is_none()
+    }
+
+    /// Checks if the tokens were granted for another investment (bonus, referral reward).
+    #[must_use]
+    pub const fn is_granted(&self) -> bool {
+        matches!(self.link, Some(InvestmentLink::Granted { .. }))
+    }
+
+    /// Accelerate the vesting of the investment.
+    ///
+    /// # Parameters
+    /// * `acceleration` - The acceleration to apply.
+    ///
+    /// # Returns
+    /// `false` if the investment was already fully vested.
+    pub fn accelerate(&mut self, acceleration: Acceleration) -> bool {
+        if self.fully_vested {
+            return false;
+        }
+        match acceleration {
+            Acceleration::Full => self.fully_vested = true,
+            Acceleration::Extra(rate) => {
+                self.accelerated = self.accelerated.saturating_add(rate).min(100_000);
+            }
+        }
+        true
+    }
+
+    /// Stop the vesting of the investment as of a given date.
+    ///
+    /// The investment is cut down to what was unvested at that date (or to what was already
+    /// released if that's more), and all of it is then available for release.
+    ///
+    /// # Parameters
+    /// * `unvesting` - The default unvesting schemes,
+    /// * `accelerated_per_kind` - Acceleration of the vesting of each type of investment,
+    /// * `launch` - Timestamp of the BGK launch,
+    /// * `date` - Timestamp as of which the vesting is stopped.
+    ///
+    /// # Returns
+    /// The number of tokens removed from the investment.
+    ///
+    /// # Errors
+    /// If there is no unvesting scheme for the investment or the computation failed.
+    pub fn stop_vesting(
+        &mut self,
+        unvesting: &HashMap<UnvestingType, UnvestingScheme>,
+        accelerated_per_kind: &HashMap<UnvestingType, u32>,
+        launch: i64,
+        date: i64,
+    ) -> Result<u64, Error> {
+        let kept = self
+            .unvested(unvesting, accelerated_per_kind, launch, date)?
+            .max(self.amount_released);
+        let removed = self.amount_bought.saturating_sub(kept);
+        self.amount_bought = kept;
+        self.fully_vested = true;
+        Ok(removed)
+    }
+
+    /// Apply a leaver event to a revocable investment, stopping its vesting.
+    ///
+    /// # Parameters
+    /// * `unvesting` - The default unvesting schemes,
+    /// * `accelerated_per_kind` - Acceleration of the vesting of each type of investment,
+    /// * `launch` - Timestamp of the BGK launch,
+    /// * `date` - Timestamp at which the user left,
+    /// * `policy` - What the user keeps of the investment.
+    ///
+    /// # Returns
+    /// The number of tokens forfeited.
+    ///
+    /// # Errors
+    /// If the investment is not revocable, if there is no unvesting scheme for it or the
+    /// computation failed.
+    pub fn revoke(
+        &mut self,
+        unvesting: &HashMap<UnvestingType, UnvestingScheme>,
+        accelerated_per_kind: &HashMap<UnvestingType, u32>,
+        launch: i64,
+        date: i64,
+        policy: LeaverPolicy,
+    ) -> Result<u64, Error> {
+        const WEEK_S: i64 = 86_400 * 7;
+
+        if !self.revocable {
+            return Err(Error::InvalidOperation);
+        }
+        let cutoff = match policy {
+            LeaverPolicy::KeepVested => date,
+            LeaverPolicy::KeepVestedPlusWeeks(weeks) => {
+                date.saturating_add(i64::from(weeks).saturating_mul(WEEK_S))
+            }
+            // Nothing is vested before the launch, so only the released tokens are kept
+            LeaverPolicy::Forfeit => i64::MIN,
+        };
+        self.stop_vesting(unvesting, accelerated_per_kind, launch, cutoff)
+    }
+}
+
+/// Hold on the releases of a user's tokens, the vesting going on in the meantime.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub struct ComplianceHold {
+    /// Reason of the hold (code defined by the compliance team).
+    pub reason: u16,
+    /// Timestamp at which the hold expires.
+    pub expiry: i64,
+}
+
+/// Acceleration of the vesting granted on an event (change of control, listing, etc.).
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub enum Acceleration {
+    /// Everything is unlocked.
+    Full,
+    /// A given percentage (x1000 factor) is unlocked on top of the unvesting scheme.
+    Extra(u32),
+}
+
+impl Acceleration {
+    /// Checks if the acceleration is valid.
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        match self {
+            Self::Full => true,
+            Self::Extra(rate) => *rate > 0 && *rate <= 100_000,
+        }
+    }
+}
+
+/// What a team member keeps of their revocable investments when they leave.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub enum LeaverPolicy {
+    /// The tokens vested at the date of departure are kept (good leaver).
+    KeepVested,
+    /// The tokens vested a given number of weeks after the date of departure are kept.
+    KeepVestedPlusWeeks(u8),
+    /// Everything that wasn't released yet is forfeited (bad leaver).
+    Forfeit,
+}
+
+/// Caps on the investments of a given type.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub struct InvestmentCap {
+    /// Type of investment concerned.
+    pub kind: UnvestingType,
+    /// Maximum number of tokens invested by all the users (no limit if `None`).
+    pub total: Option<u64>,
+    /// Maximum number of tokens invested by a single user (no limit if `None`).
+    pub per_user: Option<u64>,
+}
+
+impl InvestmentCap {
+    /// Checks if an investment cap definition seems valid.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if self.total == Some(0)
+            || self.per_user == Some(0)
+            || self
+                .total
+                .zip(self.per_user)
+                .is_some_and(|(total, per_user)| per_user > total)
+        {
+            msg!("investment cap definition invalid: {:?}", self);
+            return false;
+        }
+        true
+    }
 }
 
 /// Stores the data for a user's investments.
@@ -54,6 +479,8 @@ pub struct UserInvestment {
     pub user: Pubkey,
     /// Definition of the investments from the user.
     pub investments: Vec<Investment>,
+    /// Wallet receiving the released tokens in place of the user (if any).
+    pub beneficiary: Option<Pubkey>,
 }
 
 impl UserInvestment {
@@ -73,25 +500,237 @@ impl UserInvestment {
         amount: u64,
         custom_rule: Option<UnvestingScheme>,
     ) -> Result<Self, ProgramError> {
-        match custom_rule {
-            None => (),
-            Some(rule) => {
-                if rule.kind != kind || !rule.is_valid().unwrap_or(false) {
-                    return Err(Error::InvalidUnvestingDefinition.into());
-                }
-            }
-        }
         Ok(Self {
             user,
-            investments: vec![Investment {
-                kind,
-                timestamp: get_timestamp()?,
-                custom_rule,
-                amount_bought: amount,
-                amount_released: 0,
-            }],
+            investments: vec![Investment::new(kind, amount, custom_rule)?],
+            beneficiary: None,
         })
     }
+
+    /// Add investments to the user's investments.
+    ///
+    /// If the maximum number of entries is exceeded, the entries are compacted first.
+    ///
+    /// # Parameters
+    /// * `investments` - The investments to add.
+    ///
+    /// # Errors
+    /// If there are still too many entries after the compaction, or if the computation failed.
+    pub fn add(&mut self, investments: &[Investment]) -> Result<(), Error> {
+        self.investments.extend_from_slice(investments);
+        if self.investments.len() > MAX_INVESTMENT_ENTRIES {
+            self.compact()?;
+        }
+        if self.investments.len() > MAX_INVESTMENT_ENTRIES {
+            msg!(
+                "{} has {} investment entries, the maximum being {}",
+                self.user,
+                self.investments.len(),
+                MAX_INVESTMENT_ENTRIES
+            );
+            return Err(Error::TooManyInvestmentEntries);
+        }
+        Ok(())
+    }
+
+    /// Merge the investment entries that can be merged (see [`Investment::can_merge`]).
+    ///
+    /// The amounts bought and released are summed, the merged entry keeping the position and
+    /// the earliest timestamp of its entries.
+    ///
+    /// # Returns
+    /// The number of entries removed.
+    ///
+    /// # Errors
+    /// If the computation failed.
+    pub fn compact(&mut self) -> Result<usize, Error> {
+        let before = self.investments.len();
+        let mut compacted: Vec<Investment> = Vec::with_capacity(before);
+        for invest in &self.investments {
+            match compacted.iter_mut().find(|elt| elt.can_merge(invest)) {
+                Some(elt) => {
+                    elt.amount_bought = elt
+                        .amount_bought
+                        .checked_add(invest.amount_bought)
+                        .ok_or(Error::IntegerOverflow)?;
+                    elt.amount_released = elt
+                        .amount_released
+                        .checked_add(invest.amount_released)
+                        .ok_or(Error::IntegerOverflow)?;
+                    elt.timestamp = elt.timestamp.min(invest.timestamp);
+                }
+                None => compacted.push(*invest),
+            }
+        }
+        self.investments = compacted;
+        Ok(before.saturating_sub(self.investments.len()))
+    }
+
+    /// Get the wallet receiving the released tokens.
+    #[must_use]
+    pub fn destination(&self) -> Pubkey {
+        self.beneficiary.unwrap_or(self.user)
+    }
+
+    /// Checks if an investment was already recorded with a given idempotency key.
+    ///
+    /// # Parameters
+    /// * `reference` - Idempotency key of the investment.
+    #[must_use]
+    pub fn has_reference(&self, reference: &[u8; 32]) -> bool {
+        self.investments
+            .iter()
+            .any(|invest| invest.reference.as_ref() == Some(reference))
+    }
+
+    /// Cancel the tokens granted for an investment (bonus, referral reward) in the same
+    /// proportion as the investment itself.
+    ///
+    /// # Parameters
+    /// * `user` - User who made the investment,
+    /// * `reference` - Reference of the investment,
+    /// * `amount_before` - Number of tokens of the investment before its cancellation,
+    /// * `cancelled` - Number of tokens of the investment cancelled.
+    ///
+    /// # Returns
+    /// The number of granted tokens cancelled per type of investment.
+    ///
+    /// # Errors
+    /// If the computation failed.
+    pub fn cancel_granted(
+        &mut self,
+        user: &Pubkey,
+        reference: &[u8; 32],
+        amount_before: u64,
+        cancelled: u64,
+    ) -> Result<Vec<(UnvestingType, u64)>, Error> {
+        let link = Some(InvestmentLink::Granted {
+            user: *user,
+            reference: *reference,
+        });
+        let mut removed = Vec::new();
+        for invest in self
+            .investments
+            .iter_mut()
+            .filter(|invest| invest.link == link)
+        {
+            let amount = if cancelled >= amount_before {
+                invest.amount_bought
+            } else {
+                let amount = u128::from(invest.amount_bought)
+                    .checked_mul(u128::from(cancelled))
+                    .and_then(|total| total.checked_div(u128::from(amount_before)))
+                    .ok_or(Error::IntegerOverflow)?;
+                u64::try_from(amount).map_err(|_err| Error::IntegerOverflow)?
+            };
+            if amount > 0 {
+                invest.amount_bought = invest.amount_bought.saturating_sub(amount);
+                removed.push((invest.kind, amount));
+            }
+        }
+        self.investments
+            .retain(|invest| invest.link != link || invest.amount_bought > 0);
+        Ok(removed)
+    }
+
+    /// Get the number of tokens invested by the user in a type of investment.
+    ///
+    /// # Parameters
+    /// * `kind` - Type of investment.
+    #[must_use]
+    pub fn invested(&self, kind: UnvestingType) -> u64 {
+        self.investments
+            .iter()
+            .filter(|invest| invest.kind == kind)
+            .fold(0_u64, |total, invest| {
+                total.saturating_add(invest.amount_bought)
+            })
+    }
+
+    /// Update the released amounts of the investments.
+    ///
+    /// If the released amount is limited, the oldest investments are released first
+    /// (in the order they were recorded for investments made at the same time),
+    /// and the rest stays available for a later release.
+    ///
+    /// # Parameters
+    /// * `unvesting` - The default unvesting schemes,
+    /// * `accelerated_per_kind` - Acceleration of the vesting of each type of investment,
+    /// * `launch` - Timestamp of the BGK launch,
+    /// * `now` - Current timestamp,
+    /// * `max_amount` - Maximum number of tokens to release (everything available if `None`).
+    ///
+    /// # Returns
+    /// The number of tokens that must be transferred to the user.
+    ///
+    /// # Errors
+    /// If there is no unvesting scheme for one of the investments or the computation failed.
+    pub fn release(
+        &mut self,
+        unvesting: &HashMap<UnvestingType, UnvestingScheme>,
+        accelerated_per_kind: &HashMap<UnvestingType, u32>,
+        launch: i64,
+        now: i64,
+        max_amount: Option<u64>,
+    ) -> Result<u64, Error> {
+        Ok(self
+            .release_entries(unvesting, accelerated_per_kind, launch, now, max_amount)?
+            .iter()
+            .fold(0_u64, |total, entry| total.saturating_add(entry.amount)))
+    }
+
+    /// Update the released amounts of the investments, detailing what was released from each.
+    ///
+    /// Same as [`UserInvestment::release`].
+    ///
+    /// # Parameters
+    /// * `unvesting` - The default unvesting schemes,
+    /// * `accelerated_per_kind` - Acceleration of the vesting of each type of investment,
+    /// * `launch` - Timestamp of the BGK launch,
+    /// * `now` - Current timestamp,
+    /// * `max_amount` - Maximum number of tokens to release (everything available if `None`).
+    ///
+    /// # Returns
+    /// The investments from which tokens were released, in the release order.
+    ///
+    /// # Errors
+    /// If there is no unvesting scheme for one of the investments or the computation failed.
+    pub fn release_entries(
+        &mut self,
+        unvesting: &HashMap<UnvestingType, UnvestingScheme>,
+        accelerated_per_kind: &HashMap<UnvestingType, u32>,
+        launch: i64,
+        now: i64,
+        max_amount: Option<u64>,
+    ) -> Result<Vec<ReleasedEntry>, Error> {
+        let mut order = (0..self.investments.len()).collect::<Vec<_>>();
+        order.sort_by_key(|idx| self.investments[*idx].timestamp);
+
+        let mut remaining = max_amount.unwrap_or(u64::MAX);
+        let mut entries = Vec::new();
+        for idx in order {
+            let invest = &mut self.investments[idx];
+            let rule_available = invest
+                .unvested(unvesting, accelerated_per_kind, launch, now)?
+                .saturating_sub(invest.amount_released);
+            debug!(
+                "Rule {:?} has {} tokens available",
+                invest.kind, rule_available
+            );
+            let released = rule_available.min(remaining);
+            if released == 0 {
+                continue;
+            }
+            invest.amount_released = invest.amount_released.saturating_add(released);
+            remaining = remaining.saturating_sub(released);
+            entries.push(ReleasedEntry {
+                index: u32::try_from(idx).map_err(|_err| Error::IntegerOverflow)?,
+                kind: invest.kind,
+                amount: released,
+            });
+        }
+        Ok(entries)
+    }
 }
 
 /// PDA for a `UserInvestment`.
@@ -99,6 +738,13 @@ impl UserInvestment {
 pub struct UserInvestmentPda {
     /// Investment data
     pub investment: UserInvestment,
+    /// Wallet that paid for the account's creation (refunded when the account is closed).
+    pub payer: Pubkey,
+    /// Hold on the releases for a compliance investigation (if any).
+    pub hold: Option<ComplianceHold>,
+    /// Number of the user's investments tokenised as vesting positions that weren't fully released
+    /// (the account must be kept for their compliance hold).
+    pub positions: u32,
 }
 
 impl<'a> UserInvestmentPda<'a> {
@@ -106,14 +752,540 @@ impl<'a> UserInvestmentPda<'a> {
     ///
     /// # Parameters
     /// * `bump` - Bump used to derive the PDA address,
-    /// * `investment` - Definition of the `UserInvestment`.
+    /// * `investment` - Definition of the `UserInvestment`,
+    /// * `payer` - Wallet paying for the account's creation.
     #[must_use]
-    pub const fn new(bump: u8, investment: UserInvestment) -> Self {
+    pub const fn new(bump: u8, investment: UserInvestment, payer: Pubkey) -> Self {
         Self {
             pda_type: Self::PDA_TYPE,
             bump,
             account: None,
             investment,
+            payer,
+            hold: None,
+            positions: 0,
         }
     }
+
+    /// Checks if the releases are on hold at a given time.
+    ///
+    /// # Parameters
+    /// * `now` - Current timestamp.
+    #[must_use]
+    pub fn is_on_hold(&self, now: i64) -> bool {
+        self.hold.is_some_and(|hold| hold.expiry > now)
+    }
+
+    /// Checks if all the tokens of all the investments have been released.
+    #[must_use]
+    pub fn is_fully_released(&self) -> bool {
+        self.investment
+            .investments
+            .iter()
+            .all(|invest| invest.amount_released >= invest.amount_bought)
+    }
+
+    /// Loads a PDA stored with the layout of the v1.0.0 of the program.
+    ///
+    /// # Parameters
+    /// * `account` - Account from which to read the data,
+    /// * `payer` - Wallet recorded as the payer of the account (not stored in the legacy layout).
+    ///
+    /// # Errors
+    /// If the given account does not contain the expected data.
+    pub fn from_legacy_account(
+        account: &AccountInfo<'a>,
+        payer: Pubkey,
+    ) -> Result<Self, ProgramError> {
+        let data = account.try_borrow_data()?;
+        let mut res = Self::from_legacy_slice(&data, payer)?;
+        res.account = Some(account.clone());
+        Ok(res)
+    }
+
+    /// Converts data stored with the layout of the v1.0.0 of the program.
+    ///
+    /// # Parameters
+    /// * `data` - Data of the account,
+    /// * `payer` - Wallet recorded as the payer of the account (not stored in the legacy layout).
+    ///
+    /// # Errors
+    /// If the data does not match the legacy layout.
+    pub fn from_legacy_slice(data: &[u8], payer: Pubkey) -> Result<Self, Error> {
+        let legacy =
+            LegacyUserInvestmentPda::try_from_slice(data).map_err(|_err| Error::InvalidRawData)?;
+        if legacy.pda_type != Self::PDA_TYPE {
+            return Err(Error::InvalidPdaType);
+        }
+        Ok(Self::new(
+            legacy.bump,
+            UserInvestment {
+                user: legacy.user,
+                investments: legacy.investments.into_iter().map(Into::into).collect(),
+                beneficiary: None,
+            },
+            payer,
+        ))
+    }
+}
+
+/// Layout of an investment in the v1.0.0 of the program.
+#[derive(BorshSerialize, BorshDeserialize)]
+struct LegacyInvestment {
+    kind: UnvestingType,
+    timestamp: i64,
+    custom_rule: Option<UnvestingScheme>,
+    amount_bought: u64,
+    amount_released: u64,
+}
+
+impl From<LegacyInvestment> for Investment {
+    fn from(legacy: LegacyInvestment) -> Self {
+        Self {
+            kind: legacy.kind,
+            timestamp: legacy.timestamp,
+            custom_rule: legacy.custom_rule,
+            amount_bought: legacy.amount_bought,
+            amount_released: legacy.amount_released,
+            fully_vested: false,
+            reference: None,
+            payment: None,
+            revocable: false,
+            accelerated: 0,
+            link: None,
+        }
+    }
+}
+
+/// Layout of a `UserInvestmentPda` in the v1.0.0 of the program.
+#[derive(BorshSerialize, BorshDeserialize)]
+struct LegacyUserInvestmentPda {
+    pda_type: PdaType,
+    bump: u8,
+    user: Pubkey,
+    investments: Vec<LegacyInvestment>,
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::*;
+
+    fn legacy_data(pda_type: PdaType) -> (Pubkey, Vec<u8>) {
+        let user = Pubkey::new_unique();
+        let legacy = LegacyUserInvestmentPda {
+            pda_type,
+            bump: 254,
+            user,
+            investments: vec![
+                LegacyInvestment {
+                    kind: UnvestingType::PrivateSells,
+                    timestamp: 1_700_000_000,
+                    custom_rule: None,
+                    amount_bought: 1_000_000,
+                    amount_released: 250_000,
+                },
+                LegacyInvestment {
+                    kind: UnvestingType::AdvisersPartners,
+                    timestamp: 1_700_000_001,
+                    custom_rule: Some(UnvestingScheme {
+                        kind: UnvestingType::AdvisersPartners,
+                        start: 1,
+                        duration: 10,
+                        initial_unvesting: 20_000,
+                        weekly_unvesting: 10_000,
+                        final_unvesting: 0,
+                    }),
+                    amount_bought: 2_000_000,
+                    amount_released: 0,
+                },
+            ],
+        };
+        (user, borsh::to_vec(&legacy).unwrap())
+    }
+
+    #[test]
+    fn legacy_layout_is_migrated() {
+        let (user, data) = legacy_data(PdaType::IcoInvestment);
+        assert!(UserInvestmentPda::try_from_slice(&data).is_err());
+
+        let pda = UserInvestmentPda::from_legacy_slice(&data, Pubkey::new_unique()).unwrap();
+        assert_eq!(pda.bump, 254);
+        assert_eq!(pda.investment.user, user);
+        assert_eq!(pda.investment.investments.len(), 2);
+        let first = pda.investment.investments.first().unwrap();
+        assert_eq!(first.kind, UnvestingType::PrivateSells);
+        assert_eq!(first.timestamp, 1_700_000_000);
+        assert_eq!(first.amount_bought, 1_000_000);
+        assert_eq!(first.amount_released, 250_000);
+        assert!(!first.fully_vested);
+        assert_eq!(first.reference, None);
+        assert_eq!(first.payment, None);
+        let second = pda.investment.investments.last().unwrap();
+        assert!(second.custom_rule.is_some());
+
+        // The migrated PDA can be read back with the current layout
+        let migrated = borsh::to_vec(&pda).unwrap();
+        let current = UserInvestmentPda::try_from_slice(&migrated).unwrap();
+        assert_eq!(current.investment.investments, pda.investment.investments);
+    }
+
+    #[test]
+    fn invalid_legacy_data() {
+        let (_user, data) = legacy_data(PdaType::IcoPurchase);
+        assert!(
+            UserInvestmentPda::from_legacy_slice(&data, Pubkey::new_unique())
+                .is_err_and(|err| err == Error::InvalidPdaType)
+        );
+
+        let mut current = UserInvestmentPda::new(
+            1,
+            UserInvestment {
+                user: Pubkey::new_unique(),
+                investments: Vec::new(),
+                beneficiary: None,
+            },
+            Pubkey::new_unique(),
+        );
+        current.investment.investments.push(Investment {
+            kind: UnvestingType::PublicSells1,
+            timestamp: 0,
+            custom_rule: None,
+            amount_bought: 1,
+            amount_released: 0,
+            fully_vested: false,
+            reference: None,
+            payment: None,
+            revocable: false,
+            accelerated: 0,
+            link: None,
+        });
+        let data_current = borsh::to_vec(&current).unwrap();
+        assert!(UserInvestmentPda::from_legacy_slice(&data_current, Pubkey::new_unique()).is_err());
+    }
+
+    const fn vested_investment(
+        timestamp: i64,
+        amount_bought: u64,
+        amount_released: u64,
+    ) -> Investment {
+        Investment {
+            kind: UnvestingType::PrivateSells,
+            timestamp,
+            custom_rule: None,
+            amount_bought,
+            amount_released,
+            fully_vested: true,
+            reference: None,
+            payment: None,
+            revocable: false,
+            accelerated: 0,
+            link: None,
+        }
+    }
+
+    #[test]
+    fn partial_release() {
+        let mut investment = UserInvestment {
+            user: Pubkey::new_unique(),
+            investments: vec![
+                vested_investment(200, 100, 20),
+                vested_investment(100, 50, 0),
+                vested_investment(200, 30, 0),
+            ],
+            beneficiary: None,
+        };
+        let unvesting = HashMap::new();
+
+        // The oldest investment is released first, then the others in their recorded order
+        assert_eq!(
+            investment
+                .release(&unvesting, &HashMap::new(), 0, 1, Some(60))
+                .unwrap(),
+            60
+        );
+        let released = investment
+            .investments
+            .iter()
+            .map(|invest| invest.amount_released)
+            .collect::<Vec<_>>();
+        assert_eq!(released, vec![30, 50, 0]);
+
+        // What wasn't claimed is still available
+        assert_eq!(
+            investment
+                .release(&unvesting, &HashMap::new(), 0, 1, None)
+                .unwrap(),
+            100
+        );
+        assert!(investment
+            .investments
+            .iter()
+            .all(|invest| invest.amount_released == invest.amount_bought));
+        assert_eq!(
+            investment
+                .release(&unvesting, &HashMap::new(), 0, 1, None)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn leaver_policies() {
+        const WEEK: i64 = 7 * 86_400;
+        let unvesting = HashMap::new();
+        let mut base = Investment {
+            kind: UnvestingType::TeamFounders,
+            timestamp: 0,
+            custom_rule: Some(UnvestingScheme {
+                kind: UnvestingType::TeamFounders,
+                start: 0,
+                duration: 10,
+                initial_unvesting: 10_000,
+                weekly_unvesting: 10_000,
+                final_unvesting: 0,
+            }),
+            amount_bought: 1_000,
+            amount_released: 100,
+            fully_vested: false,
+            reference: None,
+            payment: None,
+            revocable: false,
+            accelerated: 0,
+            link: None,
+        };
+        let mut irrevocable = base;
+        assert!(irrevocable
+            .revoke(
+                &unvesting,
+                &HashMap::new(),
+                0,
+                2 * WEEK,
+                LeaverPolicy::KeepVested
+            )
+            .is_err_and(|err| err == Error::InvalidOperation));
+        base.revocable = true;
+
+        let mut good = base;
+        assert_eq!(
+            good.revoke(
+                &unvesting,
+                &HashMap::new(),
+                0,
+                2 * WEEK,
+                LeaverPolicy::KeepVested
+            )
+            .unwrap(),
+            700
+        );
+        assert_eq!(good.amount_bought, 300);
+        assert!(good.fully_vested);
+
+        let mut notice = base;
+        assert_eq!(
+            notice
+                .revoke(
+                    &unvesting,
+                    &HashMap::new(),
+                    0,
+                    2 * WEEK,
+                    LeaverPolicy::KeepVestedPlusWeeks(3)
+                )
+                .unwrap(),
+            400
+        );
+        assert_eq!(notice.amount_bought, 600);
+
+        let mut bad = base;
+        assert_eq!(
+            bad.revoke(
+                &unvesting,
+                &HashMap::new(),
+                0,
+                2 * WEEK,
+                LeaverPolicy::Forfeit
+            )
+            .unwrap(),
+            900
+        );
+        assert_eq!(bad.amount_bought, 100);
+    }
+
+    #[test]
+    fn acceleration() {
+        const WEEK: i64 = 7 * 86_400;
+        let unvesting = HashMap::new();
+        let mut investment = Investment {
+            kind: UnvestingType::TeamFounders,
+            timestamp: 0,
+            custom_rule: Some(UnvestingScheme {
+                kind: UnvestingType::TeamFounders,
+                start: 0,
+                duration: 10,
+                initial_unvesting: 10_000,
+                weekly_unvesting: 10_000,
+                final_unvesting: 0,
+            }),
+            amount_bought: 1_000,
+            amount_released: 0,
+            fully_vested: false,
+            reference: None,
+            payment: None,
+            revocable: false,
+            accelerated: 0,
+            link: None,
+        };
+        assert_eq!(
+            investment
+                .unvested(&unvesting, &HashMap::new(), 0, 2 * WEEK)
+                .unwrap(),
+            300
+        );
+
+        assert!(investment.accelerate(Acceleration::Extra(25_000)));
+        assert_eq!(
+            investment
+                .unvested(&unvesting, &HashMap::new(), 0, 2 * WEEK)
+                .unwrap(),
+            550
+        );
+        // The acceleration of the whole type adds up with the investment's one
+        let per_kind = HashMap::from([(UnvestingType::TeamFounders, 20_000)]);
+        assert_eq!(
+            investment
+                .unvested(&unvesting, &per_kind, 0, 2 * WEEK)
+                .unwrap(),
+            750
+        );
+        assert!(investment.accelerate(Acceleration::Extra(100_000)));
+        assert_eq!(investment.accelerated, 100_000);
+        assert_eq!(
+            investment
+                .unvested(&unvesting, &HashMap::new(), 0, 2 * WEEK)
+                .unwrap(),
+            1_000
+        );
+
+        assert!(investment.accelerate(Acceleration::Full));
+        assert!(investment.fully_vested);
+        assert!(!investment.accelerate(Acceleration::Extra(10_000)));
+
+        assert!(!Acceleration::Extra(0).is_valid());
+        assert!(!Acceleration::Extra(100_001).is_valid());
+    }
+
+    #[test]
+    fn compaction() {
+        let entry = |kind, timestamp, amount_bought, amount_released| Investment {
+            kind,
+            timestamp,
+            custom_rule: None,
+            amount_bought,
+            amount_released,
+            fully_vested: false,
+            reference: None,
+            payment: None,
+            revocable: false,
+            accelerated: 0,
+            link: None,
+        };
+        let mut referenced = entry(UnvestingType::PrivateSells, 4, 10, 0);
+        referenced.reference = Some([1; 32]);
+        let mut user = UserInvestment {
+            user: Pubkey::new_unique(),
+            investments: vec![
+                entry(UnvestingType::PrivateSells, 2, 100, 10),
+                entry(UnvestingType::TeamFounders, 3, 50, 0),
+                entry(UnvestingType::PrivateSells, 1, 200, 20),
+                referenced,
+                entry(UnvestingType::PrivateSells, 5, 300, 0),
+            ],
+            beneficiary: None,
+        };
+
+        assert_eq!(user.compact().unwrap(), 2);
+        assert_eq!(user.investments.len(), 3);
+        let merged = user.investments[0];
+        assert_eq!(merged.kind, UnvestingType::PrivateSells);
+        assert_eq!(merged.timestamp, 1);
+        assert_eq!(merged.amount_bought, 600);
+        assert_eq!(merged.amount_released, 30);
+        assert_eq!(user.investments[1].kind, UnvestingType::TeamFounders);
+        assert!(user.has_reference(&[1; 32]));
+        assert_eq!(user.invested(UnvestingType::PrivateSells), 610);
+        assert_eq!(user.compact().unwrap(), 0);
+
+        // Entries that can't be merged are limited
+        let mut referenced = (0..MAX_INVESTMENT_ENTRIES)
+            .map(|idx| {
+                let mut invest = entry(UnvestingType::PublicSells1, 0, 1, 0);
+                invest.reference = Some([u8::try_from(idx).unwrap(); 32]);
+                invest
+            })
+            .collect::<Vec<_>>();
+        user.investments.clear();
+        user.add(&referenced).unwrap();
+        assert_eq!(
+            user.add(&[entry(UnvestingType::PrivateSells, 0, 1, 0)]),
+            Err(Error::TooManyInvestmentEntries)
+        );
+
+        // Otherwise the entries are compacted when the limit is reached
+        referenced.truncate(MAX_INVESTMENT_ENTRIES - 1);
+        user.investments = referenced;
+        user.add(&[entry(UnvestingType::PrivateSells, 0, 1, 0)])
+            .unwrap();
+        user.add(&[entry(UnvestingType::PrivateSells, 0, 1, 0)])
+            .unwrap();
+        assert_eq!(user.investments.len(), MAX_INVESTMENT_ENTRIES);
+        assert_eq!(user.invested(UnvestingType::PrivateSells), 2);
+    }
+
+    #[test]
+    fn granted_cancellation() {
+        let owner = Pubkey::new_unique();
+        let entry = |kind, amount_bought, reference| Investment {
+            kind,
+            timestamp: 0,
+            custom_rule: None,
+            amount_bought,
+            amount_released: 0,
+            fully_vested: false,
+            reference: None,
+            payment: None,
+            revocable: false,
+            accelerated: 0,
+            link: Some(InvestmentLink::Granted {
+                user: owner,
+                reference,
+            }),
+        };
+        let mut user = UserInvestment {
+            user: owner,
+            investments: vec![
+                entry(UnvestingType::PublicSells1, 100, [1; 32]),
+                entry(UnvestingType::PublicSells2, 50, [2; 32]),
+            ],
+            beneficiary: None,
+        };
+
+        // The granted tokens are cancelled in proportion of the investment
+        assert_eq!(
+            user.cancel_granted(&owner, &[1; 32], 1_000, 250).unwrap(),
+            vec![(UnvestingType::PublicSells1, 25)]
+        );
+        assert_eq!(user.investments[0].amount_bought, 75);
+        assert!(user
+            .cancel_granted(&Pubkey::new_unique(), &[1; 32], 750, 750)
+            .unwrap()
+            .is_empty());
+
+        // And removed along with it
+        assert_eq!(
+            user.cancel_granted(&owner, &[1; 32], 750, 750).unwrap(),
+            vec![(UnvestingType::PublicSells1, 75)]
+        );
+        assert_eq!(user.investments.len(), 1);
+        assert_eq!(user.invested(UnvestingType::PublicSells2), 50);
+    }
 }
@@ -0,0 +1,76 @@
+// File: bangk-ico/src/kyc.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 13:17:15
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+use bangk_macro::pda;
+use bangk_onchain_common::pda::{BangkPda, PdaType};
+use borsh::BorshDeserialize;
+use solana_program::pubkey::Pubkey;
+
+/// PDA attesting that a user passed the KYC procedure.
+///
+/// It is issued and revoked by the compliance `MultiSig`.
+#[pda(kind = PdaType::KycAttestation, seed = "Kyc", seed = user)]
+pub struct KycAttestationPda {
+    /// User who passed the KYC procedure.
+    pub user: Pubkey,
+    /// Level of verification reached by the user.
+    pub level: u8,
+    /// Timestamp at which the attestation expires.
+    pub expiry: i64,
+    /// Jurisdiction of the user (ISO 3166-1 alpha-2 country code).
+    pub jurisdiction: [u8; 2],
+}
+
+impl<'a> KycAttestationPda<'a> {
+    /// Create a new KYC attestation.
+    ///
+    /// # Parameters
+    /// * `bump` - Bump used to derive the PDA address,
+    /// * `user` - User who passed the KYC procedure,
+    /// * `level` - Level of verification reached by the user,
+    /// * `expiry` - Timestamp at which the attestation expires,
+    /// * `jurisdiction` - Jurisdiction of the user (ISO 3166-1 alpha-2 country code).
+    #[must_use]
+    pub const fn new(
+        bump: u8,
+        user: Pubkey,
+        level: u8,
+        expiry: i64,
+        jurisdiction: [u8; 2],
+    ) -> Self {
+        Self {
+            pda_type: Self::PDA_TYPE,
+            bump,
+            account: None,
+            user,
+            level,
+            expiry,
+            jurisdiction,
+        }
+    }
+
+    /// Checks that the attestation's definition is coherent at a given time.
+    ///
+    /// # Parameters
+    /// * `now` - Timestamp at which the attestation is issued.
+    #[must_use]
+    pub fn is_well_formed(&self, now: i64) -> bool {
+        self.level > 0 && self.expiry > now && self.jurisdiction.iter().all(u8::is_ascii_uppercase)
+    }
+
+    /// Checks that the attestation has not expired at a given time.
+    ///
+    /// # Parameters
+    /// * `now` - Timestamp of the check.
+    #[must_use]
+    pub const fn is_expired(&self, now: i64) -> bool {
+        self.expiry <= now
+    }
+}
@@ -3,7 +3,7 @@
 // Creation date: Sunday 09 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Wednesday 21 August 2024 @ 19:33:07
+// Last modified: Sunday 18 October 2026 @ 17:59:42
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -13,26 +13,47 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+mod airdrop;
 mod config;
+mod denylist;
 mod entrypoint;
+mod history;
 mod instruction;
 mod investment;
+mod kyc;
+mod position;
 mod processor;
+mod referral;
+mod sale;
 mod timelock;
 mod unvesting;
 mod wallets;
 
 // Only make public elements that would be useful.
+/// Merkle airdrops of BGK tokens.
+pub use airdrop::*;
 /// The configuration PDA for Bangk's ICO program.
 pub use config::ConfigurationPda;
+/// Sanctions denylist.
+pub use denylist::*;
+/// History of the releases of the investors' tokens.
+pub use history::*;
 /// Instructions for the Bangk ICO program.
 pub use instruction::*;
 /// Definition of a user's investment.
 pub use investment::*;
+/// KYC attestations of the investors.
+pub use kyc::KycAttestationPda;
+/// Vesting positions represented by NFTs.
+pub use position::*;
 /// Handles the dispatch of the processing operations (only used in tests).
 pub use processor::process_instruction;
 /// `Timelock` delay.
 pub use processor::TIMELOCK_DELAY;
+/// Referral program rewarding the referrers of the investors.
+pub use referral::*;
+/// Token sale: phases, treasury and purchases.
+pub use sale::*;
 pub use timelock::TimelockPda;
 /// Sets the rules for the unvesting.
 pub use unvesting::*;
@@ -0,0 +1,132 @@
+// File: bangk-ico/src/position.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 16:28:19
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+use bangk_macro::pda;
+use bangk_onchain_common::pda::{BangkPda, PdaType, Seed};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use shank::ShankType;
+use solana_program::{msg, pubkey::Pubkey};
+
+use crate::investment::Investment;
+
+/// Restrictions on the transfers of the tokenised vesting positions.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub struct PositionTransferPolicy {
+    /// Minimum duration (in seconds) a position must be held before it can be transferred.
+    pub lockup: i64,
+    /// If set, the recipient of a position must have a valid KYC attestation.
+    pub require_kyc: bool,
+}
+
+impl PositionTransferPolicy {
+    /// Checks if a transfer policy definition seems valid.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if self.lockup < 0 {
+            msg!("position transfer policy definition invalid: {:?}", self);
+            return false;
+        }
+        true
+    }
+}
+
+/// PDA storing a vesting position represented by an NFT.
+///
+/// Whoever holds the NFT can claim the tokens of the position.
+#[pda(kind = PdaType::VestingPosition, seed = "Position", seed = mint)]
+pub struct VestingPositionPda {
+    /// Mint of the NFT representing the position.
+    pub mint: Pubkey,
+    /// User whose investment was tokenised.
+    pub user: Pubkey,
+    /// The underlying investment.
+    pub investment: Investment,
+    /// Timestamp at which the NFT was received by its current holder.
+    pub held_since: i64,
+}
+
+impl<'a> VestingPositionPda<'a> {
+    /// Create a new vesting position.
+    ///
+    /// # Parameters
+    /// * `bump` - Bump used to derive the PDA address,
+    /// * `mint` - Mint of the NFT representing the position,
+    /// * `user` - User whose investment is tokenised,
+    /// * `investment` - The underlying investment,
+    /// * `now` - Timestamp of the tokenisation.
+    #[must_use]
+    pub const fn new(
+        bump: u8,
+        mint: Pubkey,
+        user: Pubkey,
+        investment: Investment,
+        now: i64,
+    ) -> Self {
+        Self {
+            pda_type: Self::PDA_TYPE,
+            bump,
+            account: None,
+            mint,
+            user,
+            investment,
+            held_since: now,
+        }
+    }
+
+    /// Checks if all the tokens of the position have been released.
+    #[must_use]
+    pub const fn is_fully_released(&self) -> bool {
+        self.investment.amount_released >= self.investment.amount_bought
+    }
+
+    /// Checks if the position can be transferred at a given time.
+    ///
+    /// # Parameters
+    /// * `policy` - The transfer restrictions (transfers are disabled if `None`),
+    /// * `now` - Timestamp of the transfer.
+    #[must_use]
+    pub fn is_transferable(&self, policy: Option<&PositionTransferPolicy>, now: i64) -> bool {
+        policy.is_some_and(|policy| self.held_since.saturating_add(policy.lockup) <= now)
+    }
+}
+
+/// Get the address and bump of the mint of the NFT representing a vesting position.
+///
+/// # Parameters
+/// * `user` - User whose investment is tokenised,
+/// * `id` - Identifier of the position among the user's positions.
+#[must_use]
+pub fn get_position_mint_pda(user: &Pubkey, id: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"PositionMint", user.as_ref(), &[id]], &crate::ID)
+}
+
+/// Get the seeds of the mint of the NFT representing a vesting position.
+///
+/// # Parameters
+/// * `user` - User whose investment is tokenised,
+/// * `id` - Identifier of the position among the user's positions.
+#[must_use]
+pub fn get_position_mint_seeds(user: &Pubkey, id: u8) -> Vec<Vec<u8>> {
+    let (_address, bump) = get_position_mint_pda(user, id);
+    let seeds: Vec<Seed> = vec!["PositionMint".into(), user.into(), id.into(), bump.into()];
+    seeds.into_iter().map(Into::into).collect()
+}
@@ -3,7 +3,7 @@
 // Creation date: Sunday 09 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Wednesday 11 September 2024 @ 18:39:19
+// Last modified: Sunday 18 October 2026 @ 18:08:28
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -12,7 +12,7 @@ use std::collections::HashSet;
 
 use bangk_onchain_common::{
     check_ata_exists, check_pda_owner, check_signers, debug, get_ata_owner, get_timestamp,
-    pda::BangkPda,
+    pda::{BangkPda, PdaType},
     security::{MultiSig, MultiSigPda, MultiSigType, OperationSecurityLevel},
     Error,
 };
@@ -20,8 +20,9 @@ use borsh::BorshDeserialize as _;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
+    log::sol_log_data,
     msg,
-    program::{get_return_data, invoke, invoke_signed},
+    program::{get_return_data, invoke, invoke_signed, set_return_data},
     program_error::ProgramError,
     pubkey::Pubkey,
     rent::Rent,
@@ -30,24 +31,49 @@ use solana_program::{
 };
 use spl_associated_token_account::instruction::create_associated_token_account;
 use spl_token_2022::{
-    extension::{metadata_pointer, ExtensionType},
+    extension::{metadata_pointer, ExtensionType, StateWithExtensions},
     instruction::{
-        get_account_data_size, initialize_account3, initialize_mint2, mint_to, set_authority,
-        transfer_checked, AuthorityType,
+        freeze_account, get_account_data_size, initialize_account3, initialize_mint2, mint_to,
+        set_authority, thaw_account, transfer_checked, AuthorityType,
     },
-    state::Mint,
+    state::{Account, Mint},
 };
 use spl_token_metadata_interface::{
     instruction::initialize as initialize_metadata, state::TokenMetadata,
 };
 
 use crate::{
+    airdrop::{
+        get_airdrop_leaf, get_airdrop_vault_pda, get_airdrop_vault_seeds, verify_merkle_proof,
+        AirdropClaimsPda, AirdropPda, MAX_AIRDROP_RECIPIENTS,
+    },
     config::ConfigurationPda,
+    denylist::{get_denylist_shard, DenylistPda},
+    history::{ReleaseEvent, ReleaseHistoryPda, ReleaseRecord, RELEASE_EVENT_TAG},
     instruction::{
-        BangkIcoInstruction, CancelInvestmentArgs, InitializeArgs, LaunchBGKArgs, MintCreationArgs,
-        UpdateAdminMultisigArgs, UserInvestmentArgs,
+        AccelerateVestingArgs, BangkIcoInstruction, BatchVestingReleaseArgs, CancelInvestmentArgs,
+        CancelInvestmentEntryArgs, CancelledInvestment, ClaimAirdropArgs, ClaimVestedArgs,
+        ClawbackArgs, ClearComplianceHoldArgs, CloseUserInvestmentArgs, CompletedInvestment,
+        CreateAirdropArgs, DenylistArgs, InitializeArgs, InvestmentSelector,
+        IssueKycAttestationArgs, LaunchBGKArgs, LeaverEventArgs, MigrateConfigurationArgs,
+        MigrateUserInvestmentArgs, MintCreationArgs, PurchaseArgs, ReassignInvestmentArgs,
+        ReclaimAirdropArgs, RevokeKycAttestationArgs, SetBeneficiaryArgs, SetBonusRulesArgs,
+        SetComplianceHoldArgs, SetComplianceMultisigArgs, SetInvestmentCapsArgs,
+        SetPositionTransferPolicyArgs, SetReferralProgramArgs, SetSalePhasesArgs, SkippedRelease,
+        TokenizePositionArgs, UpdateAdminMultisigArgs, UserInvestmentArgs,
+        BATCH_RELEASE_ACCOUNTS_PER_USER,
+    },
+    investment::{
+        get_investment_reference, ComplianceHold, Investment, InvestmentLink, UserInvestment,
+        UserInvestmentPda,
+    },
+    kyc::KycAttestationPda,
+    position::{get_position_mint_pda, get_position_mint_seeds, VestingPositionPda},
+    referral::{ReferralProgram, ReferrerStatsPda},
+    sale::{
+        get_purchase_reference, get_sale_treasury_pda, get_sale_treasury_seeds, Purchase,
+        UserPurchasesPda,
     },
-    investment::{Investment, UserInvestment, UserInvestmentPda},
     timelock::{Timelock, TimelockPda},
     unvesting::UnvestingType,
     ExecuteTransferFromInternalWalletArgs, QueueTransferFromInternalWalletArgs, WalletType,
@@ -62,6 +88,7 @@ const TOTAL_TOKEN_AMOUNT: u64 = 177_000_000;
 ///
 /// # Errors
 /// If the wrong number of accounts was given, if the funds are insufficient, etc.
+#[allow(clippy::too_many_lines)]
 pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -88,12 +115,90 @@ pub fn process_instruction(
         }
         BangkIcoInstruction::LaunchBGK(args) => launch_bgk(program_id, accounts, args),
         BangkIcoInstruction::VestingRelease => vesting_release(program_id, accounts),
+        BangkIcoInstruction::ClaimVested(args) => claim_vested(program_id, accounts, args),
+        BangkIcoInstruction::BatchVestingRelease(args) => {
+            batch_vesting_release(program_id, accounts, &args)
+        }
+        BangkIcoInstruction::QueueClawback(args) => queue_clawback(program_id, accounts, args),
+        BangkIcoInstruction::ExecuteClawback(args) => execute_clawback(program_id, accounts, args),
         BangkIcoInstruction::QueueTransferFromInternalWallet(args) => {
             queue_transfer_from_reserve(program_id, accounts, args)
         }
         BangkIcoInstruction::ExecuteTransferFromInternalWallet(args) => {
             execute_transfer_from_reserve(program_id, accounts, args)
         }
+        BangkIcoInstruction::MigrateUserInvestment(args) => {
+            migrate_user_investment(program_id, accounts, &args)
+        }
+        BangkIcoInstruction::MigrateConfiguration(args) => {
+            migrate_configuration(program_id, accounts, &args)
+        }
+        BangkIcoInstruction::SetSalePhases(args) => set_sale_phases(program_id, accounts, &args),
+        BangkIcoInstruction::InitializeSaleTreasury => {
+            initialize_sale_treasury(program_id, accounts)
+        }
+        BangkIcoInstruction::Purchase(args) => purchase(program_id, accounts, args),
+        BangkIcoInstruction::AbortSale => abort_sale(program_id, accounts),
+        BangkIcoInstruction::Refund => refund(program_id, accounts),
+        BangkIcoInstruction::SetComplianceMultisig(args) => {
+            set_compliance_multisig(program_id, accounts, args)
+        }
+        BangkIcoInstruction::IssueKycAttestation(args) => {
+            issue_kyc_attestation(program_id, accounts, args)
+        }
+        BangkIcoInstruction::RevokeKycAttestation(args) => {
+            revoke_kyc_attestation(program_id, accounts, args)
+        }
+        BangkIcoInstruction::AddToDenylist(args) => add_to_denylist(program_id, accounts, args),
+        BangkIcoInstruction::RemoveFromDenylist(args) => {
+            remove_from_denylist(program_id, accounts, args)
+        }
+        BangkIcoInstruction::CreateAirdrop(args) => create_airdrop(program_id, accounts, args),
+        BangkIcoInstruction::ClaimAirdrop(args) => claim_airdrop(program_id, accounts, &args),
+        BangkIcoInstruction::ReclaimAirdrop(args) => reclaim_airdrop(program_id, accounts, args),
+        BangkIcoInstruction::SetReferralProgram(args) => {
+            set_referral_program(program_id, accounts, args)
+        }
+        BangkIcoInstruction::FundReferralRewards => fund_referral_rewards(program_id, accounts),
+        BangkIcoInstruction::SetBonusRules(args) => set_bonus_rules(program_id, accounts, &args),
+        BangkIcoInstruction::SetInvestmentCaps(args) => {
+            set_investment_caps(program_id, accounts, &args)
+        }
+        BangkIcoInstruction::CancelInvestmentEntry(args) => {
+            cancel_investment_entry(program_id, accounts, &args)
+        }
+        BangkIcoInstruction::CloseUserInvestment(args) => {
+            close_user_investment(program_id, accounts, &args)
+        }
+        BangkIcoInstruction::QueueReassignInvestment(args) => {
+            queue_reassign_investment(program_id, accounts, &args)
+        }
+        BangkIcoInstruction::ExecuteReassignInvestment(args) => {
+            execute_reassign_investment(program_id, accounts, &args)
+        }
+        BangkIcoInstruction::SetBeneficiary(args) => set_beneficiary(program_id, accounts, args),
+        BangkIcoInstruction::ApplyLeaverEvent(args) => {
+            apply_leaver_event(program_id, accounts, &args)
+        }
+        BangkIcoInstruction::SetComplianceHold(args) => {
+            set_compliance_hold(program_id, accounts, args)
+        }
+        BangkIcoInstruction::ClearComplianceHold(args) => {
+            clear_compliance_hold(program_id, accounts, args)
+        }
+        BangkIcoInstruction::AccelerateVesting(args) => {
+            accelerate_vesting(program_id, accounts, args)
+        }
+        BangkIcoInstruction::EnableReleaseHistory => enable_release_history(program_id, accounts),
+        BangkIcoInstruction::CompactInvestments => compact_investments(program_id, accounts),
+        BangkIcoInstruction::TokenizePosition(args) => {
+            tokenize_position(program_id, accounts, args)
+        }
+        BangkIcoInstruction::ClaimPosition(args) => claim_position(program_id, accounts, args),
+        BangkIcoInstruction::SetPositionTransferPolicy(args) => {
+            set_position_transfer_policy(program_id, accounts, args)
+        }
+        BangkIcoInstruction::TransferPosition => transfer_position(program_id, accounts),
     }
 }
 
@@ -558,6 +663,9 @@ struct UserInvestmentAccounts<'a> {
     sig_admin: AccountInfo<'a>,
     investment: AccountInfo<'a>,
     _program_system: AccountInfo<'a>,
+    kyc: AccountInfo<'a>,
+    referrer_stats: Option<AccountInfo<'a>>,
+    referrer_investment: Option<AccountInfo<'a>>,
 }
 
 impl<'a> UserInvestmentAccounts<'a> {
@@ -569,6 +677,9 @@ impl<'a> UserInvestmentAccounts<'a> {
             sig_admin: next_account_info(accounts_iter)?.clone(),
             investment: next_account_info(accounts_iter)?.clone(),
             _program_system: next_account_info(accounts_iter)?.clone(),
+            kyc: next_account_info(accounts_iter)?.clone(),
+            referrer_stats: accounts_iter.next().cloned(),
+            referrer_investment: accounts_iter.next().cloned(),
         })
     }
 }
@@ -591,50 +702,309 @@ fn user_investment(
         return Err(Error::InvalidPdaAddress.into());
     }
 
-    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
-    let mut config = ConfigurationPda::from_account(&ctx.config)?;
-    config.amount_invested = config.amount_invested.saturating_add(args.amount);
-
-    let max_amount = WALLET_INIT_AMOUNT
-        .iter()
-        .find(|(kind, _amount)| *kind == WalletType::Ico)
-        .map(|(_kind, amount)| amount.saturating_mul(1_000_000))
-        .unwrap_or_default();
-    if config.amount_invested > max_amount {
-        msg!(
-            "the maximum amount of available tokens has been exceeded ({} vs {})",
-            config.amount_invested,
-            max_amount
-        );
-        return Err(Error::InvalidAmount.into());
+    if args.payment.is_some_and(|payment| !payment.is_valid()) {
+        return Err(Error::InvalidOperation.into());
+    }
+    // Only the team members can leave, the other investments can't be revoked
+    if args.revocable && args.invest_kind != UnvestingType::TeamFounders {
+        msg!("only the team & founders investments can be revocable");
+        return Err(Error::InvalidOperation.into());
+    }
+    if let Some(reference) = &args.reference {
+        if ctx.investment.lamports() > 0
+            && UserInvestmentPda::from_account(&ctx.investment)?
+                .investment
+                .has_reference(reference)
+        {
+            msg!("an investment with the same reference was already recorded");
+            return Err(Error::DuplicateInvestmentReference.into());
+        }
     }
 
-    config.write(&ctx.api)?;
+    let now = get_timestamp()?;
+    check_kyc_attestation(program_id, &ctx.kyc, &args.user, now)?;
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+    config.add_invested(args.invest_kind, args.amount)?;
+    check_user_investment_cap(&config, &ctx.investment, args.invest_kind, args.amount)?;
 
-    if config.launch_date > 0 && config.launch_date <= get_timestamp()? {
+    if config.launch_date > 0 && config.launch_date <= now {
         if args.invest_kind == UnvestingType::AdvisersPartners {
             msg!("use instruction post_launch_advisers_investment instead");
         }
         return Err(Error::IcoInvestAfterLaunch.into());
     }
+    if config.sale_aborted {
+        return Err(Error::SaleAborted.into());
+    }
 
-    // If PdA doesn't exist yet, create it, otherwise update it
-    if ctx.investment.lamports() == 0 {
-        let investment =
-            UserInvestment::new(args.user, args.invest_kind, args.amount, args.custom_rule)?;
-        let pda = UserInvestmentPda::new(investment_bump, investment);
-        pda.create(&ctx.investment, &ctx.api, &crate::ID)
+    let phase = config.record_sale(args.invest_kind, args.amount, now)?;
+    let bonus = match config.bonus_rule(args.invest_kind, phase.as_ref(), args.amount) {
+        Some(rule) => {
+            let amount = rule.bonus(args.amount)?;
+            (amount > 0).then_some((amount, rule.unvesting))
+        }
+        None => None,
+    };
+    if let Some((amount, unvesting)) = bonus {
+        config.add_invested(unvesting.kind, amount)?;
+    }
+    // The tokens granted for the investment are linked to it by its reference, to be cancelled with it
+    let grants = bonus.is_some() || args.referrer.is_some();
+    let reference = if grants {
+        Some(get_granting_reference(&ctx.investment, &args, now)?)
     } else {
-        UserInvestmentPda::check_address(args.user, &crate::ID, &ctx.investment)?;
-        let mut pda = UserInvestmentPda::from_account(&ctx.investment)?;
-        pda.investment.investments.push(Investment {
-            kind: args.invest_kind,
-            timestamp: get_timestamp()?,
-            custom_rule: args.custom_rule,
-            amount_bought: args.amount,
-            amount_released: 0,
+        args.reference
+    };
+    let granted = reference
+        .filter(|_| grants)
+        .map(|reference| InvestmentLink::Granted {
+            user: args.user,
+            reference,
         });
-        pda.write(&ctx.api)
+    if let Some(referrer) = args.referrer {
+        reward_referrer(program_id, &ctx, &mut config, &args, referrer, granted)?;
+    }
+    config.write(&ctx.api)?;
+
+    let mut investment = Investment::new(args.invest_kind, args.amount, args.custom_rule)?;
+    investment.reference = reference;
+    investment.payment = args.payment;
+    investment.revocable = args.revocable;
+    investment.link = grants.then_some(InvestmentLink::Origin {
+        referrer: args.referrer,
+    });
+    add_user_investment(
+        &ctx.investment,
+        &ctx.api,
+        args.user,
+        investment_bump,
+        investment,
+    )?;
+    if let Some((amount, unvesting)) = bonus {
+        debug!("granting a bonus of {} tokens to {}", amount, args.user);
+        // The bonus follows the fate of the investment it was granted for
+        let mut bonus_investment = Investment::new(unvesting.kind, amount, Some(unvesting))?;
+        bonus_investment.revocable = args.revocable;
+        bonus_investment.link = granted;
+        add_user_investment(
+            &ctx.investment,
+            &ctx.api,
+            args.user,
+            investment_bump,
+            bonus_investment,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Get the reference linking an investment to the tokens granted for it, generating one if the
+/// investment was recorded without any.
+fn get_granting_reference(
+    account: &AccountInfo,
+    args: &UserInvestmentArgs,
+    now: i64,
+) -> Result<[u8; 32], ProgramError> {
+    if let Some(reference) = args.reference {
+        return Ok(reference);
+    }
+    let index = if account.lamports() > 0 {
+        UserInvestmentPda::from_account(account)?
+            .investment
+            .investments
+            .len()
+    } else {
+        0
+    };
+    let index = u32::try_from(index).map_err(|_err| Error::IntegerOverflow)?;
+    Ok(get_investment_reference(&args.user, now, index))
+}
+
+/// Record an investment in the statistics of the referrer who brought it, and grant them
+/// their reward as a vesting investment linked to the investment.
+fn reward_referrer(
+    program_id: &Pubkey,
+    ctx: &UserInvestmentAccounts,
+    config: &mut ConfigurationPda,
+    args: &UserInvestmentArgs,
+    referrer: Pubkey,
+    link: Option<InvestmentLink>,
+) -> ProgramResult {
+    let (Some(stats_account), Some(investment_account)) =
+        (&ctx.referrer_stats, &ctx.referrer_investment)
+    else {
+        msg!("the referrer's accounts are missing");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_pda_owner!(program_id, stats_account, investment_account);
+
+    if referrer == args.user {
+        msg!("a user can't be their own referrer");
+        return Err(Error::InvalidOperation.into());
+    }
+    let Some(program) = config.referral.as_mut() else {
+        msg!("there is no referral program");
+        return Err(Error::InvalidOperation.into());
+    };
+
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (stats_pda, stats_bump) = ReferrerStatsPda::get_address(referrer, &crate::ID);
+    let (investment_pda, investment_bump) = UserInvestmentPda::get_address(referrer, &crate::ID);
+    if stats_pda != *stats_account.key || investment_pda != *investment_account.key {
+        msg!("invalid referrer PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    let mut stats = if stats_account.lamports() == 0 {
+        ReferrerStatsPda::new(stats_bump, referrer)
+    } else {
+        ReferrerStatsPda::from_account(stats_account)?
+    };
+    let reward = program.credit(args.amount, stats.rewarded)?;
+    let unvesting = program.unvesting;
+    stats.record(args.amount, reward);
+    if stats_account.lamports() == 0 {
+        stats.create(stats_account, &ctx.api, &crate::ID)?;
+    } else {
+        stats.write(&ctx.api)?;
+    }
+
+    if reward == 0 {
+        msg!("the referral reward caps have been reached");
+        return Ok(());
+    }
+    // Like the bonuses, the rewards are released from the `Ico` wallet, even before they are funded
+    config.add_invested(unvesting.kind, reward)?;
+    debug!("rewarding {} with {} tokens", referrer, reward);
+    let mut reward_investment = Investment::new(unvesting.kind, reward, Some(unvesting))?;
+    reward_investment.link = link;
+    add_user_investment(
+        investment_account,
+        &ctx.api,
+        referrer,
+        investment_bump,
+        reward_investment,
+    )
+}
+
+/// Check that an investment doesn't exceed the cap of its type for a single user.
+///
+/// # Parameters
+/// * `config` - Configuration of the program,
+/// * `account` - Account of the user's investments (already checked),
+/// * `kind` - Type of investment,
+/// * `amount` - Number of tokens invested.
+///
+/// # Errors
+/// If the cap would be exceeded.
+fn check_user_investment_cap(
+    config: &ConfigurationPda,
+    account: &AccountInfo,
+    kind: UnvestingType,
+    amount: u64,
+) -> ProgramResult {
+    let invested = if account.lamports() == 0 {
+        0
+    } else {
+        UserInvestmentPda::from_account(account)?
+            .investment
+            .invested(kind)
+    };
+    config.check_user_cap(kind, invested, amount)?;
+    Ok(())
+}
+
+/// Check that a user holds a KYC attestation that hasn't expired.
+///
+/// # Parameters
+/// * `program_id` - ID of the program,
+/// * `account` - Account of the user's KYC attestation,
+/// * `user` - User concerned,
+/// * `now` - Current timestamp.
+///
+/// # Errors
+/// If the attestation is missing, for another user or expired.
+fn check_kyc_attestation(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+    user: &Pubkey,
+    now: i64,
+) -> ProgramResult {
+    check_pda_owner!(program_id, account);
+    KycAttestationPda::check_address(user, &crate::ID, account)?;
+    if account.lamports() == 0 {
+        msg!("{} has no KYC attestation", user);
+        return Err(Error::MissingKycAttestation.into());
+    }
+
+    let attestation = KycAttestationPda::from_account(account)?;
+    if attestation.user != *user {
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    if attestation.is_expired(now) {
+        msg!("the KYC attestation of {} has expired", user);
+        return Err(Error::KycAttestationExpired.into());
+    }
+
+    Ok(())
+}
+
+/// Check that a wallet isn't on the sanctions denylist.
+///
+/// # Parameters
+/// * `program_id` - ID of the program,
+/// * `account` - Shard of the denylist in which the wallet would be stored,
+/// * `wallet` - Wallet receiving the tokens.
+///
+/// # Errors
+/// If the shard is invalid or the wallet is on the denylist.
+fn check_not_denylisted(
+    program_id: &Pubkey,
+    account: &AccountInfo,
+    wallet: &Pubkey,
+) -> ProgramResult {
+    check_pda_owner!(program_id, account);
+    DenylistPda::check_address(get_denylist_shard(wallet), &crate::ID, account)?;
+    if account.lamports() == 0 {
+        return Ok(());
+    }
+
+    let denylist = DenylistPda::from_account(account)?;
+    if denylist.contains(wallet) {
+        msg!("{} is on the sanctions denylist", wallet);
+        return Err(Error::DenylistedWallet.into());
+    }
+
+    Ok(())
+}
+
+/// Add an investment to a user's investments, creating the PDA if it doesn't exist yet.
+fn add_user_investment<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    user: Pubkey,
+    bump: u8,
+    investment: Investment,
+) -> ProgramResult {
+    // If PdA doesn't exist yet, create it, otherwise update it
+    if account.lamports() == 0 {
+        let pda = UserInvestmentPda::new(
+            bump,
+            UserInvestment {
+                user,
+                investments: vec![investment],
+                beneficiary: None,
+            },
+            *payer.key,
+        );
+        pda.create(account, payer, &crate::ID)
+    } else {
+        UserInvestmentPda::check_address(user, &crate::ID, account)?;
+        let mut pda = UserInvestmentPda::from_account(account)?;
+        pda.investment.add(&[investment])?;
+        pda.write(payer)
     }
 }
 
@@ -683,25 +1053,16 @@ fn queue_post_launch_adivisers_investment(
         return Err(Error::PostLaunchInvestmentBeforeLaunch.into());
     }
 
-    config.amount_invested = config.amount_invested.saturating_add(args.amount);
-
-    let max_amount = WALLET_INIT_AMOUNT
-        .iter()
-        .find(|(kind, _amount)| *kind == WalletType::Ico)
-        .map(|(_kind, amount)| amount.saturating_mul(1_000_000))
-        .unwrap_or_default();
-    if config.amount_invested > max_amount {
-        msg!(
-            "the maximum amount of available tokens has been exceeded ({} vs {})",
-            config.amount_invested,
-            max_amount
-        );
-        return Err(Error::InvalidAmount.into());
-    }
+    config.add_invested(args.invest_kind, args.amount)?;
     config.write(&ctx.admin1)?;
 
     // Only advisers & partners can get investments post-launch
-    if args.invest_kind != UnvestingType::AdvisersPartners {
+    if args.invest_kind != UnvestingType::AdvisersPartners
+        || args.referrer.is_some()
+        || args.reference.is_some()
+        || args.payment.is_some()
+        || args.revocable
+    {
         msg!("this operation is only available for advisers & partners investments: aborting");
         return Err(Error::InvalidOperation.into());
     }
@@ -721,6 +1082,7 @@ struct ProcessPostLaunchInvestmentAccounts<'a> {
     timelock: AccountInfo<'a>,
     investment: AccountInfo<'a>,
     _program_system: AccountInfo<'a>,
+    kyc: AccountInfo<'a>,
 }
 
 impl<'a> ProcessPostLaunchInvestmentAccounts<'a> {
@@ -733,6 +1095,7 @@ impl<'a> ProcessPostLaunchInvestmentAccounts<'a> {
             timelock: next_account_info(accounts_iter)?.clone(),
             investment: next_account_info(accounts_iter)?.clone(),
             _program_system: next_account_info(accounts_iter)?.clone(),
+            kyc: next_account_info(accounts_iter)?.clone(),
         })
     }
 }
@@ -756,6 +1119,7 @@ fn process_post_launch_adivisers_investment(
         ctx.timelock
     );
     check_signers!(accounts, &ctx.sig_admin);
+    check_kyc_attestation(program_id, &ctx.kyc, &args.user, get_timestamp()?)?;
 
     // Check that there’s a queued transfer, and remove it from the list if found
     TimelockPda::check_address(&crate::ID, &ctx.timelock)?;
@@ -776,31 +1140,25 @@ fn process_post_launch_adivisers_investment(
     }
 
     ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let config = ConfigurationPda::from_account(&ctx.config)?;
+    check_user_investment_cap(
+        &config,
+        &ctx.investment,
+        UnvestingType::AdvisersPartners,
+        args.amount,
+    )?;
 
-    // If PdA doesn't exist yet, create it, otherwise update it
-    if ctx.investment.lamports() == 0 {
-        let investment = UserInvestment::new(
-            args.user,
+    add_user_investment(
+        &ctx.investment,
+        &ctx.payer,
+        args.user,
+        investment_bump,
+        Investment::new(
             UnvestingType::AdvisersPartners,
             args.amount,
             args.custom_rule,
-        )?;
-        let pda = UserInvestmentPda::new(investment_bump, investment);
-        pda.create(&ctx.investment, &ctx.payer, &crate::ID)?;
-    } else {
-        UserInvestmentPda::check_address(args.user, &crate::ID, &ctx.investment)?;
-        let mut pda = UserInvestmentPda::from_account(&ctx.investment)?;
-        pda.investment.investments.push(Investment {
-            kind: UnvestingType::AdvisersPartners,
-            timestamp: get_timestamp()?,
-            custom_rule: args.custom_rule,
-            amount_bought: args.amount,
-            amount_released: 0,
-        });
-        pda.write(&ctx.payer)?;
-    }
-
-    Ok(())
+        )?,
+    )
 }
 
 struct CancelInvestmentAccounts<'a> {
@@ -810,6 +1168,9 @@ struct CancelInvestmentAccounts<'a> {
     sig_admin: AccountInfo<'a>,
     investment: AccountInfo<'a>,
     _program_system: AccountInfo<'a>,
+    purchases: Option<AccountInfo<'a>>,
+    referrer_stats: Option<AccountInfo<'a>>,
+    referrer_investment: Option<AccountInfo<'a>>,
 }
 
 impl<'a> CancelInvestmentAccounts<'a> {
@@ -822,6 +1183,9 @@ impl<'a> CancelInvestmentAccounts<'a> {
             sig_admin: next_account_info(accounts_iter)?.clone(),
             investment: next_account_info(accounts_iter)?.clone(),
             _program_system: next_account_info(accounts_iter)?.clone(),
+            purchases: accounts_iter.next().cloned(),
+            referrer_stats: accounts_iter.next().cloned(),
+            referrer_investment: accounts_iter.next().cloned(),
         })
     }
 }
@@ -836,12 +1200,14 @@ fn cancel_investment(
     msg!("Bangk: deleting investment for {}", args.user);
 
     check_pda_owner!(program_id, ctx.config, ctx.sig_admin, ctx.investment);
+    if let Some(purchases) = &ctx.purchases {
+        check_pda_owner!(program_id, purchases);
+    }
     check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Sensitive);
 
     ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
     let mut config = ConfigurationPda::from_account(&ctx.config)?;
-    config.amount_invested = config.amount_invested.saturating_sub(args.amount);
-    config.write(&ctx.admin1)?;
+    config.remove_invested(args.kind, args.amount);
 
     if config.launch_date > 0 && config.launch_date < get_timestamp()? {
         return Err(Error::CancelIcoInvestmentAfterLaunch.into());
@@ -862,23 +1228,28 @@ fn cancel_investment(
     UserInvestmentPda::check_address(args.user, &crate::ID, &ctx.investment)?;
     let mut pda = UserInvestmentPda::from_account(&ctx.investment)?;
     let mut amount = args.amount;
-    // Investments that won't be touched
+    // Investments that won't be touched (the granted tokens follow the investment they were granted for)
     let mut investments: Vec<Investment> = pda
         .investment
         .investments
         .iter()
-        .filter(|elt| elt.kind != args.kind)
+        .filter(|elt| elt.kind != args.kind || elt.is_granted())
         .copied()
         .collect();
 
     // Look for the investements matching the desired type
     // Reduce their amounts until the desired canceled amount is reached
+    let mut cancelled = Vec::new();
     pda.investment
         .investments
         .iter()
-        .filter(|elt| elt.kind == args.kind)
+        .filter(|elt| elt.kind == args.kind && !elt.is_granted())
         .for_each(|elt| {
             let mut elt = *elt;
+            let removed = elt.amount_bought.min(amount);
+            if removed > 0 {
+                cancelled.push((elt, removed));
+            }
             if elt.amount_bought > amount {
                 elt.amount_bought = elt.amount_bought.saturating_sub(amount);
                 amount = 0;
@@ -892,11 +1263,252 @@ fn cancel_investment(
         return Err(Error::InvalidAmount.into());
     }
 
+    // The cancelled tokens are no longer sold, and the tokens granted for them are cancelled too
+    pda.investment.investments = investments;
+    for (elt, removed) in &cancelled {
+        config.cancel_sale(elt.kind, *removed, elt.timestamp);
+        cancel_granted_investments(
+            program_id,
+            &ctx,
+            &mut config,
+            &mut pda.investment,
+            elt,
+            *removed,
+        )?;
+    }
+    config.write(&ctx.admin1)?;
+
+    // The cancelled on-chain purchases can then be refunded
+    let purchased = cancelled
+        .iter()
+        .filter_map(|(elt, removed)| elt.reference.map(|reference| (reference, *removed)))
+        .collect::<Vec<_>>();
+    cancel_purchases(ctx.purchases.as_ref(), &ctx.admin1, &args.user, &purchased)?;
+
     // Save the PDA or delete it if there are no investments left
+    if pda.investment.investments.is_empty() {
+        pda.delete(&ctx.admin1)
+    } else {
+        pda.write(&ctx.admin1)
+    }
+}
+
+/// Cancel the tokens granted for a cancelled investment (bonus, referral reward) in the same
+/// proportion as the investment itself.
+///
+/// # Parameters
+/// * `program_id` - ID of the program,
+/// * `ctx` - Accounts of the instruction,
+/// * `config` - Configuration of the program,
+/// * `investments` - Investments of the user,
+/// * `invest` - The investment, before its cancellation,
+/// * `cancelled` - Number of tokens of the investment cancelled.
+fn cancel_granted_investments(
+    program_id: &Pubkey,
+    ctx: &CancelInvestmentAccounts,
+    config: &mut ConfigurationPda,
+    investments: &mut UserInvestment,
+    invest: &Investment,
+    cancelled: u64,
+) -> ProgramResult {
+    let (Some(InvestmentLink::Origin { referrer }), Some(reference)) =
+        (invest.link, invest.reference)
+    else {
+        return Ok(());
+    };
+    let user = investments.user;
+    debug!("cancelling the tokens granted for the investment");
+    for (kind, amount) in
+        investments.cancel_granted(&user, &reference, invest.amount_bought, cancelled)?
+    {
+        config.remove_invested(kind, amount);
+    }
+    let Some(referrer) = referrer else {
+        return Ok(());
+    };
+
+    let (Some(stats_account), Some(investment_account)) =
+        (&ctx.referrer_stats, &ctx.referrer_investment)
+    else {
+        msg!("the referrer's accounts are missing");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    check_pda_owner!(program_id, stats_account, investment_account);
+    ReferrerStatsPda::check_address(referrer, &crate::ID, stats_account)?;
+    UserInvestmentPda::check_address(referrer, &crate::ID, investment_account)?;
+
+    let mut reward = 0_u64;
+    if investment_account.lamports() > 0 {
+        let mut pda = UserInvestmentPda::from_account(investment_account)?;
+        for (kind, amount) in
+            pda.investment
+                .cancel_granted(&user, &reference, invest.amount_bought, cancelled)?
+        {
+            config.remove_invested(kind, amount);
+            reward = reward.saturating_add(amount);
+        }
+        if pda.investment.investments.is_empty() {
+            pda.delete(&ctx.admin1)?;
+        } else {
+            pda.write(&ctx.admin1)?;
+        }
+    }
+    debug!("cancelling {} tokens of {}'s reward", reward, referrer);
+    if stats_account.lamports() > 0 {
+        let mut stats = ReferrerStatsPda::from_account(stats_account)?;
+        stats.cancel(cancelled, reward);
+        stats.write(&ctx.admin1)?;
+    }
+    if let Some(program) = config.referral.as_mut() {
+        program.cancel(reward);
+    }
+    Ok(())
+}
+
+/// Mark the on-chain purchases of a user whose investments were cancelled as cancelled too,
+/// so that they can be refunded.
+///
+/// The purchases are matched with the investments they created by their reference.
+///
+/// # Parameters
+/// * `account` - Account of the user's purchases (not given by the clients predating the purchases),
+/// * `payer` - Fee payer of the instruction,
+/// * `user` - User owning the purchases,
+/// * `investments` - References of the cancelled investments, with the number of tokens cancelled.
+fn cancel_purchases<'a>(
+    account: Option<&AccountInfo<'a>>,
+    payer: &AccountInfo<'a>,
+    user: &Pubkey,
+    investments: &[([u8; 32], u64)],
+) -> ProgramResult {
+    let Some(account) = account.filter(|account| account.lamports() > 0) else {
+        return Ok(());
+    };
     if investments.is_empty() {
+        return Ok(());
+    }
+    UserPurchasesPda::check_address(user, &crate::ID, account)?;
+    let mut purchases = UserPurchasesPda::from_account(account)?;
+    let cancelled = investments.iter().fold(0_u64, |acc, (reference, amount)| {
+        acc.saturating_add(purchases.cancel(reference, *amount))
+    });
+    if cancelled > 0 {
+        debug!("{} purchased tokens can be refunded", cancelled);
+        purchases.write(payer)?;
+    }
+    Ok(())
+}
+
+/// Cancel a specific investment of a user, entirely or partially.
+fn cancel_investment_entry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &CancelInvestmentEntryArgs,
+) -> ProgramResult {
+    let ctx = CancelInvestmentAccounts::new(accounts)?;
+    msg!("Bangk: cancelling a specific investment of {}", args.user);
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin, ctx.investment);
+    if let Some(purchases) = &ctx.purchases {
+        check_pda_owner!(program_id, purchases);
+    }
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Sensitive);
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+    if config.launch_date > 0 && config.launch_date < get_timestamp()? {
+        return Err(Error::CancelIcoInvestmentAfterLaunch.into());
+    }
+
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(args.user, &crate::ID);
+    if investment_pda != *ctx.investment.key {
+        msg!("invalid user investment PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.investment.lamports() == 0 {
+        return Err(Error::InvestmentDoesNotExist.into());
+    }
+    let mut pda = UserInvestmentPda::from_account(&ctx.investment)?;
+
+    let position = match args.target {
+        InvestmentSelector::Index(index) => usize::try_from(index)
+            .ok()
+            .filter(|position| *position < pda.investment.investments.len()),
+        InvestmentSelector::Reference(reference) => pda
+            .investment
+            .investments
+            .iter()
+            .position(|invest| invest.reference == Some(reference)),
+    };
+    let Some((position, invest)) = position.and_then(|position| {
+        pda.investment
+            .investments
+            .get_mut(position)
+            .map(|invest| (position, invest))
+    }) else {
+        msg!("no investment matches {:?}", args.target);
+        return Err(Error::InvestmentDoesNotExist.into());
+    };
+
+    let original = *invest;
+    let amount_before = invest.amount_bought;
+    let cancelled = args.amount.unwrap_or(amount_before);
+    if cancelled == 0 || cancelled > amount_before {
+        msg!(
+            "cannot cancel {} tokens of an investment of {}",
+            cancelled,
+            amount_before
+        );
+        return Err(Error::InvalidAmount.into());
+    }
+    invest.amount_bought = amount_before.saturating_sub(cancelled);
+    let kind = invest.kind;
+    let timestamp = invest.timestamp;
+    let reference = invest.reference;
+    let removed = invest.amount_bought == 0;
+    if removed {
+        pda.investment.investments.remove(position);
+    }
+
+    config.remove_invested(kind, cancelled);
+    config.cancel_sale(kind, cancelled, timestamp);
+    // The tokens granted for the investment are cancelled with it
+    cancel_granted_investments(
+        program_id,
+        &ctx,
+        &mut config,
+        &mut pda.investment,
+        &original,
+        cancelled,
+    )?;
+    config.write(&ctx.admin1)?;
+
+    // The cancelled on-chain purchases can then be refunded
+    if let Some(reference) = reference {
+        cancel_purchases(
+            ctx.purchases.as_ref(),
+            &ctx.admin1,
+            &args.user,
+            &[(reference, cancelled)],
+        )?;
+    }
+
+    let record = CancelledInvestment {
+        user: args.user,
+        index: u32::try_from(position).map_err(|_err| Error::IntegerOverflow)?,
+        kind,
+        amount_before,
+        cancelled,
+        removed,
+    };
+    msg!("cancelled investment: {:?}", record);
+    set_return_data(&borsh::to_vec(&record)?);
+
+    // Save the PDA or delete it if there are no investments left
+    if pda.investment.investments.is_empty() {
         pda.delete(&ctx.admin1)
     } else {
-        pda.investment.investments = investments;
         pda.write(&ctx.admin1)
     }
 }
@@ -938,6 +1550,9 @@ fn launch_bgk(program_id: &Pubkey, accounts: &[AccountInfo], args: LaunchBGKArgs
     if config.launch_date > 0 {
         return Err(Error::BGKTokenAlreadyLaunched.into());
     }
+    if config.sale_aborted {
+        return Err(Error::SaleAborted.into());
+    }
 
     config.launch_date = args.timestamp;
     config.write(&ctx.admin1)?;
@@ -945,300 +1560,3851 @@ fn launch_bgk(program_id: &Pubkey, accounts: &[AccountInfo], args: LaunchBGKArgs
     Ok(())
 }
 
-struct VestingReleaseAccounts<'a> {
-    api: AccountInfo<'a>,
+struct SetSalePhasesAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
     config: AccountInfo<'a>,
     sig_admin: AccountInfo<'a>,
-    mint_bgk: AccountInfo<'a>,
-    pda_source: AccountInfo<'a>,
-    user: AccountInfo<'a>,
-    investment: AccountInfo<'a>,
-    ata_user: AccountInfo<'a>,
-    program_system: AccountInfo<'a>,
-    program_token: AccountInfo<'a>,
-    _program_ata: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> SetSalePhasesAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Define the phases of the token sale.
+fn set_sale_phases(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetSalePhasesArgs,
+) -> ProgramResult {
+    let ctx = SetSalePhasesAccounts::new(accounts)?;
+    msg!("Bangk: Setting the sale phases");
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+
+    if config.launch_date > 0 {
+        return Err(Error::BGKTokenAlreadyLaunched.into());
+    }
+
+    config.set_sale_phases(&args.phases)?;
+    config.write(&ctx.admin1)?;
+
+    Ok(())
+}
+
+struct SetBonusRulesAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> SetBonusRulesAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Define the bonus granted to the investors.
+fn set_bonus_rules(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetBonusRulesArgs,
+) -> ProgramResult {
+    let ctx = SetBonusRulesAccounts::new(accounts)?;
+    msg!("Bangk: Setting the bonus rules");
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+
+    if config.launch_date > 0 {
+        return Err(Error::BGKTokenAlreadyLaunched.into());
+    }
+
+    config.set_bonus_rules(&args.rules)?;
+    config.write(&ctx.admin1)?;
+
+    Ok(())
+}
+
+struct SetInvestmentCapsAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> SetInvestmentCapsAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Define the caps on the investments.
+fn set_investment_caps(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &SetInvestmentCapsArgs,
+) -> ProgramResult {
+    let ctx = SetInvestmentCapsAccounts::new(accounts)?;
+    msg!("Bangk: Setting the investment caps");
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+
+    config.set_investment_caps(&args.caps)?;
+    config.write(&ctx.admin1)?;
+
+    Ok(())
+}
+
+struct CloseUserInvestmentAccounts<'a> {
+    _payer: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    rent_payer: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> CloseUserInvestmentAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            _payer: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            rent_payer: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Close the record of a user's investments once they are fully released.
+fn close_user_investment(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &CloseUserInvestmentArgs,
+) -> ProgramResult {
+    let ctx = CloseUserInvestmentAccounts::new(accounts)?;
+    msg!("Bangk: Closing the investments of {}", args.user);
+
+    check_pda_owner!(program_id, ctx.sig_admin, ctx.investment);
+    check_signers!(accounts, &ctx.sig_admin);
+
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(args.user, &crate::ID);
+    if investment_pda != *ctx.investment.key {
+        msg!("invalid user investment PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.investment.lamports() == 0 {
+        return Err(Error::InvestmentDoesNotExist.into());
+    }
+    let pda = UserInvestmentPda::from_account(&ctx.investment)?;
+    if pda.payer != *ctx.rent_payer.key {
+        msg!("the rent must be refunded to {}", pda.payer);
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    if !pda.is_fully_released() {
+        return Err(Error::InvestmentNotFullyReleased.into());
+    }
+    // The account keeps the compliance hold of the user's tokenised positions
+    if pda.positions > 0 {
+        msg!("{} vesting positions are not fully released", pda.positions);
+        return Err(Error::InvestmentNotFullyReleased.into());
+    }
+    let now = get_timestamp()?;
+    if pda.is_on_hold(now) {
+        msg!("releases are on hold: {:?}", pda.hold);
+        return Err(Error::ReleasesOnHold.into());
+    }
+
+    let record = CompletedInvestment {
+        user: args.user,
+        investments: u32::try_from(pda.investment.investments.len())
+            .map_err(|_err| Error::IntegerOverflow)?,
+        amount: pda
+            .investment
+            .investments
+            .iter()
+            .fold(0_u64, |total, invest| {
+                total.saturating_add(invest.amount_released)
+            }),
+        closed_at: now,
+    };
+    msg!("completed investments: {:?}", record);
+    set_return_data(&borsh::to_vec(&record)?);
+
+    pda.delete(&ctx.rent_payer)
+}
+
+struct QueueReassignInvestmentAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    timelock: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> QueueReassignInvestmentAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            timelock: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Queue the reassignment of a user's investments to a new wallet.
+fn queue_reassign_investment(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ReassignInvestmentArgs,
+) -> ProgramResult {
+    let ctx = QueueReassignInvestmentAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Queuing reassignment of the investments of {} to {}",
+        args.from,
+        args.to
+    );
+
+    check_pda_owner!(program_id, ctx.sig_admin, ctx.timelock);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    if args.from == args.to {
+        msg!("the investments must be reassigned to a different wallet");
+        return Err(Error::InvalidOperation.into());
+    }
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(args.from, &crate::ID);
+    if investment_pda != *ctx.investment.key {
+        msg!("invalid user investment PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.investment.lamports() == 0 {
+        return Err(Error::InvestmentDoesNotExist.into());
+    }
+
+    // Create the timelocked instruction
+    TimelockPda::check_address(&crate::ID, &ctx.timelock)?;
+    let mut timelock_pda = TimelockPda::from_account(&ctx.timelock)?;
+    let timelock = Timelock::reassign_investment(args.from, args.to)?;
+    timelock_pda.instructions.push(timelock);
+    timelock_pda.write(&ctx.admin1)
+}
+
+struct ExecuteReassignInvestmentAccounts<'a> {
+    payer: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    timelock: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    new_investment: AccountInfo<'a>,
+    rent_payer: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+    purchases: AccountInfo<'a>,
+    new_purchases: AccountInfo<'a>,
+    owner: AccountInfo<'a>,
+    history: AccountInfo<'a>,
+    new_history: AccountInfo<'a>,
+    history_rent_payer: AccountInfo<'a>,
+}
+
+impl<'a> ExecuteReassignInvestmentAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            timelock: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            new_investment: next_account_info(accounts_iter)?.clone(),
+            rent_payer: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+            purchases: next_account_info(accounts_iter)?.clone(),
+            new_purchases: next_account_info(accounts_iter)?.clone(),
+            owner: next_account_info(accounts_iter)?.clone(),
+            history: next_account_info(accounts_iter)?.clone(),
+            new_history: next_account_info(accounts_iter)?.clone(),
+            history_rent_payer: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Move all the investments of a user, with their released amounts, to a new wallet.
+///
+/// The investments are merged with the new wallet's ones if it already has some,
+/// and the record of the previous wallet is closed.
+/// The purchases and the release history of the previous wallet (if any) are moved along, while
+/// its beneficiary is not: the new wallet keeps its own, or has none until its owner sets it.
+/// If both wallets are on hold, the hold expiring last is kept.
+fn execute_reassign_investment(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ReassignInvestmentArgs,
+) -> ProgramResult {
+    let ctx = ExecuteReassignInvestmentAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Reassigning the investments of {} to {}",
+        args.from,
+        args.to
+    );
+
+    check_pda_owner!(
+        program_id,
+        ctx.sig_admin,
+        ctx.timelock,
+        ctx.investment,
+        ctx.purchases,
+        ctx.new_purchases
+    );
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Routine);
+
+    // Check that there’s a queued reassignment, and remove it from the list if found
+    TimelockPda::check_address(&crate::ID, &ctx.timelock)?;
+    let mut timelock = TimelockPda::from_account(&ctx.timelock)?;
+    timelock.process_reassign_investment(&args.from, &args.to, &ctx.payer)?;
+    debug!("queued operation is ready, proceeding");
+
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(args.from, &crate::ID);
+    let (new_investment_pda, new_investment_bump) =
+        UserInvestmentPda::get_address(args.to, &crate::ID);
+    if investment_pda != *ctx.investment.key || new_investment_pda != *ctx.new_investment.key {
+        msg!("invalid user investment PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.investment.lamports() == 0 {
+        return Err(Error::InvestmentDoesNotExist.into());
+    }
+    let pda = UserInvestmentPda::from_account(&ctx.investment)?;
+    if pda.payer != *ctx.rent_payer.key {
+        msg!("the rent must be refunded to {}", pda.payer);
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    // The vesting positions refer to the account of the user whose investment was tokenised
+    if pda.positions > 0 {
+        msg!("{} vesting positions are not fully released", pda.positions);
+        return Err(Error::InvalidOperation.into());
+    }
+
+    debug!(
+        "moving {} investments to the new wallet",
+        pda.investment.investments.len()
+    );
+    // Loaded before anything is written, so that an invalid history doesn't leave the reassignment half done
+    let purchases = load_reassigned_purchases(&ctx, args)?;
+    let (new_history_pda, new_history_bump) = ReleaseHistoryPda::get_address(args.to, &crate::ID);
+    if new_history_pda != *ctx.new_history.key {
+        msg!("invalid release history PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    let history = load_release_history(program_id, &ctx.history, &args.from)?;
+    if let Some(history) = history
+        .as_ref()
+        .filter(|history| history.payer != *ctx.history_rent_payer.key)
+    {
+        msg!(
+            "the rent of the history must be refunded to {}",
+            history.payer
+        );
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+
+    // A compliance hold follows the investments
+    if ctx.new_investment.lamports() == 0 {
+        let mut new_pda = UserInvestmentPda::new(
+            new_investment_bump,
+            UserInvestment {
+                user: args.to,
+                investments: pda.investment.investments.clone(),
+                beneficiary: None,
+            },
+            *ctx.payer.key,
+        );
+        new_pda.hold = pda.hold;
+        new_pda.create(&ctx.new_investment, &ctx.payer, &crate::ID)?;
+    } else {
+        check_pda_owner!(program_id, ctx.new_investment);
+        let mut new_pda = UserInvestmentPda::from_account(&ctx.new_investment)?;
+        new_pda.investment.add(&pda.investment.investments)?;
+        new_pda.hold = match (new_pda.hold, pda.hold) {
+            (Some(new_hold), Some(hold)) if hold.expiry > new_hold.expiry => Some(hold),
+            (new_hold, hold) => new_hold.or(hold),
+        };
+        new_pda.write(&ctx.payer)?;
+    }
+
+    // The purchases follow the investments they created, to be refunded to the new wallet
+    if let Some((purchases, new_purchases_bump)) = purchases {
+        move_purchases(&ctx, args, &purchases, new_purchases_bump)?;
+    }
+
+    if let Some(history) = history {
+        move_release_history(program_id, &ctx, args, &history, new_history_bump)?;
+    }
+
+    pda.delete(&ctx.rent_payer)
+}
+
+/// Load the purchases of the user whose investments are reassigned, checking the accounts they
+/// will be moved to.
+///
+/// # Returns
+/// The purchases (if any), with the bump of the new wallet's purchases PDA.
+fn load_reassigned_purchases<'a>(
+    ctx: &ExecuteReassignInvestmentAccounts<'a>,
+    args: &ReassignInvestmentArgs,
+) -> Result<Option<(UserPurchasesPda<'a>, u8)>, ProgramError> {
+    UserPurchasesPda::check_address(args.from, &crate::ID, &ctx.purchases)?;
+    let (new_purchases_pda, new_purchases_bump) =
+        UserPurchasesPda::get_address(args.to, &crate::ID);
+    if new_purchases_pda != *ctx.new_purchases.key || *ctx.owner.key != args.from {
+        msg!("invalid user purchases PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.purchases.lamports() == 0 {
+        return Ok(None);
+    }
+    Ok(Some((
+        UserPurchasesPda::from_account(&ctx.purchases)?,
+        new_purchases_bump,
+    )))
+}
+
+/// Move the purchases of the user whose investments are reassigned to the new wallet, refunding
+/// the rent of their record to the previous wallet, which paid for it.
+fn move_purchases<'a>(
+    ctx: &ExecuteReassignInvestmentAccounts<'a>,
+    args: &ReassignInvestmentArgs,
+    purchases: &UserPurchasesPda<'a>,
+    new_purchases_bump: u8,
+) -> ProgramResult {
+    debug!(
+        "moving {} purchases to the new wallet",
+        purchases.purchases.len()
+    );
+    if ctx.new_purchases.lamports() == 0 {
+        UserPurchasesPda::new(new_purchases_bump, args.to, purchases.purchases.clone()).create(
+            &ctx.new_purchases,
+            &ctx.payer,
+            &crate::ID,
+        )?;
+    } else {
+        let mut new_purchases = UserPurchasesPda::from_account(&ctx.new_purchases)?;
+        new_purchases
+            .purchases
+            .extend_from_slice(&purchases.purchases);
+        new_purchases.write(&ctx.payer)?;
+    }
+    purchases.delete(&ctx.owner)
+}
+
+/// Move the release history of the user whose investments are reassigned to the new wallet,
+/// refunding the rent of their history to the wallet which paid for it.
+fn move_release_history<'a>(
+    program_id: &Pubkey,
+    ctx: &ExecuteReassignInvestmentAccounts<'a>,
+    args: &ReassignInvestmentArgs,
+    history: &ReleaseHistoryPda<'a>,
+    new_history_bump: u8,
+) -> ProgramResult {
+    debug!(
+        "moving {} releases to the new wallet",
+        history.records.len()
+    );
+    let mut new_history = if ctx.new_history.lamports() == 0 {
+        ReleaseHistoryPda::new(new_history_bump, args.to, *ctx.payer.key)
+    } else {
+        check_pda_owner!(program_id, ctx.new_history);
+        ReleaseHistoryPda::from_account(&ctx.new_history)?
+    };
+    new_history.merge(&history.records, history.last_dropped);
+    if ctx.new_history.lamports() == 0 {
+        new_history.create(&ctx.new_history, &ctx.payer, &crate::ID)?;
+    } else {
+        new_history.write(&ctx.payer)?;
+    }
+    history.delete(&ctx.history_rent_payer)
+}
+
+struct SetBeneficiaryAccounts<'a> {
+    payer: AccountInfo<'a>,
+    user: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    beneficiary: AccountInfo<'a>,
+    ata_beneficiary: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    denylist: AccountInfo<'a>,
+    program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    _program_ata: AccountInfo<'a>,
+}
+
+impl<'a> SetBeneficiaryAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            user: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            beneficiary: next_account_info(accounts_iter)?.clone(),
+            ata_beneficiary: next_account_info(accounts_iter)?.clone(),
+            mint_bgk: next_account_info(accounts_iter)?.clone(),
+            denylist: next_account_info(accounts_iter)?.clone(),
+            program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            _program_ata: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Define (or remove) the wallet receiving a user's released tokens.
+///
+/// The vesting stays attached to the user, only the destination of the releases changes.
+fn set_beneficiary(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetBeneficiaryArgs,
+) -> ProgramResult {
+    let ctx = SetBeneficiaryAccounts::new(accounts)?;
+    msg!("Bangk: Setting the beneficiary of {}", ctx.user.key);
+
+    debug!("Security checks");
+    if !ctx.user.is_signer {
+        msg!("the investor must sign to change their beneficiary");
+        return Err(Error::InvalidSigner.into());
+    }
+    check_pda_owner!(program_id, ctx.investment);
+
+    UserInvestmentPda::check_address(ctx.user.key, &crate::ID, &ctx.investment)?;
+    if ctx.investment.lamports() == 0 {
+        return Err(Error::InvestmentDoesNotExist.into());
+    }
+    let mut investment = UserInvestmentPda::from_account(&ctx.investment)?;
+    if investment.investment.user != *ctx.user.key {
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    let beneficiary = args.beneficiary.filter(|wallet| wallet != ctx.user.key);
+    investment.investment.beneficiary = beneficiary;
+    investment.write(&ctx.payer)?;
+
+    let Some(beneficiary) = beneficiary else {
+        debug!("the released tokens will go to the user");
+        return Ok(());
+    };
+    if beneficiary != *ctx.beneficiary.key {
+        msg!("unexpected beneficiary wallet");
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    check_not_denylisted(program_id, &ctx.denylist, &beneficiary)?;
+
+    // The beneficiary's ATA is needed for the releases, so we make sure it exists
+    if ctx.ata_beneficiary.lamports() > 0 {
+        if get_ata_owner(&ctx.ata_beneficiary)? != beneficiary {
+            return Err(Error::AccountOwnerMismatch.into());
+        }
+        return Ok(());
+    }
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &crate::ID);
+    if mint_address != *ctx.mint_bgk.key {
+        msg!("unexpected address for the BGK mint");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    debug!("creating the beneficiary's ATA");
+    invoke(
+        &create_associated_token_account(
+            ctx.payer.key,
+            &beneficiary,
+            ctx.mint_bgk.key,
+            ctx.program_token.key,
+        ),
+        &[
+            ctx.payer.clone(),
+            ctx.ata_beneficiary.clone(),
+            ctx.beneficiary.clone(),
+            ctx.mint_bgk.clone(),
+            ctx.program_system.clone(),
+            ctx.program_token.clone(),
+        ],
+    )
+}
+
+struct TokenizePositionAccounts<'a> {
+    payer: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    user: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    position: AccountInfo<'a>,
+    mint_position: AccountInfo<'a>,
+    ata_user: AccountInfo<'a>,
+    program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    _program_ata: AccountInfo<'a>,
+}
+
+impl<'a> TokenizePositionAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            user: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            position: next_account_info(accounts_iter)?.clone(),
+            mint_position: next_account_info(accounts_iter)?.clone(),
+            ata_user: next_account_info(accounts_iter)?.clone(),
+            program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            _program_ata: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Move one of a user's investments to a vesting position, and mint the NFT representing it.
+fn tokenize_position(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: TokenizePositionArgs,
+) -> ProgramResult {
+    let ctx = TokenizePositionAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Tokenising the investment #{} of {}",
+        args.index,
+        ctx.user.key
+    );
+
+    debug!("Security checks");
+    check_pda_owner!(program_id, ctx.sig_admin, ctx.investment);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Routine);
+    if !ctx.user.is_signer {
+        msg!("the investor must sign to tokenise their investment");
+        return Err(Error::InvalidSigner.into());
+    }
+
+    UserInvestmentPda::check_address(ctx.user.key, &crate::ID, &ctx.investment)?;
+    if ctx.investment.lamports() == 0 {
+        return Err(Error::InvestmentDoesNotExist.into());
+    }
+    let mut pda = UserInvestmentPda::from_account(&ctx.investment)?;
+    if pda.investment.user != *ctx.user.key {
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    let now = get_timestamp()?;
+    if pda.is_on_hold(now) {
+        msg!("releases are on hold: {:?}", pda.hold);
+        return Err(Error::ReleasesOnHold.into());
+    }
+
+    let index = usize::try_from(args.index).map_err(|_err| Error::IntegerOverflow)?;
+    let Some(investment) = pda.investment.investments.get(index).copied() else {
+        msg!("there is no investment #{}", args.index);
+        return Err(Error::InvestmentDoesNotExist.into());
+    };
+    // Revocable investments must stay with the team member for the leaver events
+    if investment.revocable || investment.amount_released >= investment.amount_bought {
+        msg!("this investment cannot be tokenised");
+        return Err(Error::InvalidOperation.into());
+    }
+
+    let (mint_address, _mint_bump) = get_position_mint_pda(ctx.user.key, args.id);
+    let (position_pda, position_bump) = VestingPositionPda::get_address(mint_address, program_id);
+    if mint_address != *ctx.mint_position.key || position_pda != *ctx.position.key {
+        msg!("invalid vesting position PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.mint_position.lamports() > 0 || ctx.position.lamports() > 0 {
+        return Err(Error::AccountAlreadyExists.into());
+    }
+
+    pda.investment.investments.remove(index);
+    pda.positions = pda.positions.saturating_add(1);
+    pda.write(&ctx.payer)?;
+    VestingPositionPda::new(position_bump, mint_address, *ctx.user.key, investment, now).create(
+        &ctx.position,
+        &ctx.payer,
+        &crate::ID,
+    )?;
+
+    mint_position_nft(&ctx, args.id)
+}
+
+/// Create the mint of the NFT representing a vesting position and mint it (frozen) to the user.
+fn mint_position_nft(ctx: &TokenizePositionAccounts, id: u8) -> ProgramResult {
+    MultiSigPda::check_address(MultiSigType::Admin, &crate::ID, &ctx.sig_admin)?;
+    let admin_sig = MultiSigPda::from_account(&ctx.sig_admin)?;
+    let admin_seeds = admin_sig.seeds();
+    let admin_seeds = admin_seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let mint_seeds = get_position_mint_seeds(ctx.user.key, id);
+    let mint_seeds = mint_seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+    debug!("Creating the position's mint {}", ctx.mint_position.key);
+    let mint_len = ExtensionType::try_calculate_account_len::<Mint>(&[])
+        .map_err(|_err| Error::CrossProgramCallFailed)?;
+    invoke_signed(
+        &create_account(
+            ctx.payer.key,
+            ctx.mint_position.key,
+            Rent::get()?.minimum_balance(mint_len),
+            mint_len as u64,
+            &spl_token_2022::id(),
+        ),
+        &[ctx.payer.clone(), ctx.mint_position.clone()],
+        &[mint_seeds.as_slice()],
+    )?;
+    // The admin MultiSig can freeze the NFT so that its transfers go through the program
+    invoke(
+        &initialize_mint2(
+            &spl_token_2022::id(),
+            ctx.mint_position.key,
+            ctx.sig_admin.key,
+            Some(ctx.sig_admin.key),
+            0,
+        )?,
+        &[ctx.mint_position.clone()],
+    )?;
+
+    debug!("Minting the NFT to the user");
+    invoke(
+        &create_associated_token_account(
+            ctx.payer.key,
+            ctx.user.key,
+            ctx.mint_position.key,
+            ctx.program_token.key,
+        ),
+        &[
+            ctx.payer.clone(),
+            ctx.ata_user.clone(),
+            ctx.user.clone(),
+            ctx.mint_position.clone(),
+            ctx.program_system.clone(),
+            ctx.program_token.clone(),
+        ],
+    )?;
+    invoke_signed(
+        &mint_to(
+            ctx.program_token.key,
+            ctx.mint_position.key,
+            ctx.ata_user.key,
+            ctx.sig_admin.key,
+            &[],
+            1,
+        )?,
+        &[
+            ctx.mint_position.clone(),
+            ctx.ata_user.clone(),
+            ctx.sig_admin.clone(),
+        ],
+        &[admin_seeds.as_slice()],
+    )?;
+    invoke_signed(
+        &freeze_account(
+            ctx.program_token.key,
+            ctx.ata_user.key,
+            ctx.mint_position.key,
+            ctx.sig_admin.key,
+            &[],
+        )?,
+        &[
+            ctx.ata_user.clone(),
+            ctx.mint_position.clone(),
+            ctx.sig_admin.clone(),
+        ],
+        &[admin_seeds.as_slice()],
+    )?;
+
+    // Revoking mint authority, so that there is only ever one NFT
+    invoke_signed(
+        &set_authority(
+            ctx.program_token.key,
+            ctx.mint_position.key,
+            None,
+            AuthorityType::MintTokens,
+            ctx.sig_admin.key,
+            &[],
+        )?,
+        &[ctx.mint_position.clone(), ctx.sig_admin.clone()],
+        &[admin_seeds.as_slice()],
+    )
+}
+
+/// Check that a wallet holds the NFT of a vesting position.
+///
+/// # Parameters
+/// * `ata` - Token account of the wallet for the NFT,
+/// * `holder` - Wallet holding the NFT,
+/// * `mint` - Mint of the NFT.
+///
+/// # Errors
+/// If the token account is not the holder's one for the NFT, or if it's empty.
+fn check_position_holder(ata: &AccountInfo, holder: &Pubkey, mint: &Pubkey) -> ProgramResult {
+    if *ata.owner != spl_token_2022::ID || ata.lamports() == 0 {
+        return Err(Error::ATADoesNotExist.into());
+    }
+    let data = ata.try_borrow_data()?;
+    let state = StateWithExtensions::<Account>::unpack(&data)?.base;
+    if state.owner != *holder || state.mint != *mint || state.amount == 0 {
+        msg!("{} does not hold the vesting position {}", holder, mint);
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    Ok(())
+}
+
+/// Load the investments of a user concerned by a vesting position, checking that they aren't
+/// under a compliance hold.
+///
+/// The holds of both the user whose investment was tokenised and the holder apply to the position:
+/// it can't be claimed or transferred while one of them is active.
+///
+/// # Parameters
+/// * `program_id` - ID of the program,
+/// * `account` - Investment PDA of the user,
+/// * `user` - The user whose investment was tokenised, or the holder of the position,
+/// * `now` - Current timestamp.
+///
+/// # Returns
+/// The investments of the user, if they have any (the user whose investment was tokenised keeps
+/// theirs as long as the position isn't fully released).
+///
+/// # Errors
+/// If the investment PDA is invalid or the releases of the user are on hold.
+fn load_position_investment<'a>(
+    program_id: &Pubkey,
+    account: &AccountInfo<'a>,
+    user: &Pubkey,
+    now: i64,
+) -> Result<Option<UserInvestmentPda<'a>>, ProgramError> {
+    check_pda_owner!(program_id, account);
+    UserInvestmentPda::check_address(user, &crate::ID, account)?;
+    if account.lamports() == 0 {
+        return Ok(None);
+    }
+
+    let investment = UserInvestmentPda::from_account(account)?;
+    if investment.is_on_hold(now) {
+        msg!("releases are on hold: {:?}", investment.hold);
+        return Err(Error::ReleasesOnHold.into());
+    }
+
+    Ok(Some(investment))
+}
+
+/// Record that a vesting position was fully released, so that the account of the user whose
+/// investment was tokenised no longer needs to be kept.
+///
+/// # Parameters
+/// * `investment` - Investments of the user whose investment was tokenised (if any),
+/// * `payer` - Payer of the transaction.
+fn release_position<'a>(
+    investment: Option<UserInvestmentPda<'a>>,
+    payer: &AccountInfo<'a>,
+) -> ProgramResult {
+    let Some(mut investment) = investment else {
+        return Ok(());
+    };
+    investment.positions = investment.positions.saturating_sub(1);
+    investment.write(payer)
+}
+
+struct ClaimPositionAccounts<'a> {
+    payer: AccountInfo<'a>,
+    holder: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    pda_source: AccountInfo<'a>,
+    position: AccountInfo<'a>,
+    ata_nft: AccountInfo<'a>,
+    ata_holder: AccountInfo<'a>,
+    program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    _program_ata: AccountInfo<'a>,
+    kyc: AccountInfo<'a>,
+    denylist: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    holder_investment: AccountInfo<'a>,
+    history: AccountInfo<'a>,
+}
+
+impl<'a> ClaimPositionAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            holder: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            mint_bgk: next_account_info(accounts_iter)?.clone(),
+            pda_source: next_account_info(accounts_iter)?.clone(),
+            position: next_account_info(accounts_iter)?.clone(),
+            ata_nft: next_account_info(accounts_iter)?.clone(),
+            ata_holder: next_account_info(accounts_iter)?.clone(),
+            program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            _program_ata: next_account_info(accounts_iter)?.clone(),
+            kyc: next_account_info(accounts_iter)?.clone(),
+            denylist: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            holder_investment: next_account_info(accounts_iter)?.clone(),
+            history: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Release the vested tokens of a vesting position to the holder of its NFT, possibly only
+/// part of them.
+fn claim_position(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ClaimVestedArgs,
+) -> ProgramResult {
+    let ctx = ClaimPositionAccounts::new(accounts)?;
+    msg!("Bangk: claiming the vested tokens of a vesting position");
+
+    debug!("Security checks");
+    if !ctx.holder.is_signer {
+        msg!("the holder of the position must sign to claim its tokens");
+        return Err(Error::InvalidSigner.into());
+    }
+    if args.max_amount == Some(0) {
+        msg!("the maximum amount to claim must be positive");
+        return Err(Error::InvalidAmount.into());
+    }
+    let config = load_release_config(program_id, &ctx.config, &ctx.sig_admin, &ctx.pda_source)?;
+    check_pda_owner!(program_id, ctx.position);
+    let mut position = VestingPositionPda::from_account(&ctx.position)?;
+    VestingPositionPda::check_address(position.mint, &crate::ID, &ctx.position)?;
+    check_position_holder(&ctx.ata_nft, ctx.holder.key, &position.mint)?;
+    if ctx.ata_holder.lamports() > 0 && get_ata_owner(&ctx.ata_holder)? != *ctx.holder.key {
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+
+    let now = get_timestamp()?;
+    check_kyc_attestation(program_id, &ctx.kyc, ctx.holder.key, now)?;
+    check_not_denylisted(program_id, &ctx.denylist, ctx.holder.key)?;
+    let investment = load_position_investment(program_id, &ctx.investment, &position.user, now)?;
+    load_position_investment(program_id, &ctx.holder_investment, ctx.holder.key, now)?;
+    // Loaded before anything is written, as an invalid history must not leave the release half done
+    let history = load_release_history(program_id, &ctx.history, ctx.holder.key)?;
+
+    let to_release = position
+        .investment
+        .unvested(
+            &config.unvesting,
+            &config.accelerated_per_kind,
+            config.launch_date,
+            now,
+        )?
+        .saturating_sub(position.investment.amount_released)
+        .min(args.max_amount.unwrap_or(u64::MAX));
+    if to_release == 0 {
+        return Ok(());
+    }
+    position.investment.amount_released = position
+        .investment
+        .amount_released
+        .saturating_add(to_release);
+    position.write(&ctx.payer)?;
+    if position.is_fully_released() {
+        release_position(investment, &ctx.payer)?;
+    }
+    let record =
+        ReleaseRecord::from_position(now, position.mint, position.investment.kind, to_release);
+    record_release(&ctx.payer, ctx.holder.key, history, *ctx.holder.key, record)?;
+
+    msg!("releasing {} tokens of the position", to_release);
+    transfer_released_tokens(
+        &ctx.payer,
+        &ctx.holder,
+        &ctx.ata_holder,
+        &ctx.mint_bgk,
+        &ctx.pda_source,
+        &ctx.sig_admin,
+        &ctx.program_system,
+        &ctx.program_token,
+        to_release,
+    )
+}
+
+struct SetPositionTransferPolicyAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> SetPositionTransferPolicyAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Define (or disable) the transfers of the vesting positions.
+fn set_position_transfer_policy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetPositionTransferPolicyArgs,
+) -> ProgramResult {
+    let ctx = SetPositionTransferPolicyAccounts::new(accounts)?;
+    msg!("Bangk: Setting the transfer policy of the vesting positions");
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    if args.policy.is_some_and(|policy| !policy.is_valid()) {
+        return Err(Error::InvalidOperation.into());
+    }
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+    config.position_transfers = args.policy;
+    config.write(&ctx.admin1)
+}
+
+struct TransferPositionAccounts<'a> {
+    payer: AccountInfo<'a>,
+    holder: AccountInfo<'a>,
+    recipient: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    position: AccountInfo<'a>,
+    mint_position: AccountInfo<'a>,
+    ata_holder: AccountInfo<'a>,
+    ata_recipient: AccountInfo<'a>,
+    kyc: AccountInfo<'a>,
+    denylist: AccountInfo<'a>,
+    program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    _program_ata: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    holder_investment: AccountInfo<'a>,
+}
+
+impl<'a> TransferPositionAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            holder: next_account_info(accounts_iter)?.clone(),
+            recipient: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            position: next_account_info(accounts_iter)?.clone(),
+            mint_position: next_account_info(accounts_iter)?.clone(),
+            ata_holder: next_account_info(accounts_iter)?.clone(),
+            ata_recipient: next_account_info(accounts_iter)?.clone(),
+            kyc: next_account_info(accounts_iter)?.clone(),
+            denylist: next_account_info(accounts_iter)?.clone(),
+            program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            _program_ata: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            holder_investment: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+
+    /// Freeze or thaw one of the NFT's token accounts.
+    fn set_frozen(
+        &self,
+        ata: &AccountInfo<'a>,
+        frozen: bool,
+        admin_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let instruction = if frozen {
+            freeze_account(
+                self.program_token.key,
+                ata.key,
+                self.mint_position.key,
+                self.sig_admin.key,
+                &[],
+            )?
+        } else {
+            thaw_account(
+                self.program_token.key,
+                ata.key,
+                self.mint_position.key,
+                self.sig_admin.key,
+                &[],
+            )?
+        };
+        invoke_signed(
+            &instruction,
+            &[
+                ata.clone(),
+                self.mint_position.clone(),
+                self.sig_admin.clone(),
+            ],
+            &[admin_seeds],
+        )
+    }
+}
+
+/// Transfer the NFT of a vesting position, following the restrictions set by the admin `MultiSig`.
+///
+/// The NFT's token accounts are frozen, so they are thawed for the time of the transfer.
+fn transfer_position(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = TransferPositionAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Transferring a vesting position to {}",
+        ctx.recipient.key
+    );
+
+    debug!("Security checks");
+    if !ctx.holder.is_signer {
+        msg!("the holder of the position must sign to transfer it");
+        return Err(Error::InvalidSigner.into());
+    }
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin, ctx.position);
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let config = ConfigurationPda::from_account(&ctx.config)?;
+    let mut position = VestingPositionPda::from_account(&ctx.position)?;
+    VestingPositionPda::check_address(position.mint, &crate::ID, &ctx.position)?;
+    if *ctx.mint_position.key != position.mint {
+        msg!("unexpected mint for the vesting position");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    check_position_holder(&ctx.ata_holder, ctx.holder.key, &position.mint)?;
+    if ctx.recipient.key == ctx.holder.key {
+        return Err(Error::InvalidOperation.into());
+    }
+
+    let now = get_timestamp()?;
+    let policy = config.position_transfers;
+    if !position.is_transferable(policy.as_ref(), now) {
+        msg!("transfer policy: {:?}", policy);
+        return Err(Error::PositionNotTransferable.into());
+    }
+    if policy.is_some_and(|policy| policy.require_kyc) {
+        check_kyc_attestation(program_id, &ctx.kyc, ctx.recipient.key, now)?;
+    }
+    check_not_denylisted(program_id, &ctx.denylist, ctx.recipient.key)?;
+    load_position_investment(program_id, &ctx.investment, &position.user, now)?;
+    load_position_investment(program_id, &ctx.holder_investment, ctx.holder.key, now)?;
+
+    MultiSigPda::check_address(MultiSigType::Admin, &crate::ID, &ctx.sig_admin)?;
+    let admin_sig = MultiSigPda::from_account(&ctx.sig_admin)?;
+    let admin_seeds = admin_sig.seeds();
+    let admin_seeds = admin_seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+    if ctx.ata_recipient.lamports() == 0 {
+        debug!("creating the recipient's ATA");
+        invoke(
+            &create_associated_token_account(
+                ctx.payer.key,
+                ctx.recipient.key,
+                ctx.mint_position.key,
+                ctx.program_token.key,
+            ),
+            &[
+                ctx.payer.clone(),
+                ctx.ata_recipient.clone(),
+                ctx.recipient.clone(),
+                ctx.mint_position.clone(),
+                ctx.program_system.clone(),
+                ctx.program_token.clone(),
+            ],
+        )?;
+    } else {
+        if get_ata_owner(&ctx.ata_recipient)? != *ctx.recipient.key {
+            return Err(Error::AccountOwnerMismatch.into());
+        }
+        // The recipient may have held the position before
+        let frozen = {
+            let data = ctx.ata_recipient.try_borrow_data()?;
+            StateWithExtensions::<Account>::unpack(&data)?
+                .base
+                .is_frozen()
+        };
+        if frozen {
+            ctx.set_frozen(&ctx.ata_recipient, false, &admin_seeds)?;
+        }
+    }
+
+    debug!("transferring the NFT");
+    ctx.set_frozen(&ctx.ata_holder, false, &admin_seeds)?;
+    invoke(
+        &transfer_checked(
+            ctx.program_token.key,
+            ctx.ata_holder.key,
+            ctx.mint_position.key,
+            ctx.ata_recipient.key,
+            ctx.holder.key,
+            &[],
+            1,
+            0,
+        )?,
+        &[
+            ctx.ata_holder.clone(),
+            ctx.mint_position.clone(),
+            ctx.ata_recipient.clone(),
+            ctx.holder.clone(),
+        ],
+    )?;
+    ctx.set_frozen(&ctx.ata_holder, true, &admin_seeds)?;
+    ctx.set_frozen(&ctx.ata_recipient, true, &admin_seeds)?;
+
+    position.held_since = now;
+    position.write(&ctx.payer)
+}
+
+struct CompactInvestmentsAccounts<'a> {
+    payer: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+    investments: Vec<AccountInfo<'a>>,
+}
+
+impl<'a> CompactInvestmentsAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+            investments: accounts_iter.cloned().collect(),
+        })
+    }
+}
+
+/// Merge the investment entries of some users that can be merged, keeping the totals.
+fn compact_investments(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = CompactInvestmentsAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Compacting the investments of {} users",
+        ctx.investments.len()
+    );
+
+    check_pda_owner!(program_id, ctx.sig_admin);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Routine);
+
+    for account in &ctx.investments {
+        check_pda_owner!(program_id, account);
+        if account.lamports() == 0 {
+            return Err(Error::InvestmentDoesNotExist.into());
+        }
+        let mut pda = UserInvestmentPda::from_account(account)?;
+        UserInvestmentPda::check_address(pda.investment.user, &crate::ID, account)?;
+
+        let removed = pda.investment.compact()?;
+        if removed > 0 {
+            debug!(
+                "{} investment entries merged for {}",
+                removed, pda.investment.user
+            );
+            pda.write(&ctx.payer)?;
+        }
+    }
+
+    Ok(())
+}
+
+struct EnableReleaseHistoryAccounts<'a> {
+    payer: AccountInfo<'a>,
+    user: AccountInfo<'a>,
+    history: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> EnableReleaseHistoryAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            user: next_account_info(accounts_iter)?.clone(),
+            history: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Create the PDA keeping the history of a user's releases.
+fn enable_release_history(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = EnableReleaseHistoryAccounts::new(accounts)?;
+    msg!("Bangk: Enabling the release history of {}", ctx.user.key);
+
+    debug!("Security checks");
+    if !ctx.user.is_signer {
+        msg!("the investor must sign to enable their release history");
+        return Err(Error::InvalidSigner.into());
+    }
+
+    let (history_pda, history_bump) = ReleaseHistoryPda::get_address(ctx.user.key, program_id);
+    if history_pda != *ctx.history.key {
+        msg!("invalid release history PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.history.lamports() > 0 {
+        return Err(Error::AccountAlreadyExists.into());
+    }
+
+    ReleaseHistoryPda::new(history_bump, *ctx.user.key, *ctx.payer.key).create(
+        &ctx.history,
+        &ctx.payer,
+        &crate::ID,
+    )
+}
+
+struct AccelerateVestingAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+    investments: Vec<AccountInfo<'a>>,
+}
+
+impl<'a> AccelerateVestingAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+            investments: accounts_iter.cloned().collect(),
+        })
+    }
+}
+
+/// Accelerate the vesting of the investments of some users, the acceleration being recorded
+/// in each investment, or of a whole type of investment, the acceleration being recorded
+/// in the configuration.
+fn accelerate_vesting(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: AccelerateVestingArgs,
+) -> ProgramResult {
+    let ctx = AccelerateVestingAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Accelerating the vesting ({:?}) of {:?} investments",
+        args.acceleration,
+        args.kind
+    );
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    if !args.acceleration.is_valid() {
+        msg!("invalid acceleration rate");
+        return Err(Error::InvalidAmount.into());
+    }
+
+    if ctx.investments.is_empty() {
+        let Some(kind) = args.kind else {
+            msg!("the type of the investments to accelerate is missing");
+            return Err(Error::InvalidOperation.into());
+        };
+        ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+        let mut config = ConfigurationPda::from_account(&ctx.config)?;
+        if !config.accelerate(kind, args.acceleration) {
+            msg!("the {:?} investments are already fully accelerated", kind);
+            return Err(Error::InvalidOperation.into());
+        }
+        return config.write(&ctx.admin1);
+    }
+
+    // Each PDA is read after the previous one was written, so the same one can't be given twice
+    if ctx
+        .investments
+        .iter()
+        .map(|account| account.key)
+        .collect::<HashSet<_>>()
+        .len()
+        != ctx.investments.len()
+    {
+        msg!("an investment PDA was given twice");
+        return Err(Error::InvalidOperation.into());
+    }
+
+    let mut accelerated = 0_u32;
+    for account in &ctx.investments {
+        check_pda_owner!(program_id, account);
+        if account.lamports() == 0 {
+            return Err(Error::InvestmentDoesNotExist.into());
+        }
+        // The tokenised investments are accelerated in their vesting position
+        let pda_type = PdaType::deserialize(&mut &account.try_borrow_data()?[..])?;
+        if pda_type == VestingPositionPda::PDA_TYPE {
+            let mut position = VestingPositionPda::from_account(account)?;
+            VestingPositionPda::check_address(position.mint, &crate::ID, account)?;
+            if args
+                .kind
+                .is_some_and(|kind| kind != position.investment.kind)
+                || !position.investment.accelerate(args.acceleration)
+            {
+                continue;
+            }
+            debug!("vesting accelerated for the position {}", position.mint);
+            accelerated = accelerated.saturating_add(1);
+            position.write(&ctx.admin1)?;
+            continue;
+        }
+        let mut pda = UserInvestmentPda::from_account(account)?;
+        UserInvestmentPda::check_address(pda.investment.user, &crate::ID, account)?;
+
+        let mut updated = false;
+        for invest in &mut pda.investment.investments {
+            if args.kind.is_some_and(|kind| kind != invest.kind) {
+                continue;
+            }
+            if invest.accelerate(args.acceleration) {
+                accelerated = accelerated.saturating_add(1);
+                updated = true;
+            }
+        }
+        if updated {
+            debug!("vesting accelerated for {}", pda.investment.user);
+            pda.write(&ctx.admin1)?;
+        }
+    }
+
+    if accelerated == 0 {
+        msg!("there are no investments to accelerate");
+        return Err(Error::InvalidOperation.into());
+    }
+    msg!("{} investments accelerated", accelerated);
+    Ok(())
+}
+
+struct ComplianceHoldAccounts<'a> {
+    authority: AccountInfo<'a>,
+    sig_multisig: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> ComplianceHoldAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            authority: next_account_info(accounts_iter)?.clone(),
+            sig_multisig: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+
+    /// Check the signatures (compliance `MultiSig`, or Sensitive admin one) and load the
+    /// user's investments.
+    fn load_investment(
+        &self,
+        program_id: &Pubkey,
+        accounts: &[AccountInfo<'a>],
+        user: &Pubkey,
+    ) -> Result<UserInvestmentPda<'a>, ProgramError> {
+        check_pda_owner!(program_id, self.sig_multisig, self.investment);
+        let (compliance_keys_pda, _compliance_bump) =
+            MultiSigPda::get_address(MultiSigType::Compliance, &crate::ID);
+        if *self.sig_multisig.key == compliance_keys_pda {
+            check_signers!(
+                accounts,
+                &self.sig_multisig,
+                OperationSecurityLevel::Routine,
+                MultiSigType::Compliance
+            );
+        } else {
+            MultiSigPda::check_address(MultiSigType::Admin, &crate::ID, &self.sig_multisig)?;
+            check_signers!(
+                accounts,
+                &self.sig_multisig,
+                OperationSecurityLevel::Sensitive
+            );
+        }
+
+        UserInvestmentPda::check_address(user, &crate::ID, &self.investment)?;
+        if self.investment.lamports() == 0 {
+            return Err(Error::InvestmentDoesNotExist.into());
+        }
+        UserInvestmentPda::from_account(&self.investment)
+    }
+}
+
+/// Put the releases of a user's tokens on hold, the vesting going on in the meantime.
+fn set_compliance_hold(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetComplianceHoldArgs,
+) -> ProgramResult {
+    let ctx = ComplianceHoldAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Putting the releases of {} on hold (reason {})",
+        args.user,
+        args.reason
+    );
+
+    let mut pda = ctx.load_investment(program_id, accounts, &args.user)?;
+    if args.expiry <= get_timestamp()? {
+        msg!("the hold must expire in the future");
+        return Err(Error::InvalidOperation.into());
+    }
+    pda.hold = Some(ComplianceHold {
+        reason: args.reason,
+        expiry: args.expiry,
+    });
+    pda.write(&ctx.authority)
+}
+
+/// Lift the hold on the releases of a user's tokens.
+fn clear_compliance_hold(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ClearComplianceHoldArgs,
+) -> ProgramResult {
+    let ctx = ComplianceHoldAccounts::new(accounts)?;
+    msg!("Bangk: Lifting the hold on the releases of {}", args.user);
+
+    let mut pda = ctx.load_investment(program_id, accounts, &args.user)?;
+    if pda.hold.take().is_none() {
+        msg!("the releases are not on hold");
+        return Err(Error::InvalidOperation.into());
+    }
+    pda.write(&ctx.authority)
+}
+
+struct InitializeSaleTreasuryAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    mint_payment: AccountInfo<'a>,
+    treasury: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+}
+
+impl<'a> InitializeSaleTreasuryAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            mint_payment: next_account_info(accounts_iter)?.clone(),
+            treasury: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Create the token account receiving the payments of the on-chain purchases.
+///
+/// The treasury is owned by the admin `MultiSig`.
+fn initialize_sale_treasury(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = InitializeSaleTreasuryAccounts::new(accounts)?;
+    msg!("Bangk: Creating the sale treasury");
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+    if config.payment_mint.is_some() {
+        msg!("the sale treasury has already been initialized");
+        return Err(Error::UniqueOperationAlreadyExecuted.into());
+    }
+
+    spl_token_2022::check_spl_token_program_account(ctx.program_token.key)?;
+    if ctx.mint_payment.owner != ctx.program_token.key {
+        msg!("the payment mint is not owned by the given token program");
+        return Err(Error::InvalidOwner.into());
+    }
+
+    let (treasury, _treasury_bump) = get_sale_treasury_pda(ctx.mint_payment.key);
+    if treasury != *ctx.treasury.key {
+        msg!("invalid sale treasury address");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    debug!("creating the sale treasury");
+    create_admin_token_account(
+        &ctx.admin1,
+        &ctx.treasury,
+        &ctx.mint_payment,
+        &ctx.sig_admin,
+        &ctx.program_token,
+        &get_sale_treasury_seeds(ctx.mint_payment.key),
+    )?;
+
+    config.payment_mint = Some(*ctx.mint_payment.key);
+    config.write(&ctx.admin1)?;
+
+    Ok(())
+}
+
+/// Create a token account at a PDA address, with the admin `MultiSig` as its owner.
+///
+/// # Parameters
+/// * `payer` - Account paying for the rent,
+/// * `account` - Token account to create,
+/// * `mint` - Mint of the token account,
+/// * `sig_admin` - Admin `MultiSig` PDA, owner of the token account,
+/// * `program_token` - Token program owning the mint,
+/// * `seeds` - Seeds of the token account's address.
+fn create_admin_token_account<'a>(
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    sig_admin: &AccountInfo<'a>,
+    program_token: &AccountInfo<'a>,
+    seeds: &[Vec<u8>],
+) -> ProgramResult {
+    invoke(
+        &get_account_data_size(program_token.key, mint.key, &[])?,
+        &[mint.clone()],
+    )?;
+    let Some((_key, data_len)) = get_return_data() else {
+        msg!("could not retrieve account size");
+        return Err(Error::InvalidRawData.into());
+    };
+    let data_len = u64::try_from_slice(&data_len)?;
+    #[allow(clippy::cast_possible_truncation)]
+    let rent = Rent::get()?.minimum_balance(data_len as usize);
+
+    let seeds = seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    invoke_signed(
+        &create_account(payer.key, account.key, rent, data_len, program_token.key),
+        &[payer.clone(), account.clone()],
+        &[seeds.as_slice()],
+    )?;
+    invoke(
+        &initialize_account3(program_token.key, account.key, mint.key, sig_admin.key)?,
+        &[account.clone(), mint.clone()],
+    )
+}
+
+struct PurchaseAccounts<'a> {
+    buyer: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    mint_payment: AccountInfo<'a>,
+    account_buyer: AccountInfo<'a>,
+    treasury: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    purchases: AccountInfo<'a>,
+    kyc: AccountInfo<'a>,
+}
+
+impl<'a> PurchaseAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            buyer: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            mint_payment: next_account_info(accounts_iter)?.clone(),
+            account_buyer: next_account_info(accounts_iter)?.clone(),
+            treasury: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            purchases: next_account_info(accounts_iter)?.clone(),
+            kyc: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Purchase BGK tokens, paid at the price of the running sale phase.
+///
+/// The payment and the creation / update of the user's investment are done atomically.
+#[allow(clippy::too_many_lines)]
+fn purchase(program_id: &Pubkey, accounts: &[AccountInfo], args: PurchaseArgs) -> ProgramResult {
+    let ctx = PurchaseAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Purchase of {} BGK by {}",
+        args.amount,
+        ctx.buyer.key
+    );
+
+    check_pda_owner!(program_id, ctx.config, ctx.investment, ctx.purchases);
+    if !ctx.buyer.is_signer {
+        msg!("the buyer must sign the purchase");
+        return Err(Error::InvalidSigner.into());
+    }
+
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (investment_pda, investment_bump) =
+        UserInvestmentPda::get_address(ctx.buyer.key, &crate::ID);
+    if investment_pda != *ctx.investment.key {
+        msg!("invalid user investment PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+
+    if config.payment_mint != Some(*ctx.mint_payment.key) {
+        msg!("{} is not accepted for the purchases", ctx.mint_payment.key);
+        return Err(Error::UnknownCurrency.into());
+    }
+    let (treasury, _treasury_bump) = get_sale_treasury_pda(ctx.mint_payment.key);
+    if treasury != *ctx.treasury.key {
+        msg!("invalid sale treasury address");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    spl_token_2022::check_spl_token_program_account(ctx.program_token.key)?;
+
+    let now = get_timestamp()?;
+    check_kyc_attestation(program_id, &ctx.kyc, ctx.buyer.key, now)?;
+    if config.launch_date > 0 && config.launch_date <= now {
+        return Err(Error::IcoInvestAfterLaunch.into());
+    }
+    if config.sale_aborted {
+        return Err(Error::SaleAborted.into());
+    }
+    if !args.kind.is_sale() {
+        msg!("{:?} investments cannot be purchased", args.kind);
+        return Err(Error::InvalidOperation.into());
+    }
+    if args.amount == 0 {
+        return Err(Error::InvalidAmount.into());
+    }
+
+    config.add_invested(args.kind, args.amount)?;
+    check_user_investment_cap(&config, &ctx.investment, args.kind, args.amount)?;
+    let Some(phase) = config.record_sale(args.kind, args.amount, now)? else {
+        return Err(Error::NoActiveSalePhase.into());
+    };
+    let cost = phase.cost(args.amount)?;
+    config.write(&ctx.buyer)?;
+
+    let decimals = StateWithExtensions::<Mint>::unpack(&ctx.mint_payment.try_borrow_data()?)?
+        .base
+        .decimals;
+    debug!("transferring {} to the sale treasury", cost);
+    invoke(
+        &transfer_checked(
+            ctx.program_token.key,
+            ctx.account_buyer.key,
+            ctx.mint_payment.key,
+            ctx.treasury.key,
+            ctx.buyer.key,
+            &[],
+            cost,
+            decimals,
+        )?,
+        &[
+            ctx.account_buyer.clone(),
+            ctx.mint_payment.clone(),
+            ctx.treasury.clone(),
+            ctx.buyer.clone(),
+        ],
+    )?;
+
+    // Keep track of the payment so it can be refunded if the investment is cancelled
+    let (purchases_pda, purchases_bump) = UserPurchasesPda::get_address(ctx.buyer.key, &crate::ID);
+    if purchases_pda != *ctx.purchases.key {
+        msg!("invalid user purchases PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    let mut pda = if ctx.purchases.lamports() == 0 {
+        UserPurchasesPda::new(purchases_bump, *ctx.buyer.key, Vec::new())
+    } else {
+        UserPurchasesPda::from_account(&ctx.purchases)?
+    };
+    let index = u32::try_from(pda.purchases.len()).map_err(|_err| Error::IntegerOverflow)?;
+    let reference = get_purchase_reference(ctx.buyer.key, index);
+    pda.purchases.push(Purchase {
+        kind: args.kind,
+        timestamp: now,
+        amount: args.amount,
+        paid: cost,
+        cancelled: 0,
+        refunded: 0,
+        reference,
+    });
+    if ctx.purchases.lamports() == 0 {
+        pda.create(&ctx.purchases, &ctx.buyer, &crate::ID)?;
+    } else {
+        pda.write(&ctx.buyer)?;
+    }
+
+    // The investment is linked to the purchase, to refund it if the investment is cancelled
+    let mut investment = Investment::new(args.kind, args.amount, None)?;
+    investment.reference = Some(reference);
+    add_user_investment(
+        &ctx.investment,
+        &ctx.buyer,
+        *ctx.buyer.key,
+        investment_bump,
+        investment,
+    )
+}
+
+struct AbortSaleAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> AbortSaleAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Abort the token sale.
+///
+/// No more investments can be made, the BGK can't be launched and all the purchases can be refunded.
+fn abort_sale(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = AbortSaleAccounts::new(accounts)?;
+    msg!("Bangk: Aborting the token sale");
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+    if config.launch_date > 0 {
+        return Err(Error::BGKTokenAlreadyLaunched.into());
+    }
+    if config.sale_aborted {
+        msg!("the token sale has already been aborted");
+        return Err(Error::UniqueOperationAlreadyExecuted.into());
+    }
+
+    config.sale_aborted = true;
+    config.write(&ctx.admin1)?;
+
+    Ok(())
+}
+
+struct RefundAccounts<'a> {
+    payer: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    user: AccountInfo<'a>,
+    purchases: AccountInfo<'a>,
+    mint_payment: AccountInfo<'a>,
+    account_user: AccountInfo<'a>,
+    treasury: AccountInfo<'a>,
+    program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    _program_ata: AccountInfo<'a>,
+}
+
+impl<'a> RefundAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            user: next_account_info(accounts_iter)?.clone(),
+            purchases: next_account_info(accounts_iter)?.clone(),
+            mint_payment: next_account_info(accounts_iter)?.clone(),
+            account_user: next_account_info(accounts_iter)?.clone(),
+            treasury: next_account_info(accounts_iter)?.clone(),
+            program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            _program_ata: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Refund the payments of a user's cancelled purchases (or all of them if the sale was aborted).
+///
+/// Can be requested by the user, or by an admin on their behalf.
+fn refund(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = RefundAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Refunding the cancelled purchases of {}",
+        ctx.user.key
+    );
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin, ctx.purchases);
+    if !ctx.user.is_signer {
+        check_signers!(accounts, &ctx.sig_admin);
+    }
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+    if config.payment_mint != Some(*ctx.mint_payment.key) {
+        msg!("{} is not accepted for the purchases", ctx.mint_payment.key);
+        return Err(Error::UnknownCurrency.into());
+    }
+    let (treasury, _treasury_bump) = get_sale_treasury_pda(ctx.mint_payment.key);
+    if treasury != *ctx.treasury.key {
+        msg!("invalid sale treasury address");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    spl_token_2022::check_spl_token_program_account(ctx.program_token.key)?;
+
+    if ctx.purchases.lamports() == 0 {
+        msg!("the user didn't make any purchase");
+        return Err(Error::NothingToRefund.into());
+    }
+    UserPurchasesPda::check_address(ctx.user.key, &crate::ID, &ctx.purchases)?;
+    let mut purchases = UserPurchasesPda::from_account(&ctx.purchases)?;
+    // The purchases cancelled by an aborted sale are no longer sold (the others were removed
+    // from their sale phase when they were cancelled)
+    let aborted = config.sale_aborted;
+    if aborted {
+        for purchase in &purchases.purchases {
+            let remaining = purchase.amount.saturating_sub(purchase.cancelled);
+            config.cancel_sale(purchase.kind, remaining, purchase.timestamp);
+        }
+    }
+    let to_refund = purchases.refund(aborted)?;
+    if to_refund == 0 {
+        return Err(Error::NothingToRefund.into());
+    }
+    purchases.write(&ctx.payer)?;
+    if aborted {
+        config.write(&ctx.payer)?;
+    }
+
+    if ctx.account_user.lamports() > 0 && get_ata_owner(&ctx.account_user)? != *ctx.user.key {
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    if ctx.account_user.lamports() == 0 {
+        debug!("creating the user's ATA");
+        invoke(
+            &create_associated_token_account(
+                ctx.payer.key,
+                ctx.user.key,
+                ctx.mint_payment.key,
+                ctx.program_token.key,
+            ),
+            &[
+                ctx.payer.clone(),
+                ctx.account_user.clone(),
+                ctx.user.clone(),
+                ctx.mint_payment.clone(),
+                ctx.program_system.clone(),
+                ctx.program_token.clone(),
+            ],
+        )?;
+    }
+
+    MultiSigPda::check_address(MultiSigType::Admin, &crate::ID, &ctx.sig_admin)?;
+    let admin_sig = MultiSigPda::from_account(&ctx.sig_admin)?;
+    let admin_seeds = admin_sig.seeds();
+    let admin_seeds = admin_seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let decimals = StateWithExtensions::<Mint>::unpack(&ctx.mint_payment.try_borrow_data()?)?
+        .base
+        .decimals;
+    debug!("refunding {} from the sale treasury", to_refund);
+    invoke_signed(
+        &transfer_checked(
+            ctx.program_token.key,
+            ctx.treasury.key,
+            ctx.mint_payment.key,
+            ctx.account_user.key,
+            ctx.sig_admin.key,
+            &[],
+            to_refund,
+            decimals,
+        )?,
+        &[
+            ctx.treasury.clone(),
+            ctx.mint_payment.clone(),
+            ctx.account_user.clone(),
+            ctx.sig_admin.clone(),
+        ],
+        &[admin_seeds.as_slice()],
+    )
+}
+
+struct SetComplianceMultisigAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    sig_compliance: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> SetComplianceMultisigAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            sig_compliance: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Create or update the keys of the compliance `MultiSig`.
+fn set_compliance_multisig(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetComplianceMultisigArgs,
+) -> ProgramResult {
+    let ctx = SetComplianceMultisigAccounts::new(accounts)?;
+    msg!("Bangk: Setting the compliance MultiSig");
+
+    check_pda_owner!(program_id, ctx.sig_admin, ctx.sig_compliance);
+    MultiSigPda::check_address(MultiSigType::Admin, &crate::ID, &ctx.sig_admin)?;
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    if args.keys.is_empty() {
+        msg!("the compliance multisig needs at least one key");
+        return Err(Error::NotEnoughMultiSigKeys.into());
+    }
+    if args.keys.iter().collect::<HashSet<_>>().len() != args.keys.len() {
+        msg!("duplicated key in compliance multisig definition");
+        return Err(Error::DuplicatedKeyInMultisigDefinition.into());
+    }
+
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (compliance_keys_pda, compliance_bump) =
+        MultiSigPda::get_address(MultiSigType::Compliance, &crate::ID);
+    if compliance_keys_pda != *ctx.sig_compliance.key {
+        msg!("invalid compliance multisig PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    if ctx.sig_compliance.lamports() == 0 {
+        let compliance_sig = MultiSig::new(MultiSigType::Compliance, args.keys);
+        let pda = MultiSigPda::new(compliance_bump, compliance_sig);
+        pda.create(&ctx.sig_compliance, &ctx.admin1, &crate::ID)
+    } else {
+        let mut pda = MultiSigPda::from_account(&ctx.sig_compliance)?;
+        pda.multisig.keys = args.keys;
+        pda.write(&ctx.admin1)
+    }
+}
+
+struct KycAttestationAccounts<'a> {
+    compliance: AccountInfo<'a>,
+    sig_compliance: AccountInfo<'a>,
+    kyc: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> KycAttestationAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            compliance: next_account_info(accounts_iter)?.clone(),
+            sig_compliance: next_account_info(accounts_iter)?.clone(),
+            kyc: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Issue (or renew) a user's KYC attestation.
+fn issue_kyc_attestation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: IssueKycAttestationArgs,
+) -> ProgramResult {
+    let ctx = KycAttestationAccounts::new(accounts)?;
+    msg!("Bangk: Issuing a KYC attestation for {}", args.user);
+
+    check_pda_owner!(program_id, ctx.sig_compliance, ctx.kyc);
+    MultiSigPda::check_address(MultiSigType::Compliance, &crate::ID, &ctx.sig_compliance)?;
+    check_signers!(
+        accounts,
+        &ctx.sig_compliance,
+        OperationSecurityLevel::Routine,
+        MultiSigType::Compliance
+    );
+
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (kyc_pda, kyc_bump) = KycAttestationPda::get_address(args.user, &crate::ID);
+    if kyc_pda != *ctx.kyc.key {
+        msg!("invalid KYC attestation PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    let attestation = KycAttestationPda::new(
+        kyc_bump,
+        args.user,
+        args.level,
+        args.expiry,
+        args.jurisdiction,
+    );
+    if !attestation.is_well_formed(get_timestamp()?) {
+        return Err(Error::InvalidKycAttestation.into());
+    }
+
+    if ctx.kyc.lamports() == 0 {
+        attestation.create(&ctx.kyc, &ctx.compliance, &crate::ID)
+    } else {
+        let mut pda = KycAttestationPda::from_account(&ctx.kyc)?;
+        pda.level = attestation.level;
+        pda.expiry = attestation.expiry;
+        pda.jurisdiction = attestation.jurisdiction;
+        pda.write(&ctx.compliance)
+    }
+}
+
+/// Revoke a user's KYC attestation, its rent is returned to the signer.
+fn revoke_kyc_attestation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RevokeKycAttestationArgs,
+) -> ProgramResult {
+    let ctx = KycAttestationAccounts::new(accounts)?;
+    msg!("Bangk: Revoking the KYC attestation of {}", args.user);
+
+    check_pda_owner!(program_id, ctx.sig_compliance, ctx.kyc);
+    MultiSigPda::check_address(MultiSigType::Compliance, &crate::ID, &ctx.sig_compliance)?;
+    check_signers!(
+        accounts,
+        &ctx.sig_compliance,
+        OperationSecurityLevel::Routine,
+        MultiSigType::Compliance
+    );
+
+    KycAttestationPda::check_address(args.user, &crate::ID, &ctx.kyc)?;
+    if ctx.kyc.lamports() == 0 {
+        return Err(Error::MissingKycAttestation.into());
+    }
+    let attestation = KycAttestationPda::from_account(&ctx.kyc)?;
+    attestation.delete(&ctx.compliance)
+}
+
+struct DenylistAccounts<'a> {
+    compliance: AccountInfo<'a>,
+    sig_compliance: AccountInfo<'a>,
+    denylist: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> DenylistAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            compliance: next_account_info(accounts_iter)?.clone(),
+            sig_compliance: next_account_info(accounts_iter)?.clone(),
+            denylist: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Add a wallet to the sanctions denylist, creating its shard if needed.
+fn add_to_denylist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: DenylistArgs,
+) -> ProgramResult {
+    let ctx = DenylistAccounts::new(accounts)?;
+    msg!("Bangk: Adding {} to the sanctions denylist", args.wallet);
+
+    check_pda_owner!(program_id, ctx.sig_compliance, ctx.denylist);
+    MultiSigPda::check_address(MultiSigType::Compliance, &crate::ID, &ctx.sig_compliance)?;
+    check_signers!(
+        accounts,
+        &ctx.sig_compliance,
+        OperationSecurityLevel::Routine,
+        MultiSigType::Compliance
+    );
+
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let shard = get_denylist_shard(&args.wallet);
+    let (denylist_pda, denylist_bump) = DenylistPda::get_address(shard, &crate::ID);
+    if denylist_pda != *ctx.denylist.key {
+        msg!("invalid denylist PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    let mut denylist = if ctx.denylist.lamports() == 0 {
+        DenylistPda::new(denylist_bump, shard)
+    } else {
+        DenylistPda::from_account(&ctx.denylist)?
+    };
+    if !denylist.add(args.wallet) {
+        msg!("{} is already on the denylist", args.wallet);
+        return Err(Error::UniqueOperationAlreadyExecuted.into());
+    }
+
+    if ctx.denylist.lamports() == 0 {
+        denylist.create(&ctx.denylist, &ctx.compliance, &crate::ID)
+    } else {
+        denylist.write(&ctx.compliance)
+    }
+}
+
+/// Remove a wallet from the sanctions denylist.
+fn remove_from_denylist(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: DenylistArgs,
+) -> ProgramResult {
+    let ctx = DenylistAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Removing {} from the sanctions denylist",
+        args.wallet
+    );
+
+    check_pda_owner!(program_id, ctx.sig_compliance, ctx.denylist);
+    MultiSigPda::check_address(MultiSigType::Compliance, &crate::ID, &ctx.sig_compliance)?;
+    check_signers!(
+        accounts,
+        &ctx.sig_compliance,
+        OperationSecurityLevel::Routine,
+        MultiSigType::Compliance
+    );
+
+    DenylistPda::check_address(get_denylist_shard(&args.wallet), &crate::ID, &ctx.denylist)?;
+    if ctx.denylist.lamports() == 0 {
+        return Err(Error::InvalidOperation.into());
+    }
+    let mut denylist = DenylistPda::from_account(&ctx.denylist)?;
+    if !denylist.remove(&args.wallet) {
+        msg!("{} is not on the denylist", args.wallet);
+        return Err(Error::InvalidOperation.into());
+    }
+    denylist.write(&ctx.compliance)
+}
+
+struct CreateAirdropAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    airdrop: AccountInfo<'a>,
+    claims: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    pda_source: AccountInfo<'a>,
+    vault: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+}
+
+impl<'a> CreateAirdropAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            airdrop: next_account_info(accounts_iter)?.clone(),
+            claims: next_account_info(accounts_iter)?.clone(),
+            mint_bgk: next_account_info(accounts_iter)?.clone(),
+            pda_source: next_account_info(accounts_iter)?.clone(),
+            vault: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Create a Merkle airdrop, transferring its tokens from an internal wallet to its vault.
+fn create_airdrop(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CreateAirdropArgs,
+) -> ProgramResult {
+    let ctx = CreateAirdropAccounts::new(accounts)?;
+    msg!("Bangk: Creating airdrop {}", args.id);
+
+    check_pda_owner!(program_id, ctx.sig_admin, ctx.airdrop, ctx.claims);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (airdrop_pda, airdrop_bump) = AirdropPda::get_address(args.id, &crate::ID);
+    let (claims_pda, claims_bump) = AirdropClaimsPda::get_address(args.id, &crate::ID);
+    let (vault, _vault_bump) = get_airdrop_vault_pda(args.id);
+    if airdrop_pda != *ctx.airdrop.key || claims_pda != *ctx.claims.key || vault != *ctx.vault.key {
+        msg!("invalid airdrop PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.airdrop.lamports() > 0 {
+        msg!("airdrop {} already exists", args.id);
+        return Err(Error::UniqueOperationAlreadyExecuted.into());
+    }
+    if args.source == WalletType::Ico || *ctx.pda_source.key != args.source.get_pda().0 {
+        msg!("invalid source wallet for the airdrop");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    debug!("integrity checks on the airdrop's definition");
+    if args.total == 0 || args.recipients == 0 || args.recipients > MAX_AIRDROP_RECIPIENTS {
+        return Err(Error::InvalidAmount.into());
+    }
+    if args.deadline <= get_timestamp()? {
+        msg!("the airdrop's deadline is in the past");
+        return Err(Error::InvalidOperation.into());
+    }
+    if args
+        .unvesting
+        .is_some_and(|scheme| !scheme.is_valid().unwrap_or(false))
+    {
+        return Err(Error::InvalidUnvestingDefinition.into());
+    }
+
+    debug!("creating the airdrop's vault");
+    create_admin_token_account(
+        &ctx.admin1,
+        &ctx.vault,
+        &ctx.mint_bgk,
+        &ctx.sig_admin,
+        &ctx.program_token,
+        &get_airdrop_vault_seeds(args.id),
+    )?;
+    AirdropPda::new(
+        airdrop_bump,
+        args.id,
+        args.source,
+        args.root,
+        args.total,
+        args.recipients,
+        args.deadline,
+        args.unvesting,
+    )
+    .create(&ctx.airdrop, &ctx.admin1, &crate::ID)?;
+    AirdropClaimsPda::new(claims_bump, args.id, args.recipients).create(
+        &ctx.claims,
+        &ctx.admin1,
+        &crate::ID,
+    )?;
+
+    debug!("funding the airdrop");
+    transfer_from_internal_wallet(
+        &ctx.program_token,
+        &ctx.pda_source,
+        &ctx.mint_bgk,
+        &ctx.vault,
+        &ctx.sig_admin,
+        args.total,
+    )
+}
+
+struct ClaimAirdropAccounts<'a> {
+    claimant: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    airdrop: AccountInfo<'a>,
+    claims: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    vault: AccountInfo<'a>,
+    ata_claimant: AccountInfo<'a>,
+    pda_invested: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    _program_ata: AccountInfo<'a>,
+    denylist: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+}
+
+impl<'a> ClaimAirdropAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            claimant: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            airdrop: next_account_info(accounts_iter)?.clone(),
+            claims: next_account_info(accounts_iter)?.clone(),
+            mint_bgk: next_account_info(accounts_iter)?.clone(),
+            vault: next_account_info(accounts_iter)?.clone(),
+            ata_claimant: next_account_info(accounts_iter)?.clone(),
+            pda_invested: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            _program_ata: next_account_info(accounts_iter)?.clone(),
+            denylist: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Claim tokens from a Merkle airdrop.
+///
+/// The tokens are sent to the claimant's ATA, or added to their investments if the airdrop
+/// is subject to an unvesting scheme.
+fn claim_airdrop(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &ClaimAirdropArgs,
+) -> ProgramResult {
+    let ctx = ClaimAirdropAccounts::new(accounts)?;
+    msg!("Bangk: Claiming tokens from airdrop {}", args.id);
+
+    check_pda_owner!(
+        program_id,
+        ctx.sig_admin,
+        ctx.airdrop,
+        ctx.claims,
+        ctx.investment
+    );
+    if !ctx.claimant.is_signer {
+        msg!("the recipient must sign to claim their tokens");
+        return Err(Error::InvalidSigner.into());
+    }
+
+    AirdropPda::check_address(args.id, &crate::ID, &ctx.airdrop)?;
+    AirdropClaimsPda::check_address(args.id, &crate::ID, &ctx.claims)?;
+    let (vault, _vault_bump) = get_airdrop_vault_pda(args.id);
+    if vault != *ctx.vault.key {
+        msg!("invalid airdrop vault");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    let mut airdrop = AirdropPda::from_account(&ctx.airdrop)?;
+    if !airdrop.is_open(get_timestamp()?) {
+        return Err(Error::AirdropClosed.into());
+    }
+    check_not_denylisted(program_id, &ctx.denylist, ctx.claimant.key)?;
+
+    debug!("checking the Merkle proof");
+    let leaf = get_airdrop_leaf(args.index, ctx.claimant.key, args.amount);
+    if !verify_merkle_proof(leaf, &args.proof, &airdrop.root) {
+        return Err(Error::InvalidMerkleProof.into());
+    }
+    let mut claims = AirdropClaimsPda::from_account(&ctx.claims)?;
+    claims.set_claimed(args.index)?;
+    airdrop.claim(args.amount)?;
+    claims.write(&ctx.claimant)?;
+    airdrop.write(&ctx.claimant)?;
+
+    let target = if let Some(scheme) = airdrop.unvesting {
+        debug!("the claimed tokens will be vested");
+        // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+        let (investment_pda, investment_bump) =
+            UserInvestmentPda::get_address(ctx.claimant.key, &crate::ID);
+        if investment_pda != *ctx.investment.key
+            || *ctx.pda_invested.key != WalletType::Ico.get_pda().0
+        {
+            msg!("invalid investment PDA");
+            return Err(Error::InvalidPdaAddress.into());
+        }
+        check_pda_owner!(program_id, ctx.config);
+        ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+        // Like the bonuses, the vested tokens are released from the `Ico` wallet
+        let mut config = ConfigurationPda::from_account(&ctx.config)?;
+        config.add_invested(scheme.kind, args.amount)?;
+        config.write(&ctx.claimant)?;
+        add_user_investment(
+            &ctx.investment,
+            &ctx.claimant,
+            *ctx.claimant.key,
+            investment_bump,
+            Investment::new(scheme.kind, args.amount, Some(scheme))?,
+        )?;
+        &ctx.pda_invested
+    } else {
+        if ctx.ata_claimant.lamports() > 0 && get_ata_owner(&ctx.ata_claimant)? != *ctx.claimant.key
+        {
+            return Err(Error::AccountOwnerMismatch.into());
+        }
+        if ctx.ata_claimant.lamports() == 0 {
+            debug!("creating the claimant's ATA since it doesn't yet exist");
+            invoke(
+                &create_associated_token_account(
+                    ctx.claimant.key,
+                    ctx.claimant.key,
+                    ctx.mint_bgk.key,
+                    ctx.program_token.key,
+                ),
+                &[
+                    ctx.claimant.clone(),
+                    ctx.ata_claimant.clone(),
+                    ctx.claimant.clone(),
+                    ctx.mint_bgk.clone(),
+                    ctx.program_system.clone(),
+                    ctx.program_token.clone(),
+                ],
+            )?;
+        }
+        &ctx.ata_claimant
+    };
+
+    transfer_from_internal_wallet(
+        &ctx.program_token,
+        &ctx.vault,
+        &ctx.mint_bgk,
+        target,
+        &ctx.sig_admin,
+        args.amount,
+    )
+}
+
+struct ReclaimAirdropAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    airdrop: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    vault: AccountInfo<'a>,
+    pda_source: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+}
+
+impl<'a> ReclaimAirdropAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            airdrop: next_account_info(accounts_iter)?.clone(),
+            mint_bgk: next_account_info(accounts_iter)?.clone(),
+            vault: next_account_info(accounts_iter)?.clone(),
+            pda_source: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Return the unclaimed tokens of an airdrop to its source wallet once its deadline has passed.
+fn reclaim_airdrop(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ReclaimAirdropArgs,
+) -> ProgramResult {
+    let ctx = ReclaimAirdropAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Reclaiming the unclaimed tokens of airdrop {}",
+        args.id
+    );
+
+    check_pda_owner!(program_id, ctx.sig_admin, ctx.airdrop);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Sensitive);
+
+    AirdropPda::check_address(args.id, &crate::ID, &ctx.airdrop)?;
+    let (vault, _vault_bump) = get_airdrop_vault_pda(args.id);
+    if vault != *ctx.vault.key {
+        msg!("invalid airdrop vault");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    let mut airdrop = AirdropPda::from_account(&ctx.airdrop)?;
+    if airdrop.is_open(get_timestamp()?) {
+        return Err(Error::AirdropNotClosed.into());
+    }
+    if airdrop.reclaimed {
+        msg!("the airdrop's tokens have already been reclaimed");
+        return Err(Error::UniqueOperationAlreadyExecuted.into());
+    }
+    if *ctx.pda_source.key != airdrop.source.get_pda().0 {
+        msg!("unexpected address for wallet PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    let unclaimed = airdrop.total.saturating_sub(airdrop.claimed);
+    airdrop.reclaimed = true;
+    airdrop.write(&ctx.admin1)?;
+    if unclaimed == 0 {
+        return Ok(());
+    }
+
+    transfer_from_internal_wallet(
+        &ctx.program_token,
+        &ctx.vault,
+        &ctx.mint_bgk,
+        &ctx.pda_source,
+        &ctx.sig_admin,
+        unclaimed,
+    )
+}
+
+struct SetReferralProgramAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> SetReferralProgramAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Define (or stop) the referral program.
+///
+/// The rewards already granted are kept when the program is redefined.
+fn set_referral_program(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: SetReferralProgramArgs,
+) -> ProgramResult {
+    let ctx = SetReferralProgramAccounts::new(accounts)?;
+    msg!("Bangk: Setting the referral program");
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+
+    let current = config.referral;
+    let (rewarded, funded) = current.map_or((0, 0), |old| (old.rewarded, old.funded));
+    if rewarded > funded && current.map(|old| old.source) != args.program.map(|new| new.source) {
+        msg!("the rewards granted so far must be funded before changing their source");
+        return Err(Error::InvalidOperation.into());
+    }
+    config.referral = match args.program {
+        None => None,
+        Some(program) => {
+            if !program.is_valid() {
+                return Err(Error::InvalidOperation.into());
+            }
+            Some(ReferralProgram {
+                rewarded,
+                funded,
+                ..program
+            })
+        }
+    };
+    config.write(&ctx.admin1)
+}
+
+struct FundReferralRewardsAccounts<'a> {
+    payer: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    pda_source: AccountInfo<'a>,
+    pda_invested: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+}
+
+impl<'a> FundReferralRewardsAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            mint_bgk: next_account_info(accounts_iter)?.clone(),
+            pda_source: next_account_info(accounts_iter)?.clone(),
+            pda_invested: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Transfer the referral rewards granted so far from their source wallet to the `Ico` wallet,
+/// from which they will be released.
+fn fund_referral_rewards(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = FundReferralRewardsAccounts::new(accounts)?;
+    msg!("Bangk: Funding the referral rewards");
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Routine);
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+    let Some(program) = config.referral.as_mut() else {
+        msg!("there is no referral program");
+        return Err(Error::InvalidOperation.into());
+    };
+    if *ctx.pda_source.key != program.source.get_pda().0
+        || *ctx.pda_invested.key != WalletType::Ico.get_pda().0
+    {
+        msg!("unexpected address for wallet PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    let amount = program.rewarded.saturating_sub(program.funded);
+    if amount == 0 {
+        return Ok(());
+    }
+    program.funded = program.rewarded;
+    config.write(&ctx.payer)?;
+
+    transfer_from_internal_wallet(
+        &ctx.program_token,
+        &ctx.pda_source,
+        &ctx.mint_bgk,
+        &ctx.pda_invested,
+        &ctx.sig_admin,
+        amount,
+    )
+}
+
+struct VestingReleaseAccounts<'a> {
+    payer: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    pda_source: AccountInfo<'a>,
+    user: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    ata_user: AccountInfo<'a>,
+    program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    _program_ata: AccountInfo<'a>,
+    kyc: AccountInfo<'a>,
+    denylist: AccountInfo<'a>,
+    denylist_destination: AccountInfo<'a>,
+    history: AccountInfo<'a>,
 }
 
 impl<'a> VestingReleaseAccounts<'a> {
     fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
         let accounts_iter = &mut accounts.iter();
         Ok(Self {
-            api: next_account_info(accounts_iter)?.clone(),
+            payer: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            mint_bgk: next_account_info(accounts_iter)?.clone(),
+            pda_source: next_account_info(accounts_iter)?.clone(),
+            user: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            ata_user: next_account_info(accounts_iter)?.clone(),
+            program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            _program_ata: next_account_info(accounts_iter)?.clone(),
+            kyc: next_account_info(accounts_iter)?.clone(),
+            denylist: next_account_info(accounts_iter)?.clone(),
+            denylist_destination: next_account_info(accounts_iter)?.clone(),
+            history: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Release vested tokens
+fn vesting_release(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let ctx = VestingReleaseAccounts::new(accounts)?;
+    msg!("Bangk: releasing vested tokens");
+
+    debug!("Security checks");
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Routine);
+
+    release_vested_tokens(program_id, &ctx, None)
+}
+
+/// Release vested tokens on the investor's own request, possibly only part of them.
+fn claim_vested(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ClaimVestedArgs,
+) -> ProgramResult {
+    let ctx = VestingReleaseAccounts::new(accounts)?;
+    msg!("Bangk: claiming vested tokens");
+
+    debug!("Security checks");
+    if !ctx.user.is_signer {
+        msg!("the investor must sign to claim their tokens");
+        return Err(Error::InvalidSigner.into());
+    }
+    if args.max_amount == Some(0) {
+        msg!("the maximum amount to claim must be positive");
+        return Err(Error::InvalidAmount.into());
+    }
+
+    release_vested_tokens(program_id, &ctx, args.max_amount)
+}
+
+struct BatchVestingReleaseAccounts<'a> {
+    payer: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    pda_source: AccountInfo<'a>,
+    program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    program_ata: AccountInfo<'a>,
+    users: Vec<AccountInfo<'a>>,
+}
+
+impl<'a> BatchVestingReleaseAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            mint_bgk: next_account_info(accounts_iter)?.clone(),
+            pda_source: next_account_info(accounts_iter)?.clone(),
+            program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            program_ata: next_account_info(accounts_iter)?.clone(),
+            users: accounts_iter.cloned().collect(),
+        })
+    }
+
+    /// Get the accounts needed to release the tokens of each user of the batch.
+    ///
+    /// # Parameters
+    /// * `beneficiary_shards` - For each user, whether the denylist shard of their beneficiary
+    ///   follows their accounts (their own shard being used otherwise).
+    fn releases(
+        &self,
+        beneficiary_shards: &[bool],
+    ) -> Result<Vec<VestingReleaseAccounts<'a>>, ProgramError> {
+        let accounts_iter = &mut self.users.iter();
+        let mut releases = Vec::with_capacity(beneficiary_shards.len());
+        for beneficiary_shard in beneficiary_shards {
+            let user = next_account_info(accounts_iter)?;
+            let investment = next_account_info(accounts_iter)?;
+            let ata_user = next_account_info(accounts_iter)?;
+            let kyc = next_account_info(accounts_iter)?;
+            let denylist = next_account_info(accounts_iter)?;
+            let history = next_account_info(accounts_iter)?;
+            let denylist_destination = if *beneficiary_shard {
+                next_account_info(accounts_iter)?
+            } else {
+                denylist
+            };
+            releases.push(VestingReleaseAccounts {
+                payer: self.payer.clone(),
+                config: self.config.clone(),
+                sig_admin: self.sig_admin.clone(),
+                mint_bgk: self.mint_bgk.clone(),
+                pda_source: self.pda_source.clone(),
+                user: user.clone(),
+                investment: investment.clone(),
+                ata_user: ata_user.clone(),
+                program_system: self.program_system.clone(),
+                program_token: self.program_token.clone(),
+                _program_ata: self.program_ata.clone(),
+                kyc: kyc.clone(),
+                denylist: denylist.clone(),
+                denylist_destination: denylist_destination.clone(),
+                history: history.clone(),
+            });
+        }
+        if accounts_iter.next().is_some() {
+            msg!(
+                "each user needs {} accounts, plus the shard of their beneficiary if needed",
+                BATCH_RELEASE_ACCOUNTS_PER_USER
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(releases)
+    }
+}
+
+/// Release vested tokens for several users.
+///
+/// Users that can't be processed are skipped, and reported in the return data.
+fn batch_vesting_release(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &BatchVestingReleaseArgs,
+) -> ProgramResult {
+    let ctx = BatchVestingReleaseAccounts::new(accounts)?;
+    msg!("Bangk: releasing vested tokens in batch");
+    let releases = ctx.releases(&args.beneficiary_shards)?;
+
+    debug!("Security checks");
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Routine);
+    let config = load_release_config(program_id, &ctx.config, &ctx.sig_admin, &ctx.pda_source)?;
+
+    debug!("Getting Timestamp");
+    let now = get_timestamp()?;
+
+    let mut skipped = Vec::new();
+    for release in releases {
+        match release_user_tokens(program_id, &release, &config, now, None) {
+            Ok(amount) => {
+                debug!("released {} tokens for {}", amount, release.user.key);
+            }
+            Err(err) => {
+                msg!("skipping release for {}: {}", release.user.key, err);
+                skipped.push(SkippedRelease {
+                    user: *release.user.key,
+                    reason: err.into(),
+                });
+            }
+        }
+    }
+
+    set_return_data(&borsh::to_vec(&skipped)?);
+    Ok(())
+}
+
+/// Transfer to the user the tokens that were unvested since the last release.
+///
+/// Authorization checks must be done by the caller.
+fn release_vested_tokens(
+    program_id: &Pubkey,
+    ctx: &VestingReleaseAccounts,
+    max_amount: Option<u64>,
+) -> ProgramResult {
+    let config = load_release_config(program_id, &ctx.config, &ctx.sig_admin, &ctx.pda_source)?;
+
+    debug!("Getting Timestamp");
+    let now = get_timestamp()?;
+
+    release_user_tokens(program_id, ctx, &config, now, max_amount)?;
+    Ok(())
+}
+
+/// Load the program's configuration and check the accounts common to all releases.
+fn load_release_config<'a>(
+    program_id: &Pubkey,
+    config: &AccountInfo<'a>,
+    sig_admin: &AccountInfo<'a>,
+    pda_source: &AccountInfo<'a>,
+) -> Result<ConfigurationPda<'a>, ProgramError> {
+    check_pda_owner!(program_id, config, sig_admin);
+    // The admin MultiSig signs the transfers: it must be checked before anything is written
+    MultiSigPda::check_address(MultiSigType::Admin, &crate::ID, sig_admin)?;
+
+    debug!("Reading configuration");
+    ConfigurationPda::check_address(&crate::ID, config)?;
+    let config = ConfigurationPda::from_account(config)?;
+
+    if config.launch_date == 0 {
+        return Err(Error::IcoUnvestBeforeLaunch.into());
+    }
+    if *pda_source.key != WalletType::Ico.get_pda().0 {
+        msg!("unexpected address for wallet PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    Ok(config)
+}
+
+/// Load the release history of the user, if they enabled it.
+fn load_release_history<'a>(
+    program_id: &Pubkey,
+    account: &AccountInfo<'a>,
+    user: &Pubkey,
+) -> Result<Option<ReleaseHistoryPda<'a>>, ProgramError> {
+    // The history is checked even if it doesn't exist, so that it can't be bypassed
+    ReleaseHistoryPda::check_address(user, &crate::ID, account)?;
+    if account.lamports() == 0 {
+        return Ok(None);
+    }
+    check_pda_owner!(program_id, account);
+    Ok(Some(ReleaseHistoryPda::from_account(account)?))
+}
+
+/// Log a release as an event, and add it to the user's release history if they have one.
+fn record_release<'a>(
+    payer: &AccountInfo<'a>,
+    user: &Pubkey,
+    history: Option<ReleaseHistoryPda<'a>>,
+    destination: Pubkey,
+    record: ReleaseRecord,
+) -> ProgramResult {
+    if let Some(mut history) = history {
+        history.record(record.clone());
+        history.write(payer)?;
+    }
+
+    let event = ReleaseEvent {
+        user: *user,
+        destination,
+        record,
+    };
+    msg!("release: {:?}", event.record);
+    sol_log_data(&[RELEASE_EVENT_TAG, &borsh::to_vec(&event)?]);
+    Ok(())
+}
+
+/// Transfer to a user the tokens unvested since the last release (up to `max_amount`),
+/// returning their number.
+fn release_user_tokens(
+    program_id: &Pubkey,
+    ctx: &VestingReleaseAccounts,
+    config: &ConfigurationPda,
+    now: i64,
+    max_amount: Option<u64>,
+) -> Result<u64, ProgramError> {
+    check_pda_owner!(program_id, ctx.investment);
+
+    debug!("Reading PDA data");
+    UserInvestmentPda::check_address(ctx.user.key, &crate::ID, &ctx.investment)?;
+    let mut investment = UserInvestmentPda::from_account(&ctx.investment)?;
+
+    debug!("Integrity checks");
+    check_ata_exists!(ctx.investment);
+    if investment.investment.user != *ctx.user.key {
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    // The tokens go to the beneficiary if the user defined one
+    let destination = investment.investment.destination();
+    if ctx.ata_user.lamports() > 0 && get_ata_owner(&ctx.ata_user)? != destination {
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    if ctx.ata_user.lamports() == 0 && destination != *ctx.user.key {
+        msg!("the ATA of the beneficiary {} does not exist", destination);
+        return Err(Error::ATADoesNotExist.into());
+    }
+    check_kyc_attestation(program_id, &ctx.kyc, ctx.user.key, now)?;
+    check_not_denylisted(program_id, &ctx.denylist, ctx.user.key)?;
+    if destination != *ctx.user.key {
+        check_not_denylisted(program_id, &ctx.denylist_destination, &destination)?;
+    }
+    if investment.is_on_hold(now) {
+        msg!("releases are on hold: {:?}", investment.hold);
+        return Err(Error::ReleasesOnHold.into());
+    }
+    // Loaded before anything is written, as an invalid history must not leave the release half done
+    let history = load_release_history(program_id, &ctx.history, ctx.user.key)?;
+
+    // Get the number of tokens that should be released for the user.
+    let entries = investment.investment.release_entries(
+        &config.unvesting,
+        &config.accelerated_per_kind,
+        config.launch_date,
+        now,
+        max_amount,
+    )?;
+    let record = ReleaseRecord::new(now, entries);
+    let to_release = record.amount;
+
+    if to_release == 0 {
+        return Ok(0);
+    }
+    investment.write(&ctx.payer)?;
+    record_release(&ctx.payer, ctx.user.key, history, destination, record)?;
+
+    transfer_released_tokens(
+        &ctx.payer,
+        &ctx.user,
+        &ctx.ata_user,
+        &ctx.mint_bgk,
+        &ctx.pda_source,
+        &ctx.sig_admin,
+        &ctx.program_system,
+        &ctx.program_token,
+        to_release,
+    )?;
+    Ok(to_release)
+}
+
+/// Transfer released tokens from the `Ico` wallet to the ATA of their recipient, creating the
+/// ATA (owned by `owner`) if needed.
+///
+/// Authorization checks (including the address of the admin `MultiSig`) must be done by the caller,
+/// see `load_release_config`.
+#[allow(clippy::too_many_arguments)]
+fn transfer_released_tokens<'a>(
+    payer: &AccountInfo<'a>,
+    owner: &AccountInfo<'a>,
+    ata: &AccountInfo<'a>,
+    mint_bgk: &AccountInfo<'a>,
+    pda_source: &AccountInfo<'a>,
+    sig_admin: &AccountInfo<'a>,
+    program_system: &AccountInfo<'a>,
+    program_token: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    // Transferring the required amount of tokens from the invested ATA to the recipient's ATA
+    if ata.lamports() == 0 {
+        // Creating the ATA
+        debug!("creating the recipient's ATA");
+        invoke(
+            &create_associated_token_account(payer.key, owner.key, mint_bgk.key, program_token.key),
+            &[
+                payer.clone(),
+                ata.clone(),
+                owner.clone(),
+                mint_bgk.clone(),
+                program_system.clone(),
+                program_token.clone(),
+            ],
+        )?;
+    }
+
+    let admin_sig = MultiSigPda::from_account(sig_admin)?;
+    let admin_seeds = admin_sig.seeds();
+    let admin_seeds = admin_seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+    debug!(
+        "transferring {} tokens from the invested ATA to the recipient's ATA",
+        amount
+    );
+    invoke_signed(
+        &transfer_checked(
+            program_token.key,
+            pda_source.key,
+            mint_bgk.key,
+            ata.key,
+            sig_admin.key,
+            &[],
+            amount,
+            6,
+        )?,
+        &[
+            pda_source.clone(),
+            mint_bgk.clone(),
+            ata.clone(),
+            sig_admin.clone(),
+        ],
+        &[admin_seeds.as_slice()],
+    )?;
+    Ok(())
+}
+
+struct QueueTransferFromReserveAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    timelock: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> QueueTransferFromReserveAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            timelock: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Transfer BGK tokens from Bangk's reserve.
+fn queue_transfer_from_reserve(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: QueueTransferFromInternalWalletArgs,
+) -> ProgramResult {
+    let ctx = QueueTransferFromReserveAccounts::new(accounts)?;
+    msg!("Bangk: Queue tranfering BGK tokens from Bangk's reserve");
+
+    check_pda_owner!(program_id, ctx.sig_admin, ctx.timelock);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    TimelockPda::check_address(&crate::ID, &ctx.timelock)?;
+    let mut timelock_pda = TimelockPda::from_account(&ctx.timelock)?;
+    // Create the timelocked instruction
+    let timelock = Timelock::transfer_from_internal_wallet(args.source, args.target, args.amount)?;
+    timelock_pda.instructions.push(timelock);
+    timelock_pda.write(&ctx.admin1)
+}
+
+struct ExecuteTransferFromReserveAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    timelock: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    pda_source: AccountInfo<'a>,
+    user: AccountInfo<'a>,
+    ata_target: AccountInfo<'a>,
+    program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    _program_ata: AccountInfo<'a>,
+    denylist: AccountInfo<'a>,
+}
+
+impl<'a> ExecuteTransferFromReserveAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            timelock: next_account_info(accounts_iter)?.clone(),
+            mint_bgk: next_account_info(accounts_iter)?.clone(),
+            pda_source: next_account_info(accounts_iter)?.clone(),
+            user: next_account_info(accounts_iter)?.clone(),
+            ata_target: next_account_info(accounts_iter)?.clone(),
+            program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            _program_ata: next_account_info(accounts_iter)?.clone(),
+            denylist: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Transfer BGK tokens from Bangk's reserve.
+fn execute_transfer_from_reserve(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ExecuteTransferFromInternalWalletArgs,
+) -> ProgramResult {
+    let ctx = ExecuteTransferFromReserveAccounts::new(accounts)?;
+    msg!("Bangk: Tranfering BGK tokens from Bangk's reserve");
+
+    check_pda_owner!(program_id, ctx.sig_admin, ctx.timelock);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Routine);
+
+    debug!("integrity check on the source wallet");
+    if *ctx.pda_source.key != args.source.get_pda().0 {
+        msg!("unexpected address for wallet PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    debug!("checking that the owner of the target ATA isn't under sanctions");
+    let owner = if ctx.ata_target.lamports() == 0 {
+        *ctx.user.key
+    } else {
+        get_ata_owner(&ctx.ata_target)?
+    };
+    check_not_denylisted(program_id, &ctx.denylist, &owner)?;
+
+    // Check that there’s a queued transfer, and remove it from the list if found
+    TimelockPda::check_address(&crate::ID, &ctx.timelock)?;
+    let mut timelock = TimelockPda::from_account(&ctx.timelock)?;
+    timelock.process_transfer_from_internal_wallet(
+        args.source,
+        ctx.ata_target.key,
+        args.amount,
+        &ctx.admin1,
+    )?;
+    debug!("queued operation is ready, proceeding");
+
+    // Transferring the required amount of tokens from the reserve ATA to the target ATA
+    MultiSigPda::check_address(MultiSigType::Admin, &crate::ID, &ctx.sig_admin)?;
+    let admin_sig = MultiSigPda::from_account(&ctx.sig_admin)?;
+    let admin_seeds = admin_sig.seeds();
+    let admin_seeds = admin_seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+    // Creating the ATA
+    if ctx.ata_target.lamports() == 0 {
+        debug!("creating the target ATA since it doesn't yet exist");
+        invoke(
+            &create_associated_token_account(
+                ctx.admin1.key,
+                ctx.user.key,
+                ctx.mint_bgk.key,
+                ctx.program_token.key,
+            ),
+            &[
+                ctx.admin1.clone(),
+                ctx.ata_target.clone(),
+                ctx.user.clone(),
+                ctx.mint_bgk.clone(),
+                ctx.program_system.clone(),
+                ctx.program_token.clone(),
+            ],
+        )?;
+    }
+
+    debug!("transferring the tokens from the reserve ATA to the target ATA");
+    invoke_signed(
+        &transfer_checked(
+            ctx.program_token.key,
+            ctx.pda_source.key,
+            ctx.mint_bgk.key,
+            ctx.ata_target.key,
+            ctx.sig_admin.key,
+            &[],
+            args.amount,
+            6,
+        )?,
+        &[
+            ctx.pda_source.clone(),
+            ctx.mint_bgk.clone(),
+            ctx.ata_target.clone(),
+            ctx.sig_admin.clone(),
+        ],
+        &[admin_seeds.as_slice()],
+    )
+}
+
+struct QueueClawbackAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    timelock: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+}
+
+impl<'a> QueueClawbackAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            timelock: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+        })
+    }
+}
+
+/// Queue the clawback of a user's unvested tokens.
+fn queue_clawback(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ClawbackArgs,
+) -> ProgramResult {
+    let ctx = QueueClawbackAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Queuing clawback of unvested tokens for {}",
+        args.user
+    );
+
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin, ctx.timelock);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let config = ConfigurationPda::from_account(&ctx.config)?;
+    check_clawback(&config, &args)?;
+
+    // Create the timelocked instruction
+    TimelockPda::check_address(&crate::ID, &ctx.timelock)?;
+    let mut timelock_pda = TimelockPda::from_account(&ctx.timelock)?;
+    let timelock = Timelock::clawback(args.user, args.kind, args.date, args.target)?;
+    timelock_pda.instructions.push(timelock);
+    timelock_pda.write(&ctx.admin1)
+}
+
+/// Checks that a clawback can be performed.
+fn check_clawback(config: &ConfigurationPda, args: &ClawbackArgs) -> ProgramResult {
+    if config.launch_date == 0 {
+        msg!("use instruction cancel_investment before the launch");
+        return Err(Error::IcoUnvestBeforeLaunch.into());
+    }
+    if args.date > get_timestamp()? {
+        msg!("cannot claw back tokens as of a future date");
+        return Err(Error::InvalidOperation.into());
+    }
+    if args.target == WalletType::Ico {
+        msg!("the clawed back tokens must leave the ICO wallet");
+        return Err(Error::InvalidOperation.into());
+    }
+    Ok(())
+}
+
+struct ExecuteClawbackAccounts<'a> {
+    payer: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    timelock: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    pda_source: AccountInfo<'a>,
+    pda_target: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+    positions: Vec<AccountInfo<'a>>,
+}
+
+impl<'a> ExecuteClawbackAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            payer: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            sig_admin: next_account_info(accounts_iter)?.clone(),
+            timelock: next_account_info(accounts_iter)?.clone(),
+            mint_bgk: next_account_info(accounts_iter)?.clone(),
+            pda_source: next_account_info(accounts_iter)?.clone(),
+            pda_target: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
+            program_token: next_account_info(accounts_iter)?.clone(),
+            positions: accounts_iter.cloned().collect(),
+        })
+    }
+}
+
+/// Claw back a user's unvested tokens to an internal wallet.
+fn execute_clawback(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ClawbackArgs,
+) -> ProgramResult {
+    let ctx = ExecuteClawbackAccounts::new(accounts)?;
+    msg!("Bangk: Clawing back unvested tokens for {}", args.user);
+
+    check_pda_owner!(
+        program_id,
+        ctx.config,
+        ctx.sig_admin,
+        ctx.timelock,
+        ctx.investment
+    );
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Routine);
+
+    // Check that there’s a queued clawback, and remove it from the list if found
+    TimelockPda::check_address(&crate::ID, &ctx.timelock)?;
+    let mut timelock = TimelockPda::from_account(&ctx.timelock)?;
+    timelock.process_clawback(&args.user, args.kind, args.date, args.target, &ctx.payer)?;
+    debug!("queued operation is ready, proceeding");
+
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
+    check_clawback(&config, &args)?;
+
+    debug!("integrity check on the wallets");
+    if *ctx.pda_source.key != WalletType::Ico.get_pda().0
+        || *ctx.pda_target.key != args.target.get_pda().0
+    {
+        msg!("unexpected address for wallet PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(args.user, &crate::ID);
+    if investment_pda != *ctx.investment.key {
+        msg!("invalid user investment PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.investment.lamports() == 0 {
+        return Err(Error::InvestmentDoesNotExist.into());
+    }
+    let mut pda = UserInvestmentPda::from_account(&ctx.investment)?;
+
+    // Stop the vesting of all the concerned investments
+    let mut clawed_back = 0_u64;
+    for invest in &mut pda.investment.investments {
+        if args.kind.is_some_and(|kind| kind != invest.kind) {
+            continue;
+        }
+        let removed = invest.stop_vesting(
+            &config.unvesting,
+            &config.accelerated_per_kind,
+            config.launch_date,
+            args.date,
+        )?;
+        debug!(
+            "{} tokens removed from a {:?} investment",
+            removed, invest.kind
+        );
+        config.remove_invested(invest.kind, removed);
+        clawed_back = clawed_back.saturating_add(removed);
+    }
+    let positions = load_clawback_positions(program_id, &ctx.positions, &pda)?;
+    for mut position in positions {
+        if args
+            .kind
+            .is_some_and(|kind| kind != position.investment.kind)
+        {
+            continue;
+        }
+        let removed = position.investment.stop_vesting(
+            &config.unvesting,
+            &config.accelerated_per_kind,
+            config.launch_date,
+            args.date,
+        )?;
+        if removed == 0 {
+            continue;
+        }
+        debug!(
+            "{} tokens removed from the vesting position {}",
+            removed, position.mint
+        );
+        config.remove_invested(position.investment.kind, removed);
+        clawed_back = clawed_back.saturating_add(removed);
+        if position.is_fully_released() {
+            pda.positions = pda.positions.saturating_sub(1);
+        }
+        position.write(&ctx.payer)?;
+    }
+    if clawed_back == 0 {
+        msg!("there are no unvested tokens to claw back");
+        return Ok(());
+    }
+    pda.write(&ctx.payer)?;
+
+    // The clawed back tokens are no longer invested
+    config.write(&ctx.payer)?;
+
+    debug!(
+        "transferring {} tokens from the invested wallet to the target wallet",
+        clawed_back
+    );
+    transfer_from_internal_wallet(
+        &ctx.program_token,
+        &ctx.pda_source,
+        &ctx.mint_bgk,
+        &ctx.pda_target,
+        &ctx.sig_admin,
+        clawed_back,
+    )
+}
+
+/// Load the vesting positions tokenised from a user's investments, checking that none of the
+/// outstanding ones is missing so that they can't escape the clawback.
+///
+/// # Parameters
+/// * `program_id` - ID of the program,
+/// * `accounts` - Accounts of the vesting positions,
+/// * `pda` - Investments of the user.
+///
+/// # Errors
+/// If a position is invalid, given twice, or not one of the user's, or if some are missing.
+fn load_clawback_positions<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    pda: &UserInvestmentPda,
+) -> Result<Vec<VestingPositionPda<'a>>, ProgramError> {
+    if accounts
+        .iter()
+        .map(|account| account.key)
+        .collect::<HashSet<_>>()
+        .len()
+        != accounts.len()
+    {
+        msg!("a vesting position was given twice");
+        return Err(Error::InvalidOperation.into());
+    }
+
+    let mut positions = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        check_pda_owner!(program_id, account);
+        if account.lamports() == 0 {
+            return Err(Error::InvestmentDoesNotExist.into());
+        }
+        let position = VestingPositionPda::from_account(account)?;
+        VestingPositionPda::check_address(position.mint, &crate::ID, account)?;
+        if position.user != pda.investment.user {
+            msg!(
+                "the vesting position {} is not one of the user's",
+                position.mint
+            );
+            return Err(Error::AccountOwnerMismatch.into());
+        }
+        if !position.is_fully_released() {
+            positions.push(position);
+        }
+    }
+
+    if positions.len() != usize::try_from(pda.positions).map_err(|_err| Error::IntegerOverflow)? {
+        msg!(
+            "the {} outstanding vesting positions of the user must be given",
+            pda.positions
+        );
+        return Err(Error::InvalidOperation.into());
+    }
+    Ok(positions)
+}
+
+struct ApplyLeaverEventAccounts<'a> {
+    admin1: AccountInfo<'a>,
+    _admin2: AccountInfo<'a>,
+    _admin3: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    sig_admin: AccountInfo<'a>,
+    mint_bgk: AccountInfo<'a>,
+    pda_source: AccountInfo<'a>,
+    pda_target: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
+    program_token: AccountInfo<'a>,
+}
+
+impl<'a> ApplyLeaverEventAccounts<'a> {
+    fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
+        let accounts_iter = &mut accounts.iter();
+        Ok(Self {
+            admin1: next_account_info(accounts_iter)?.clone(),
+            _admin2: next_account_info(accounts_iter)?.clone(),
+            _admin3: next_account_info(accounts_iter)?.clone(),
             config: next_account_info(accounts_iter)?.clone(),
             sig_admin: next_account_info(accounts_iter)?.clone(),
             mint_bgk: next_account_info(accounts_iter)?.clone(),
             pda_source: next_account_info(accounts_iter)?.clone(),
-            user: next_account_info(accounts_iter)?.clone(),
+            pda_target: next_account_info(accounts_iter)?.clone(),
             investment: next_account_info(accounts_iter)?.clone(),
-            ata_user: next_account_info(accounts_iter)?.clone(),
-            program_system: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
             program_token: next_account_info(accounts_iter)?.clone(),
-            _program_ata: next_account_info(accounts_iter)?.clone(),
         })
     }
 }
 
-/// Release vested tokens
-fn vesting_release(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
-    let ctx = VestingReleaseAccounts::new(accounts)?;
-    msg!("Bangk: releasing vested tokens");
+/// Stop the vesting of a leaving team member's revocable investments, the forfeited tokens
+/// going back to the teams & advisers wallet.
+fn apply_leaver_event(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: &LeaverEventArgs,
+) -> ProgramResult {
+    let ctx = ApplyLeaverEventAccounts::new(accounts)?;
+    msg!(
+        "Bangk: Applying a {:?} leaver event for {}",
+        args.policy,
+        args.user
+    );
 
-    debug!("Security checks");
-    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Routine);
-    check_pda_owner!(program_id, ctx.config, ctx.investment);
+    check_pda_owner!(program_id, ctx.config, ctx.sig_admin, ctx.investment);
+    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
 
-    debug!("Reading PDA data");
     ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
-    let config = ConfigurationPda::from_account(&ctx.config)?;
-    UserInvestmentPda::check_address(ctx.user.key, &crate::ID, &ctx.investment)?;
-    let mut investment = UserInvestmentPda::from_account(&ctx.investment)?;
-
-    debug!("Integrity checks");
+    let mut config = ConfigurationPda::from_account(&ctx.config)?;
     if config.launch_date == 0 {
+        msg!("use instruction cancel_investment before the launch");
         return Err(Error::IcoUnvestBeforeLaunch.into());
     }
-    check_ata_exists!(ctx.investment);
-    if investment.investment.user != *ctx.user.key {
-        return Err(Error::AccountOwnerMismatch.into());
-    }
-    if ctx.ata_user.lamports() > 0 && get_ata_owner(&ctx.ata_user)? != *ctx.user.key {
-        return Err(Error::AccountOwnerMismatch.into());
+    if args.date > get_timestamp()? {
+        msg!("cannot apply a leaver event at a future date");
+        return Err(Error::InvalidOperation.into());
     }
-    if *ctx.pda_source.key != WalletType::Ico.get_pda().0 {
+
+    debug!("integrity check on the wallets");
+    if *ctx.pda_source.key != WalletType::Ico.get_pda().0
+        || *ctx.pda_target.key != WalletType::TeamsAdvisers.get_pda().0
+    {
         msg!("unexpected address for wallet PDA");
         return Err(Error::InvalidPdaAddress.into());
     }
 
-    debug!("Getting Timestamp");
-    let now = get_timestamp()?;
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(args.user, &crate::ID);
+    if investment_pda != *ctx.investment.key {
+        msg!("invalid user investment PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.investment.lamports() == 0 {
+        return Err(Error::InvestmentDoesNotExist.into());
+    }
+    let mut pda = UserInvestmentPda::from_account(&ctx.investment)?;
+    if !pda
+        .investment
+        .investments
+        .iter()
+        .any(|invest| invest.revocable)
+    {
+        msg!("the user has no revocable investment");
+        return Err(Error::InvalidOperation.into());
+    }
 
-    // Get the number of tokens that should be released for the user.
-    let mut to_release = 0_u64;
-    for invest in &mut investment.investment.investments {
-        let rule_released = invest.amount_released;
-        let rule = match invest.custom_rule {
-            Some(rule) => rule,
-            None => *config
-                .unvesting
-                .get(&invest.kind)
-                .ok_or(Error::InvalidUnvestingDefinition)?,
-        };
-        let rule_available = rule
-            .unvested(config.launch_date, now)?
-            .saturating_mul(invest.amount_bought)
-            .saturating_div(100_000);
+    // Freeze the vesting of all the revocable investments
+    let mut forfeited = 0_u64;
+    for invest in &mut pda.investment.investments {
+        if !invest.revocable {
+            continue;
+        }
+        let removed = invest.revoke(
+            &config.unvesting,
+            &config.accelerated_per_kind,
+            config.launch_date,
+            args.date,
+            args.policy,
+        )?;
         debug!(
-            "Rule {:?} has {} tokens available",
-            invest.kind, rule_available
+            "{} tokens forfeited from a {:?} investment",
+            removed, invest.kind
         );
-        to_release = to_release.saturating_add(rule_available.saturating_sub(rule_released));
-        invest.amount_released = rule_available;
+        config.remove_invested(invest.kind, removed);
+        forfeited = forfeited.saturating_add(removed);
     }
-
-    if to_release == 0 {
+    pda.write(&ctx.admin1)?;
+    if forfeited == 0 {
+        msg!("there are no unvested tokens to forfeit");
         return Ok(());
     }
-    investment.write(&ctx.api)?;
-
-    // Transferring the required amount of tokens from the invested ATA to the user's ATA
-    if ctx.ata_user.lamports() == 0 {
-        // Creating the ATA
-        debug!("creating the user's ATA");
-        invoke(
-            &create_associated_token_account(
-                ctx.api.key,
-                ctx.user.key,
-                ctx.mint_bgk.key,
-                ctx.program_token.key,
-            ),
-            &[
-                ctx.api.clone(),
-                ctx.ata_user.clone(),
-                ctx.user.clone(),
-                ctx.mint_bgk.clone(),
-                ctx.program_system.clone(),
-                ctx.program_token.clone(),
-            ],
-        )?;
-    }
 
-    MultiSigPda::check_address(MultiSigType::Admin, &crate::ID, &ctx.sig_admin)?;
-    let admin_sig = MultiSigPda::from_account(&ctx.sig_admin)?;
-    let admin_seeds = admin_sig.seeds();
-    let admin_seeds = admin_seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    // The forfeited tokens are no longer invested
+    config.write(&ctx.admin1)?;
 
     debug!(
-        "transferring {} tokens from the invested ATA to the user's ATA",
-        to_release
+        "transferring {} tokens from the invested wallet to the teams & advisers wallet",
+        forfeited
     );
-    invoke_signed(
-        &transfer_checked(
-            ctx.program_token.key,
-            ctx.pda_source.key,
-            ctx.mint_bgk.key,
-            ctx.ata_user.key,
-            ctx.sig_admin.key,
-            &[],
-            to_release,
-            6,
-        )?,
-        &[
-            ctx.pda_source.clone(),
-            ctx.mint_bgk.clone(),
-            ctx.ata_user.clone(),
-            ctx.sig_admin.clone(),
-        ],
-        &[admin_seeds.as_slice()],
+    transfer_from_internal_wallet(
+        &ctx.program_token,
+        &ctx.pda_source,
+        &ctx.mint_bgk,
+        &ctx.pda_target,
+        &ctx.sig_admin,
+        forfeited,
     )
 }
 
-struct QueueTransferFromReserveAccounts<'a> {
-    admin1: AccountInfo<'a>,
-    _admin2: AccountInfo<'a>,
-    _admin3: AccountInfo<'a>,
+struct MigrateUserInvestmentAccounts<'a> {
+    payer: AccountInfo<'a>,
     sig_admin: AccountInfo<'a>,
-    timelock: AccountInfo<'a>,
+    investment: AccountInfo<'a>,
     _program_system: AccountInfo<'a>,
 }
 
-impl<'a> QueueTransferFromReserveAccounts<'a> {
+impl<'a> MigrateUserInvestmentAccounts<'a> {
     fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
         let accounts_iter = &mut accounts.iter();
         Ok(Self {
-            admin1: next_account_info(accounts_iter)?.clone(),
-            _admin2: next_account_info(accounts_iter)?.clone(),
-            _admin3: next_account_info(accounts_iter)?.clone(),
+            payer: next_account_info(accounts_iter)?.clone(),
             sig_admin: next_account_info(accounts_iter)?.clone(),
-            timelock: next_account_info(accounts_iter)?.clone(),
+            investment: next_account_info(accounts_iter)?.clone(),
             _program_system: next_account_info(accounts_iter)?.clone(),
         })
     }
 }
 
-/// Transfer BGK tokens from Bangk's reserve.
-fn queue_transfer_from_reserve(
+/// Convert the investments of a user stored with the layout of the v1.0.0 of the program.
+fn migrate_user_investment(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: QueueTransferFromInternalWalletArgs,
+    args: &MigrateUserInvestmentArgs,
 ) -> ProgramResult {
-    let ctx = QueueTransferFromReserveAccounts::new(accounts)?;
-    msg!("Bangk: Queue tranfering BGK tokens from Bangk's reserve");
+    let ctx = MigrateUserInvestmentAccounts::new(accounts)?;
+    msg!("Bangk: Migrating the investments of {}", args.user);
 
-    check_pda_owner!(program_id, ctx.sig_admin, ctx.timelock);
-    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Critical);
+    check_pda_owner!(program_id, ctx.sig_admin, ctx.investment);
+    check_signers!(accounts, &ctx.sig_admin);
 
-    TimelockPda::check_address(&crate::ID, &ctx.timelock)?;
-    let mut timelock_pda = TimelockPda::from_account(&ctx.timelock)?;
-    // Create the timelocked instruction
-    let timelock = Timelock::transfer_from_internal_wallet(args.source, args.target, args.amount)?;
-    timelock_pda.instructions.push(timelock);
-    timelock_pda.write(&ctx.admin1)
+    // Special case here, we want to make sure there are no risks for the wrong PDA address to be given, so we recompute it
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(args.user, &crate::ID);
+    if investment_pda != *ctx.investment.key {
+        msg!("invalid user investment PDA");
+        return Err(Error::InvalidPdaAddress.into());
+    }
+    if ctx.investment.lamports() == 0 {
+        return Err(Error::InvestmentDoesNotExist.into());
+    }
+    if UserInvestmentPda::from_account(&ctx.investment).is_ok() {
+        msg!("the investments already use the current layout");
+        return Err(Error::UniqueOperationAlreadyExecuted.into());
+    }
+
+    // The rent of the legacy accounts was paid by the API, which is expected to run the migration
+    let pda = UserInvestmentPda::from_legacy_account(&ctx.investment, *ctx.payer.key)?;
+    if pda.investment.user != args.user {
+        return Err(Error::AccountOwnerMismatch.into());
+    }
+    pda.write(&ctx.payer)
 }
 
-struct ExecuteTransferFromReserveAccounts<'a> {
-    admin1: AccountInfo<'a>,
+struct MigrateConfigurationAccounts<'a> {
+    payer: AccountInfo<'a>,
     sig_admin: AccountInfo<'a>,
-    timelock: AccountInfo<'a>,
-    mint_bgk: AccountInfo<'a>,
-    pda_source: AccountInfo<'a>,
-    user: AccountInfo<'a>,
-    ata_target: AccountInfo<'a>,
-    program_system: AccountInfo<'a>,
-    program_token: AccountInfo<'a>,
-    _program_ata: AccountInfo<'a>,
+    config: AccountInfo<'a>,
+    _program_system: AccountInfo<'a>,
 }
 
-impl<'a> ExecuteTransferFromReserveAccounts<'a> {
+impl<'a> MigrateConfigurationAccounts<'a> {
     fn new(accounts: &[AccountInfo<'a>]) -> Result<Self, ProgramError> {
         let accounts_iter = &mut accounts.iter();
         Ok(Self {
-            admin1: next_account_info(accounts_iter)?.clone(),
+            payer: next_account_info(accounts_iter)?.clone(),
             sig_admin: next_account_info(accounts_iter)?.clone(),
-            timelock: next_account_info(accounts_iter)?.clone(),
-            mint_bgk: next_account_info(accounts_iter)?.clone(),
-            pda_source: next_account_info(accounts_iter)?.clone(),
-            user: next_account_info(accounts_iter)?.clone(),
-            ata_target: next_account_info(accounts_iter)?.clone(),
-            program_system: next_account_info(accounts_iter)?.clone(),
-            program_token: next_account_info(accounts_iter)?.clone(),
-            _program_ata: next_account_info(accounts_iter)?.clone(),
+            config: next_account_info(accounts_iter)?.clone(),
+            _program_system: next_account_info(accounts_iter)?.clone(),
         })
     }
 }
 
-/// Transfer BGK tokens from Bangk's reserve.
-fn execute_transfer_from_reserve(
+/// Convert the configuration of the program stored with the layout of the v1.0.0 of the program.
+fn migrate_configuration(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    args: ExecuteTransferFromInternalWalletArgs,
+    args: &MigrateConfigurationArgs,
 ) -> ProgramResult {
-    let ctx = ExecuteTransferFromReserveAccounts::new(accounts)?;
-    msg!("Bangk: Tranfering BGK tokens from Bangk's reserve");
+    let ctx = MigrateConfigurationAccounts::new(accounts)?;
+    msg!("Bangk: Migrating the configuration");
 
-    check_pda_owner!(program_id, ctx.sig_admin, ctx.timelock);
-    check_signers!(accounts, &ctx.sig_admin, OperationSecurityLevel::Routine);
+    check_pda_owner!(program_id, ctx.sig_admin, ctx.config);
+    check_signers!(accounts, &ctx.sig_admin);
+    ConfigurationPda::check_address(&crate::ID, &ctx.config)?;
 
-    debug!("integrity check on the source wallet");
-    if *ctx.pda_source.key != args.source.get_pda().0 {
-        msg!("unexpected address for wallet PDA");
-        return Err(Error::InvalidPdaAddress.into());
+    if ConfigurationPda::from_account(&ctx.config).is_ok() {
+        msg!("the configuration already uses the current layout");
+        return Err(Error::UniqueOperationAlreadyExecuted.into());
     }
 
-    // Check that there’s a queued transfer, and remove it from the list if found
-    TimelockPda::check_address(&crate::ID, &ctx.timelock)?;
-    let mut timelock = TimelockPda::from_account(&ctx.timelock)?;
-    timelock.process_transfer_from_internal_wallet(
-        args.source,
-        ctx.ata_target.key,
-        args.amount,
-        &ctx.admin1,
-    )?;
-    debug!("queued operation is ready, proceeding");
+    // The new settings make the account bigger: the payer pays for the additional rent
+    let mut config = ConfigurationPda::from_legacy_account(&ctx.config)?;
+    // The caps on the investments rely on the amounts invested per type
+    config.seed_invested_per_kind(&args.invested_per_kind)?;
+    config.write(&ctx.payer)
+}
 
-    // Transferring the required amount of tokens from the reserve ATA to the target ATA
-    MultiSigPda::check_address(MultiSigType::Admin, &crate::ID, &ctx.sig_admin)?;
-    let admin_sig = MultiSigPda::from_account(&ctx.sig_admin)?;
+/// Transfer tokens out of one of Bangk's internal wallets.
+fn transfer_from_internal_wallet<'a>(
+    program_token: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    target: &AccountInfo<'a>,
+    sig_admin: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    MultiSigPda::check_address(MultiSigType::Admin, &crate::ID, sig_admin)?;
+    let admin_sig = MultiSigPda::from_account(sig_admin)?;
     let admin_seeds = admin_sig.seeds();
     let admin_seeds = admin_seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
 
-    // Creating the ATA
-    if ctx.ata_target.lamports() == 0 {
-        debug!("creating the target ATA since it doesn't yet exist");
-        invoke(
-            &create_associated_token_account(
-                ctx.admin1.key,
-                ctx.user.key,
-                ctx.mint_bgk.key,
-                ctx.program_token.key,
-            ),
-            &[
-                ctx.admin1.clone(),
-                ctx.ata_target.clone(),
-                ctx.user.clone(),
-                ctx.mint_bgk.clone(),
-                ctx.program_system.clone(),
-                ctx.program_token.clone(),
-            ],
-        )?;
-    }
-
-    debug!("transferring the tokens from the reserve ATA to the target ATA");
     invoke_signed(
         &transfer_checked(
-            ctx.program_token.key,
-            ctx.pda_source.key,
-            ctx.mint_bgk.key,
-            ctx.ata_target.key,
-            ctx.sig_admin.key,
+            program_token.key,
+            source.key,
+            mint.key,
+            target.key,
+            sig_admin.key,
             &[],
-            args.amount,
+            amount,
             6,
         )?,
         &[
-            ctx.pda_source.clone(),
-            ctx.mint_bgk.clone(),
-            ctx.ata_target.clone(),
-            ctx.sig_admin.clone(),
+            source.clone(),
+            mint.clone(),
+            target.clone(),
+            sig_admin.clone(),
         ],
         &[admin_seeds.as_slice()],
     )
@@ -0,0 +1,155 @@
+// File: bangk-ico/src/referral.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:36:50
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+use bangk_macro::pda;
+use bangk_onchain_common::{
+    pda::{BangkPda, PdaType},
+    Error,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use shank::ShankType;
+use solana_program::{msg, pubkey::Pubkey};
+
+use crate::{unvesting::UnvestingScheme, WalletType};
+
+/// Definition of the program rewarding the referrers of the investors.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub struct ReferralProgram {
+    /// Internal wallet funding the rewards (`Marketing` or `Community`).
+    pub source: WalletType,
+    /// Reward per token invested by a referred user (x1000 factor, 100% being 100 000).
+    pub rate: u32,
+    /// Maximum number of tokens a single referrer can be rewarded.
+    pub referrer_cap: u64,
+    /// Maximum number of tokens rewarded to all the referrers.
+    pub total_cap: u64,
+    /// Unvesting scheme of the rewards.
+    pub unvesting: UnvestingScheme,
+    /// Number of tokens rewarded so far.
+    pub rewarded: u64,
+    /// Number of rewarded tokens already transferred from the source wallet to the `Ico` wallet.
+    pub funded: u64,
+}
+
+impl ReferralProgram {
+    /// Checks if a referral program definition seems valid.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if !matches!(self.source, WalletType::Marketing | WalletType::Community)
+            || self.rate == 0
+            || self.rate > 100_000
+            || self.referrer_cap == 0
+            || self.referrer_cap > self.total_cap
+            || !self.unvesting.is_valid().unwrap_or(false)
+        {
+            msg!("referral program definition invalid: {:?}", self);
+            return false;
+        }
+        true
+    }
+
+    /// Credit a referrer with the reward for an investment they brought, within the caps.
+    ///
+    /// # Parameters
+    /// * `amount` - Number of tokens invested by the referred user,
+    /// * `already_rewarded` - Number of tokens the referrer has already been rewarded.
+    ///
+    /// # Returns
+    /// The number of tokens rewarded (may be zero once a cap is reached).
+    ///
+    /// # Errors
+    /// If the computation overflows.
+    pub fn credit(&mut self, amount: u64, already_rewarded: u64) -> Result<u64, Error> {
+        let reward = u128::from(amount)
+            .checked_mul(u128::from(self.rate))
+            .and_then(|total| total.checked_div(100_000))
+            .ok_or(Error::IntegerOverflow)?;
+        let reward = u64::try_from(reward)
+            .map_err(|_err| Error::IntegerOverflow)?
+            .min(self.referrer_cap.saturating_sub(already_rewarded))
+            .min(self.total_cap.saturating_sub(self.rewarded));
+        self.rewarded = self.rewarded.saturating_add(reward);
+        Ok(reward)
+    }
+
+    /// Withdraw the reward of a cancelled investment, which can then be granted to other referrers.
+    ///
+    /// # Parameters
+    /// * `reward` - Number of tokens of the reward cancelled.
+    pub fn cancel(&mut self, reward: u64) {
+        self.rewarded = self.rewarded.saturating_sub(reward);
+    }
+}
+
+/// PDA storing the statistics of a referrer.
+#[pda(kind = PdaType::ReferrerStats, seed = "Referrer", seed = referrer)]
+pub struct ReferrerStatsPda {
+    /// Wallet of the referrer.
+    pub referrer: Pubkey,
+    /// Number of investments made by the users they referred.
+    pub referrals: u32,
+    /// Number of tokens invested by the users they referred.
+    pub referred_amount: u64,
+    /// Number of tokens they were rewarded.
+    pub rewarded: u64,
+}
+
+impl<'a> ReferrerStatsPda<'a> {
+    /// Create new statistics for a referrer.
+    ///
+    /// # Parameters
+    /// * `bump` - Bump used to derive the PDA address,
+    /// * `referrer` - Wallet of the referrer.
+    #[must_use]
+    pub const fn new(bump: u8, referrer: Pubkey) -> Self {
+        Self {
+            pda_type: Self::PDA_TYPE,
+            bump,
+            account: None,
+            referrer,
+            referrals: 0,
+            referred_amount: 0,
+            rewarded: 0,
+        }
+    }
+
+    /// Record an investment made by a referred user.
+    ///
+    /// # Parameters
+    /// * `amount` - Number of tokens invested,
+    /// * `reward` - Number of tokens rewarded to the referrer.
+    pub fn record(&mut self, amount: u64, reward: u64) {
+        self.referrals = self.referrals.saturating_add(1);
+        self.referred_amount = self.referred_amount.saturating_add(amount);
+        self.rewarded = self.rewarded.saturating_add(reward);
+    }
+
+    /// Record the cancellation of an investment made by a referred user.
+    ///
+    /// # Parameters
+    /// * `amount` - Number of tokens of the investment cancelled,
+    /// * `reward` - Number of tokens of the reward cancelled.
+    pub fn cancel(&mut self, amount: u64, reward: u64) {
+        self.referred_amount = self.referred_amount.saturating_sub(amount);
+        self.rewarded = self.rewarded.saturating_sub(reward);
+    }
+}
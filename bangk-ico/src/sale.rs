@@ -0,0 +1,326 @@
+// File: bangk-ico/src/sale.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 16:07:56
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+use bangk_macro::pda;
+use bangk_onchain_common::{
+    pda::{BangkPda, PdaType, Seed},
+    Error,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+use shank::ShankType;
+use solana_program::{keccak, msg, pubkey::Pubkey};
+
+use crate::unvesting::{UnvestingScheme, UnvestingType};
+
+/// Definition of a phase of the token sale.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub struct SalePhase {
+    /// Type of investment sold during the phase.
+    pub kind: UnvestingType,
+    /// Timestamp of the start of the phase.
+    pub start: i64,
+    /// Timestamp of the end of the phase (excluded).
+    pub end: i64,
+    /// Price of one BGK (1e6 tokens) in the smallest unit of the payment currency.
+    pub price: u64,
+    /// Maximum number of tokens that can be sold during the phase.
+    pub cap: u64,
+    /// Number of tokens already sold during the phase.
+    pub sold: u64,
+}
+
+impl SalePhase {
+    /// Checks if a sale phase definition seems valid.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if !self.kind.is_sale() || self.start >= self.end || self.price == 0 || self.cap == 0 {
+            msg!("sale phase definition invalid: {:?}", self);
+            return false;
+        }
+        true
+    }
+
+    /// Checks if the phase is running at a given time.
+    #[must_use]
+    pub const fn is_active(&self, now: i64) -> bool {
+        self.start <= now && now < self.end
+    }
+
+    /// Checks if two phases overlap in time.
+    #[must_use]
+    pub const fn overlaps(&self, other: &Self) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// Get the price of a number of tokens during the phase (rounded up).
+    ///
+    /// # Parameters
+    /// * `amount` - Number of tokens bought.
+    ///
+    /// # Errors
+    /// If the price overflows.
+    pub fn cost(&self, amount: u64) -> Result<u64, Error> {
+        let cost = u128::from(amount)
+            .checked_mul(u128::from(self.price))
+            .ok_or(Error::IntegerOverflow)?
+            .div_ceil(1_000_000);
+        u64::try_from(cost).map_err(|_err| Error::IntegerOverflow)
+    }
+
+    /// Record the sale of tokens during the phase.
+    ///
+    /// # Errors
+    /// If the phase's cap would be exceeded.
+    pub fn sell(&mut self, amount: u64) -> Result<(), Error> {
+        let sold = self.sold.saturating_add(amount);
+        if sold > self.cap {
+            msg!(
+                "the sale phase's cap would be exceeded ({} vs {})",
+                sold,
+                self.cap
+            );
+            return Err(Error::SalePhaseCapExceeded);
+        }
+        self.sold = sold;
+        Ok(())
+    }
+}
+
+/// Bonus granted to the investors of a sale phase or of an amount tier.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub struct BonusRule {
+    /// Type of investment granting the bonus.
+    pub kind: UnvestingType,
+    /// Start of the sale phase granting the bonus (every phase if `None`).
+    pub phase_start: Option<i64>,
+    /// Minimum number of tokens invested at once to get the bonus.
+    pub min_amount: u64,
+    /// Bonus per token invested (x1000 factor, 100% being 100 000).
+    pub rate: u32,
+    /// Unvesting scheme of the bonus tokens.
+    pub unvesting: UnvestingScheme,
+}
+
+impl BonusRule {
+    /// Checks if a bonus rule definition seems valid.
+    #[must_use]
+    pub fn is_valid(&self) -> bool {
+        if !self.kind.is_sale()
+            || self.rate == 0
+            || self.rate > 100_000
+            || !self.unvesting.is_valid().unwrap_or(false)
+        {
+            msg!("bonus rule definition invalid: {:?}", self);
+            return false;
+        }
+        true
+    }
+
+    /// Checks if the rule applies to an investment.
+    ///
+    /// # Parameters
+    /// * `kind` - Type of investment,
+    /// * `phase` - Sale phase in which the investment is made (if any),
+    /// * `amount` - Number of tokens invested.
+    #[must_use]
+    pub fn applies(&self, kind: UnvestingType, phase: Option<&SalePhase>, amount: u64) -> bool {
+        self.kind == kind
+            && amount >= self.min_amount
+            && self
+                .phase_start
+                .map_or(true, |start| phase.is_some_and(|sale| sale.start == start))
+    }
+
+    /// Get the bonus granted for an investment (rounded down).
+    ///
+    /// # Parameters
+    /// * `amount` - Number of tokens invested.
+    ///
+    /// # Errors
+    /// If the computation overflows.
+    pub fn bonus(&self, amount: u64) -> Result<u64, Error> {
+        let bonus = u128::from(amount)
+            .checked_mul(u128::from(self.rate))
+            .and_then(|total| total.checked_div(100_000))
+            .ok_or(Error::IntegerOverflow)?;
+        u64::try_from(bonus).map_err(|_err| Error::IntegerOverflow)
+    }
+}
+
+/// Get the reference of the investment created by an on-chain purchase.
+///
+/// # Parameters
+/// * `user` - User who made the purchase,
+/// * `index` - Position of the purchase among the user's purchases.
+#[must_use]
+#[allow(clippy::little_endian_bytes)]
+pub fn get_purchase_reference(user: &Pubkey, index: u32) -> [u8; 32] {
+    keccak::hashv(&[b"Purchase", user.as_ref(), &index.to_le_bytes()]).to_bytes()
+}
+
+/// Get the address and bump of the treasury receiving the payments made in a given currency.
+///
+/// # Parameters
+/// * `mint` - Mint of the currency used for the payments.
+#[must_use]
+pub fn get_sale_treasury_pda(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"SaleTreasury", mint.as_ref()], &crate::ID)
+}
+
+/// Get the seeds of the treasury receiving the payments made in a given currency.
+///
+/// # Parameters
+/// * `mint` - Mint of the currency used for the payments.
+#[must_use]
+pub fn get_sale_treasury_seeds(mint: &Pubkey) -> Vec<Vec<u8>> {
+    let (_address, bump) = get_sale_treasury_pda(mint);
+    let seeds: Vec<Seed> = vec!["SaleTreasury".into(), mint.into(), bump.into()];
+    seeds.into_iter().map(Into::into).collect()
+}
+
+/// On-chain purchase of BGK tokens.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    ShankType,
+    Serialize,
+    Deserialize,
+)]
+pub struct Purchase {
+    /// Type of investment bought.
+    pub kind: UnvestingType,
+    /// Timestamp of the purchase.
+    pub timestamp: i64,
+    /// Number of tokens bought.
+    pub amount: u64,
+    /// Amount paid, in the smallest unit of the payment currency.
+    pub paid: u64,
+    /// Number of tokens bought that have been cancelled since.
+    pub cancelled: u64,
+    /// Amount already refunded, in the smallest unit of the payment currency.
+    pub refunded: u64,
+    /// Reference of the investment created by the purchase (see `get_purchase_reference`).
+    pub reference: [u8; 32],
+}
+
+impl Purchase {
+    /// Get the amount that still has to be refunded for the cancelled tokens.
+    ///
+    /// # Errors
+    /// If the computation overflows.
+    pub fn refund_due(&self) -> Result<u64, Error> {
+        let due = u128::from(self.paid)
+            .checked_mul(u128::from(self.cancelled))
+            .and_then(|total| total.checked_div(u128::from(self.amount)))
+            .unwrap_or_default();
+        let due = u64::try_from(due).map_err(|_err| Error::IntegerOverflow)?;
+        Ok(due.saturating_sub(self.refunded))
+    }
+}
+
+/// PDA storing the on-chain purchases of a user.
+#[pda(kind = PdaType::IcoPurchase, seed = "Purchase", seed = user)]
+pub struct UserPurchasesPda {
+    /// User who made the purchases.
+    pub user: Pubkey,
+    /// Purchases of the user.
+    pub purchases: Vec<Purchase>,
+}
+
+impl<'a> UserPurchasesPda<'a> {
+    /// Create a new record of a user's purchases.
+    ///
+    /// # Parameters
+    /// * `bump` - Bump used to derive the PDA address,
+    /// * `user` - User who made the purchases,
+    /// * `purchases` - Purchases of the user.
+    #[must_use]
+    pub const fn new(bump: u8, user: Pubkey, purchases: Vec<Purchase>) -> Self {
+        Self {
+            pda_type: Self::PDA_TYPE,
+            bump,
+            account: None,
+            user,
+            purchases,
+        }
+    }
+
+    /// Mark the tokens of a purchase as cancelled, so that they can be refunded.
+    ///
+    /// # Parameters
+    /// * `reference` - Reference of the cancelled investment,
+    /// * `amount` - Number of tokens of the investment that were cancelled.
+    ///
+    /// # Returns
+    /// The number of purchased tokens that have been cancelled (0 if the investment wasn't purchased).
+    pub fn cancel(&mut self, reference: &[u8; 32], amount: u64) -> u64 {
+        let Some(purchase) = self
+            .purchases
+            .iter_mut()
+            .find(|purchase| purchase.reference == *reference)
+        else {
+            return 0;
+        };
+        let cancelled = amount.min(purchase.amount.saturating_sub(purchase.cancelled));
+        purchase.cancelled = purchase.cancelled.saturating_add(cancelled);
+        cancelled
+    }
+
+    /// Mark the refunds due for the cancelled purchases as done.
+    ///
+    /// # Parameters
+    /// * `aborted` - If the sale was aborted, in which case all the purchases are refunded.
+    ///
+    /// # Returns
+    /// The amount to refund, in the smallest unit of the payment currency.
+    ///
+    /// # Errors
+    /// If the computation overflows.
+    pub fn refund(&mut self, aborted: bool) -> Result<u64, Error> {
+        let mut total = 0_u64;
+        for purchase in &mut self.purchases {
+            if aborted {
+                purchase.cancelled = purchase.amount;
+            }
+            let due = purchase.refund_due()?;
+            purchase.refunded = purchase.refunded.saturating_add(due);
+            total = total.saturating_add(due);
+        }
+        Ok(total)
+    }
+}
@@ -3,7 +3,7 @@
 // Creation date: Monday 12 August 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Wednesday 21 August 2024 @ 19:33:07
+// Last modified: Sunday 18 October 2026 @ 13:58:14
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -16,7 +16,7 @@ use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubke
 
 use bangk_onchain_common::{pda::BangkPda, Result};
 
-use crate::{processor::TIMELOCK_DELAY, UnvestingScheme, WalletType};
+use crate::{processor::TIMELOCK_DELAY, UnvestingScheme, UnvestingType, WalletType};
 
 /// Data for instructions subjected to time-locks
 #[derive(
@@ -40,6 +40,24 @@ pub enum TimelockInstruction {
         /// Amount to unvest
         amount: u64,
     },
+    /// Claw back the unvested tokens of a user
+    Clawback {
+        /// Pubkey of the target user
+        user: Pubkey,
+        /// Type of the investments concerned (all of them if `None`)
+        kind: Option<UnvestingType>,
+        /// Date as of which the vesting is stopped
+        date: i64,
+        /// Internal wallet receiving the tokens
+        target: WalletType,
+    },
+    /// Reassign the investments of a user to a new wallet
+    ReassignInvestment {
+        /// Pubkey of the current owner of the investments
+        from: Pubkey,
+        /// Pubkey of the new owner of the investments
+        to: Pubkey,
+    },
 }
 
 /// A time-locked instruction
@@ -100,6 +118,52 @@ impl Timelock {
         })
     }
 
+    /// Create a new `TimelockInstruction::Clawback`
+    ///
+    /// # Parameters
+    /// * `user` - The user whose tokens are clawed back,
+    /// * `kind` - The type of investments concerned (all of them if `None`),
+    /// * `date` - Date as of which the vesting is stopped,
+    /// * `target` - Internal wallet receiving the tokens.
+    pub fn clawback<I>(
+        user: I,
+        kind: Option<UnvestingType>,
+        date: i64,
+        target: WalletType,
+    ) -> Result<Self>
+    where
+        I: Into<Pubkey>,
+    {
+        Ok(Self {
+            instruction: TimelockInstruction::Clawback {
+                user: user.into(),
+                kind,
+                date,
+                target,
+            },
+            creation_time: get_timestamp()?,
+        })
+    }
+
+    /// Create a new `TimelockInstruction::ReassignInvestment`
+    ///
+    /// # Parameters
+    /// * `from` - The current owner of the investments,
+    /// * `to` - The new owner of the investments.
+    pub fn reassign_investment<I, J>(from: I, to: J) -> Result<Self>
+    where
+        I: Into<Pubkey>,
+        J: Into<Pubkey>,
+    {
+        Ok(Self {
+            instruction: TimelockInstruction::ReassignInvestment {
+                from: from.into(),
+                to: to.into(),
+            },
+            creation_time: get_timestamp()?,
+        })
+    }
+
     /// Checks if the instruction is ready to be executed
     ///
     /// # Errors
@@ -199,4 +263,48 @@ impl<'a> TimelockPda<'a> {
         };
         self.process_instruction(instr, payer)
     }
+
+    /// Checks a clawback instruction
+    ///
+    /// If the instruction exists and is ready, the PDA's state on the blockchain
+    /// is updated, otherwise an error is returned.
+    ///
+    /// # Errors
+    /// If the instruction does not exist or if it is not ready.
+    pub fn process_clawback(
+        &mut self,
+        user: &Pubkey,
+        kind: Option<UnvestingType>,
+        date: i64,
+        target: WalletType,
+        payer: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let instr = TimelockInstruction::Clawback {
+            user: *user,
+            kind,
+            date,
+            target,
+        };
+        self.process_instruction(instr, payer)
+    }
+
+    /// Checks an investment reassignment instruction
+    ///
+    /// If the instruction exists and is ready, the PDA's state on the blockchain
+    /// is updated, otherwise an error is returned.
+    ///
+    /// # Errors
+    /// If the instruction does not exist or if it is not ready.
+    pub fn process_reassign_investment(
+        &mut self,
+        from: &Pubkey,
+        to: &Pubkey,
+        payer: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let instr = TimelockInstruction::ReassignInvestment {
+            from: *from,
+            to: *to,
+        };
+        self.process_instruction(instr, payer)
+    }
 }
@@ -3,7 +3,7 @@
 // Creation date: Thursday 13 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Monday 15 July 2024 @ 14:41:32
+// Last modified: Sunday 18 October 2026 @ 13:02:53
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -97,6 +97,17 @@ pub enum UnvestingType {
     PublicSells3,
 }
 
+impl UnvestingType {
+    /// Checks if the investments of this type are sold during the ICO.
+    #[must_use]
+    pub const fn is_sale(self) -> bool {
+        matches!(
+            self,
+            Self::PrivateSells | Self::PublicSells1 | Self::PublicSells2 | Self::PublicSells3
+        )
+    }
+}
+
 /// Definition of an unvesting scheme.
 #[derive(
     BorshSerialize,
@@ -0,0 +1,271 @@
+// File: bangk-ico/tests/acceleration.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:48:54
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::{error, result};
+
+use bangk_ico::{
+    accelerate_vesting, accelerate_vesting_type, claim_vested, Acceleration, ConfigurationPda,
+    UnvestingType, UserInvestmentPda,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, get_unvesting_def, launch_tokens, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+const INVEST_TYPE: UnvestingType = UnvestingType::PrivateSells;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const WEEK: i64 = 7 * 86_400;
+
+#[tokio::test]
+async fn invalid_acceleration() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+
+    let instruction1 =
+        accelerate_vesting(&api, &admin2, &admin2, &[user], None, Acceleration::Full)?;
+    let res1 = env
+        .execute_transaction(&instruction1, &["API", "Admin 2"])
+        .await;
+    assert!(
+        res1.is_err_and(|err| err == BangkError::InvalidSigner),
+        "the vesting was accelerated without enough signers"
+    );
+
+    let instruction2 = accelerate_vesting(
+        &api,
+        &admin2,
+        &admin4,
+        &[user],
+        None,
+        Acceleration::Extra(0),
+    )?;
+    let res2 = env
+        .execute_transaction(&instruction2, &["API", "Admin 2", "Admin 4"])
+        .await;
+    assert!(
+        res2.is_err_and(|err| err == BangkError::InvalidAmount),
+        "an empty acceleration was accepted"
+    );
+
+    let instruction3 = accelerate_vesting(
+        &api,
+        &admin2,
+        &admin4,
+        &[user],
+        Some(UnvestingType::TeamFounders),
+        Acceleration::Full,
+    )?;
+    let res3 = env
+        .execute_transaction(&instruction3, &["API", "Admin 2", "Admin 4"])
+        .await;
+    assert!(
+        res3.is_err_and(|err| err == BangkError::InvalidOperation),
+        "an acceleration without any matching investment was accepted"
+    );
+
+    // The same investments can't be accelerated twice at once
+    let instruction4 = accelerate_vesting(
+        &api,
+        &admin2,
+        &admin4,
+        &[user, user],
+        None,
+        Acceleration::Extra(10_000),
+    )?;
+    let res4 = env
+        .execute_transaction(&instruction4, &["API", "Admin 2", "Admin 4"])
+        .await;
+    assert!(
+        res4.is_err_and(|err| err == BangkError::InvalidOperation),
+        "an investment PDA was accelerated twice"
+    );
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert!(pda
+        .investment
+        .investments
+        .iter()
+        .all(|invest| invest.accelerated == 0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn accelerate_type() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user1 = Pubkey::new_unique();
+    let user2 = Pubkey::new_unique();
+    add_investment(&mut env, &user1, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    add_investment(
+        &mut env,
+        &user1,
+        INVESTED_AMOUNT,
+        UnvestingType::TeamFounders,
+        None,
+    )
+    .await?;
+    add_investment(&mut env, &user2, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+
+    let instructions = accelerate_vesting(
+        &api,
+        &admin2,
+        &admin4,
+        &[user1, user2],
+        Some(INVEST_TYPE),
+        Acceleration::Full,
+    )?;
+    env.execute_transaction(&instructions, &["API", "Admin 2", "Admin 4"])
+        .await?;
+
+    for user in [user1, user2] {
+        let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+        let pda: UserInvestmentPda = env
+            .from_account(&investment_pda)
+            .await
+            .ok_or("could not load the investment PDA")?;
+        for invest in &pda.investment.investments {
+            assert_eq!(
+                invest.fully_vested,
+                invest.kind == INVEST_TYPE,
+                "only the investments of the given type should be accelerated"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn extra_release() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+
+    let instructions = accelerate_vesting(
+        &api,
+        &admin2,
+        &admin4,
+        &[user],
+        None,
+        Acceleration::Extra(50_000),
+    )?;
+    env.execute_transaction(&instructions, &["API", "Admin 2", "Admin 4"])
+        .await?;
+
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let user_ata =
+        get_associated_token_address_with_program_id(&user, &mint_address, &spl_token_2022::ID);
+
+    // The extra percentage is released on top of the unvesting scheme
+    let instruction = claim_vested(&user, &user)?;
+    env.execute_transaction(&[instruction], &["User"]).await?;
+    let target = INVESTED_AMOUNT * (u64::from(scheme.initial_unvesting) + 50_000) / 100_000;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(target));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn accelerate_whole_type() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+
+    // Recorded in the configuration, without listing the investors
+    let instruction1 = accelerate_vesting_type(
+        &api,
+        &admin2,
+        &admin4,
+        INVEST_TYPE,
+        Acceleration::Extra(50_000),
+    )?;
+    env.execute_transaction(&[instruction1], &["API", "Admin 2", "Admin 4"])
+        .await?;
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration PDA")?;
+    assert_eq!(config.accelerated_per_kind.get(&INVEST_TYPE), Some(&50_000));
+
+    // The investments made afterwards are accelerated as well
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let user_ata =
+        get_associated_token_address_with_program_id(&user, &mint_address, &spl_token_2022::ID);
+
+    let instruction2 = claim_vested(&user, &user)?;
+    env.execute_transaction(&[instruction2], &["User"]).await?;
+    let target = 2 * INVESTED_AMOUNT * (u64::from(scheme.initial_unvesting) + 50_000) / 100_000;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(target));
+
+    // Once fully accelerated, the type can't be accelerated anymore
+    let instruction3 =
+        accelerate_vesting_type(&api, &admin2, &admin4, INVEST_TYPE, Acceleration::Full)?;
+    env.execute_transaction(&[instruction3], &["API", "Admin 2", "Admin 4"])
+        .await?;
+    let instruction4 =
+        accelerate_vesting_type(&api, &admin2, &admin4, INVEST_TYPE, Acceleration::Full)?;
+    let res = env
+        .execute_transaction(&[instruction4], &["API", "Admin 2", "Admin 4"])
+        .await;
+    assert!(res.is_err_and(|err| err == BangkError::InvalidOperation));
+
+    Ok(())
+}
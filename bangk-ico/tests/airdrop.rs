@@ -0,0 +1,251 @@
+// File: bangk-ico/tests/airdrop.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 16:58:26
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+use std::thread::sleep;
+use std::time::Duration;
+use std::{error, result};
+
+use bangk_ico::{
+    claim_airdrop, create_airdrop, get_airdrop_leaf, get_airdrop_vault_pda, get_merkle_proof,
+    get_merkle_root, reclaim_airdrop, AirdropPda, ConfigurationPda, CreateAirdropArgs,
+    UnvestingScheme, UnvestingType, UserInvestmentPda, WalletType,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::PROGRAM_ID;
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer as _};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use tests_utilities::onchain::Environment;
+
+pub mod common;
+
+const AIRDROP_ID: u8 = 1;
+const AMOUNTS: [u64; 3] = [1_000_000, 2_000_000, 3_000_000];
+const TOTAL: u64 = 6_000_000;
+
+/// Create an airdrop for the users "User 0", "User 1" and "User 2".
+async fn init_airdrop(
+    deadline: i64,
+    unvesting: Option<UnvestingScheme>,
+) -> Result<(Environment, Vec<Pubkey>, Vec<[u8; 32]>)> {
+    let mut env = common::init_with_mint().await?;
+    let mut users = Vec::new();
+    for index in 0..AMOUNTS.len() {
+        users.push(env.add_wallet(&format!("User {index}")).await);
+    }
+    let leaves: Vec<[u8; 32]> = users
+        .iter()
+        .zip(AMOUNTS)
+        .zip(0..)
+        .map(|((user, amount), index)| get_airdrop_leaf(index, user, amount))
+        .collect();
+
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let instruction = create_airdrop(
+        &admin1,
+        &admin2,
+        &admin3,
+        CreateAirdropArgs {
+            id: AIRDROP_ID,
+            source: WalletType::Community,
+            root: get_merkle_root(&leaves),
+            total: TOTAL,
+            recipients: 3,
+            deadline,
+            unvesting,
+        },
+    )?;
+    env.execute_transaction(&[instruction], &["Admin 1", "Admin 2", "Admin 3"])
+        .await?;
+
+    Ok((env, users, leaves))
+}
+
+#[tokio::test]
+async fn claim_tokens() -> Result<()> {
+    let deadline = chrono::Utc::now().timestamp() + 86_400;
+    let (mut env, users, leaves) = init_airdrop(deadline, None).await?;
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let (vault, _vault_bump) = get_airdrop_vault_pda(AIRDROP_ID);
+    assert_eq!(env.get_token_amount(&vault).await, Some(TOTAL));
+
+    let user = *users.get(1).ok_or("missing user")?;
+    let user_ata =
+        get_associated_token_address_with_program_id(&user, &mint_address, &spl_token_2022::ID);
+
+    // Claiming more than granted
+    let proof = get_merkle_proof(&leaves, 1);
+    let greedy = claim_airdrop(&user, AIRDROP_ID, 1, TOTAL, proof.clone())?;
+    let res = env.execute_transaction(&[greedy], &["User 1"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidMerkleProof),
+        "an invalid claim was accepted"
+    );
+
+    let instruction = claim_airdrop(&user, AIRDROP_ID, 1, 2_000_000, proof.clone())?;
+    env.execute_transaction(&[instruction], &["User 1"]).await?;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(2_000_000));
+    assert_eq!(env.get_token_amount(&vault).await, Some(TOTAL - 2_000_000));
+
+    let (airdrop_pda, _airdrop_bump) = AirdropPda::get_address(AIRDROP_ID, &PROGRAM_ID);
+    let airdrop: AirdropPda = env
+        .from_account(&airdrop_pda)
+        .await
+        .ok_or("could not load the airdrop")?;
+    assert_eq!(airdrop.claimed, 2_000_000);
+
+    // Claiming twice
+    let twice = claim_airdrop(&user, AIRDROP_ID, 1, 2_000_000, proof)?;
+    let res_twice = env.execute_transaction(&[twice], &["User 1"]).await;
+    assert!(
+        res_twice.is_err_and(|err| err == BangkError::AirdropAlreadyClaimed),
+        "an airdrop was claimed twice"
+    );
+
+    // Claiming with someone else's proof
+    let other = *users.first().ok_or("missing user")?;
+    let stolen = claim_airdrop(
+        &other,
+        AIRDROP_ID,
+        2,
+        3_000_000,
+        get_merkle_proof(&leaves, 2),
+    )?;
+    let res_stolen = env.execute_transaction(&[stolen], &["User 0"]).await;
+    assert!(
+        res_stolen.is_err_and(|err| err == BangkError::InvalidMerkleProof),
+        "an airdrop was claimed by the wrong recipient"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn claim_vested_tokens() -> Result<()> {
+    let deadline = chrono::Utc::now().timestamp() + 86_400;
+    let scheme = UnvestingScheme {
+        kind: UnvestingType::PublicSells1,
+        start: 1,
+        duration: 10,
+        initial_unvesting: 20_000,
+        weekly_unvesting: 10_000,
+        final_unvesting: 0,
+    };
+    let (mut env, users, leaves) = init_airdrop(deadline, Some(scheme)).await?;
+    let user = *users.first().ok_or("missing user")?;
+
+    let instruction = claim_airdrop(
+        &user,
+        AIRDROP_ID,
+        0,
+        1_000_000,
+        get_merkle_proof(&leaves, 0),
+    )?;
+    env.execute_transaction(&[instruction], &["User 0"]).await?;
+
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let investment: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the user's investment")?;
+    assert!(investment
+        .investment
+        .investments
+        .first()
+        .is_some_and(
+            |invest| invest.amount_bought == 1_000_000 && invest.custom_rule == Some(scheme)
+        ));
+
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration")?;
+    assert_eq!(
+        config.invested_per_kind.get(&UnvestingType::PublicSells1),
+        Some(&1_000_000)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reclaim_unclaimed_tokens() -> Result<()> {
+    let deadline = chrono::Utc::now().timestamp() + 2;
+    let (mut env, users, leaves) = init_airdrop(deadline, None).await?;
+    let community = WalletType::Community.get_pda().0;
+    let initial = env
+        .get_token_amount(&community)
+        .await
+        .ok_or("missing community wallet")?;
+
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let early = reclaim_airdrop(&admin1, &admin2, AIRDROP_ID, WalletType::Community)?;
+    let res = env
+        .execute_transaction(&[early], &["Admin 1", "Admin 2"])
+        .await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::AirdropNotClosed),
+        "an airdrop was reclaimed before its deadline"
+    );
+
+    let user = *users.first().ok_or("missing user")?;
+    let instruction = claim_airdrop(
+        &user,
+        AIRDROP_ID,
+        0,
+        1_000_000,
+        get_merkle_proof(&leaves, 0),
+    )?;
+    env.execute_transaction(&[instruction], &["User 0"]).await?;
+    sleep(Duration::from_secs(3));
+
+    let late_user = *users.get(2).ok_or("missing user")?;
+    let late = claim_airdrop(
+        &late_user,
+        AIRDROP_ID,
+        2,
+        3_000_000,
+        get_merkle_proof(&leaves, 2),
+    )?;
+    let res_late = env.execute_transaction(&[late], &["User 2"]).await;
+    assert!(
+        res_late.is_err_and(|err| err == BangkError::AirdropClosed),
+        "an airdrop was claimed after its deadline"
+    );
+
+    let reclaim = reclaim_airdrop(&admin1, &admin2, AIRDROP_ID, WalletType::Community)?;
+    env.execute_transaction(&[reclaim], &["Admin 1", "Admin 2"])
+        .await?;
+    assert_eq!(
+        env.get_token_amount(&community).await,
+        Some(initial + TOTAL - 1_000_000)
+    );
+
+    let twice = reclaim_airdrop(&admin1, &admin2, AIRDROP_ID, WalletType::Community)?;
+    let res_twice = env
+        .execute_transaction(&[twice], &["Admin 1", "Admin 2"])
+        .await;
+    assert!(
+        res_twice.is_err_and(|err| err == BangkError::UniqueOperationAlreadyExecuted),
+        "an airdrop was reclaimed twice"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,275 @@
+// File: bangk-ico/tests/batch_release.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:57:21
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::{error, result};
+
+use bangk_ico::{
+    batch_release_lookup_table, batch_vesting_release, batch_vesting_release_messages,
+    batch_vesting_release_to_beneficiaries, UnvestingType, UserInvestmentPda, WalletType,
+    BATCH_RELEASE_ACCOUNTS_PER_USER, MAX_BATCH_RELEASE_USERS,
+    MAX_BATCH_RELEASE_USERS_WITH_LOOKUP_TABLE,
+};
+use bangk_onchain_common::{
+    security::{MultiSigPda, MultiSigType},
+    Error as BangkError,
+};
+use common::{add_investment, get_unvesting_def, launch_tokens, PROGRAM_ID, TOTAL_ICO_TOKENS};
+use solana_program_test::tokio;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction, hash::Hash, message::VersionedMessage,
+    packet::PACKET_DATA_SIZE, pubkey::Pubkey, signer::Signer,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+const INVEST_TYPE: UnvestingType = UnvestingType::TeamFounders;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const WEEK: i64 = 7 * 86_400;
+const BATCH_BUDGET: u32 = 1_400_000;
+
+#[test]
+fn split_batches() -> Result<()> {
+    let payer = Pubkey::new_unique();
+    let users = (0..=MAX_BATCH_RELEASE_USERS)
+        .map(|_| Pubkey::new_unique())
+        .collect::<Vec<_>>();
+
+    let instructions = batch_vesting_release(&payer, &users)?;
+    assert_eq!(
+        instructions.len(),
+        2,
+        "users should be split in two batches"
+    );
+    assert_eq!(
+        instructions
+            .first()
+            .map(|instruction| instruction.accounts.len()),
+        Some(8 + MAX_BATCH_RELEASE_USERS * BATCH_RELEASE_ACCOUNTS_PER_USER)
+    );
+    assert_eq!(
+        instructions
+            .last()
+            .map(|instruction| instruction.accounts.len()),
+        Some(8 + BATCH_RELEASE_ACCOUNTS_PER_USER)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn beneficiary_shards() -> Result<()> {
+    let payer = Pubkey::new_unique();
+    let user = Pubkey::new_from_array([1; 32]);
+    let mut same_shard = [1; 32];
+    same_shard[31] = 2;
+    let same_shard = Pubkey::new_from_array(same_shard);
+    let other_shard = Pubkey::new_from_array([2; 32]);
+
+    // The shard of the beneficiary is only given if it isn't the user's one
+    let instructions = batch_vesting_release_to_beneficiaries(
+        &payer,
+        &[(user, user), (user, same_shard), (user, other_shard)],
+    )?;
+    assert_eq!(
+        instructions
+            .first()
+            .map(|instruction| instruction.accounts.len()),
+        Some(8 + 3 * BATCH_RELEASE_ACCOUNTS_PER_USER + 1)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lookup_table_batches() -> Result<()> {
+    let payer = Pubkey::new_unique();
+    let releases = (0..=MAX_BATCH_RELEASE_USERS_WITH_LOOKUP_TABLE)
+        .map(|_| (Pubkey::new_unique(), Pubkey::new_unique()))
+        .collect::<Vec<_>>();
+
+    let (lookup_table, instructions) = batch_release_lookup_table(&payer, &payer, 0, &releases)?;
+    assert!(instructions.len() > 1, "the lookup table was not filled");
+    let budget = ComputeBudgetInstruction::set_compute_unit_limit(BATCH_BUDGET);
+    let messages = batch_vesting_release_messages(
+        &payer,
+        &releases,
+        &[budget],
+        &lookup_table,
+        Hash::default(),
+    )?;
+    assert_eq!(messages.len(), 2, "users should be split in two batches");
+
+    // Even with a beneficiary for each user, the transactions fit in a packet and lock few
+    // enough accounts
+    for message in &messages {
+        let VersionedMessage::V0(v0) = message else {
+            panic!("the message is not a v0 one");
+        };
+        let loaded = v0
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+            .sum::<usize>();
+        assert!(v0.account_keys.len() + loaded <= 64);
+        assert!(1 + 64 + message.serialize().len() <= PACKET_DATA_SIZE);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn release_batch() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let users = (0..3_u8).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    for user in &users {
+        add_investment(&mut env, user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    }
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    let api = env.wallets["API"].pubkey();
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let ico_pda = WalletType::Ico.get_pda().0;
+
+    // Release the tokens
+    let instructions = batch_vesting_release(&api, &users)?;
+    assert_eq!(
+        instructions.len(),
+        1,
+        "all users should fit in a single batch"
+    );
+    env.execute_transaction_custom_budget(&instructions, &["API"], BATCH_BUDGET)
+        .await?;
+
+    // Check results
+    let target = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    for user in &users {
+        let user_ata =
+            get_associated_token_address_with_program_id(user, &mint_address, &spl_token_2022::ID);
+        assert_eq!(env.get_token_amount(&user_ata).await, Some(target));
+    }
+    assert_eq!(
+        env.get_token_amount(&ico_pda).await,
+        Some(TOTAL_ICO_TOKENS - 3 * target)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn skip_invalid_users() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let investor = Pubkey::new_unique();
+    let stranger = Pubkey::new_unique();
+    add_investment(&mut env, &investor, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    let api = env.wallets["API"].pubkey();
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let ico_pda = WalletType::Ico.get_pda().0;
+
+    // The user without investment doesn't prevent the other one from being processed
+    let instructions = batch_vesting_release(&api, &[stranger, investor])?;
+    env.execute_transaction_custom_budget(&instructions, &["API"], BATCH_BUDGET)
+        .await?;
+
+    let target = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    let investor_ata =
+        get_associated_token_address_with_program_id(&investor, &mint_address, &spl_token_2022::ID);
+    let stranger_ata =
+        get_associated_token_address_with_program_id(&stranger, &mint_address, &spl_token_2022::ID);
+    assert_eq!(env.get_token_amount(&investor_ata).await, Some(target));
+    assert_eq!(env.get_token_amount(&stranger_ata).await, None);
+    assert_eq!(
+        env.get_token_amount(&ico_pda).await,
+        Some(TOTAL_ICO_TOKENS - target)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reject_foreign_multisig() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let users = (0..2_u8).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    for user in &users {
+        add_investment(&mut env, user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    }
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    // The compliance MultiSig can't sign the admin operations
+    let compliance = env.wallets["Compliance"].pubkey();
+    let (compliance_pda, _compliance_bump) =
+        MultiSigPda::get_address(MultiSigType::Compliance, &PROGRAM_ID);
+    let mut instructions = batch_vesting_release(&compliance, &users)?;
+    for instruction in &mut instructions {
+        if let Some(sig_admin) = instruction.accounts.get_mut(2) {
+            sig_admin.pubkey = compliance_pda;
+        }
+    }
+    let res = env
+        .execute_transaction_custom_budget(&instructions, &["Compliance"], BATCH_BUDGET)
+        .await;
+    assert!(res.is_err_and(|err| err == BangkError::InvalidPdaAddress));
+
+    // None of the tokens were marked as released
+    for user in &users {
+        let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(*user, &PROGRAM_ID);
+        let pda: UserInvestmentPda = env
+            .from_account(&investment_pda)
+            .await
+            .ok_or("could not load the investment PDA")?;
+        assert!(pda
+            .investment
+            .investments
+            .iter()
+            .all(|invest| invest.amount_released == 0));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+// File: bangk-ico/tests/beneficiary.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:56:20
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::{error, result};
+
+use bangk_ico::{
+    add_to_denylist, claim_vested, claim_vested_to_beneficiary, remove_from_denylist,
+    set_beneficiary, vesting_release, vesting_release_to_beneficiary, UnvestingType,
+    UserInvestmentPda,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, get_unvesting_def, launch_tokens, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+const INVEST_TYPE: UnvestingType = UnvestingType::TeamFounders;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const WEEK: i64 = 7 * 86_400;
+
+fn bgk_ata(wallet: &Pubkey) -> Pubkey {
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    get_associated_token_address_with_program_id(wallet, &mint_address, &spl_token_2022::ID)
+}
+
+#[tokio::test]
+async fn user_signature_required() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = env.add_wallet("User").await;
+    let custody = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+
+    let mut instruction = set_beneficiary(&api, &user, Some(&custody))?;
+    instruction.accounts[1].is_signer = false;
+    let res = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidSigner),
+        "the beneficiary was changed without the user's signature"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn release_to_beneficiary() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = env.add_wallet("User").await;
+    let custody = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+
+    let instruction1 = set_beneficiary(&api, &user, Some(&custody))?;
+    env.execute_transaction(&[instruction1], &["API", "User"])
+        .await?;
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert_eq!(pda.investment.beneficiary, Some(custody));
+    assert_eq!(env.get_token_amount(&bgk_ata(&custody)).await, Some(0));
+
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    // The tokens can't go to the user's own wallet anymore
+    let instruction2 = vesting_release(&api, &user)?;
+    let res = env.execute_transaction(&[instruction2], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::ATADoesNotExist),
+        "the tokens were not sent to the beneficiary"
+    );
+
+    let instruction3 = vesting_release_to_beneficiary(&api, &user, &custody)?;
+    env.execute_transaction(&[instruction3], &["API"]).await?;
+    let released = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    assert_eq!(
+        env.get_token_amount(&bgk_ata(&custody)).await,
+        Some(released)
+    );
+    assert!(env.get_account(&bgk_ata(&user)).await.is_none());
+
+    // The vesting stays attached to the user
+    let updated: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert_eq!(updated.investment.user, user);
+    assert_eq!(updated.investment.investments[0].amount_released, released);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn claim_and_remove_beneficiary() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let user = env.add_wallet("User").await;
+    let custody = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    let instruction1 = set_beneficiary(&user, &user, Some(&custody))?;
+    env.execute_transaction(&[instruction1], &["User"]).await?;
+    let instruction2 = claim_vested_to_beneficiary(&user, &user, &custody)?;
+    env.execute_transaction(&[instruction2], &["User"]).await?;
+    let released = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    assert_eq!(
+        env.get_token_amount(&bgk_ata(&custody)).await,
+        Some(released)
+    );
+
+    // Back to the user's own wallet
+    let instruction3 = set_beneficiary(&user, &user, None)?;
+    env.execute_transaction(&[instruction3], &["User"]).await?;
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert!(pda.investment.beneficiary.is_none());
+    let instruction4 = claim_vested(&user, &user)?;
+    env.execute_transaction(&[instruction4], &["User"]).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn denylisted_beneficiary() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let compliance = env.wallets["Compliance"].pubkey();
+    let user = env.add_wallet("User").await;
+    let custody = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let instruction = set_beneficiary(&api, &user, Some(&custody))?;
+    env.execute_transaction(&[instruction], &["API", "User"])
+        .await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    let add = add_to_denylist(&compliance, &custody)?;
+    env.execute_transaction(&[add], &["Compliance"]).await?;
+
+    let instruction1 = vesting_release_to_beneficiary(&api, &user, &custody)?;
+    let res1 = env.execute_transaction(&[instruction1], &["API"]).await;
+    assert!(
+        res1.is_err_and(|err| err == BangkError::DenylistedWallet),
+        "tokens were released to a denylisted beneficiary"
+    );
+    let instruction2 = claim_vested_to_beneficiary(&user, &user, &custody)?;
+    let res2 = env.execute_transaction(&[instruction2], &["User"]).await;
+    assert!(
+        res2.is_err_and(|err| err == BangkError::DenylistedWallet),
+        "a denylisted beneficiary received claimed tokens"
+    );
+
+    // The shard of the beneficiary can't be swapped for another one
+    let mut instruction3 = vesting_release_to_beneficiary(&api, &user, &custody)?;
+    instruction3.accounts[13] = instruction3.accounts[12].clone();
+    let res3 = env.execute_transaction(&[instruction3], &["API"]).await;
+    assert!(
+        res3.is_err(),
+        "the denylist of the beneficiary was bypassed"
+    );
+    assert_eq!(env.get_token_amount(&bgk_ata(&custody)).await, Some(0));
+
+    let remove = remove_from_denylist(&compliance, &custody)?;
+    env.execute_transaction(&[remove], &["Compliance"]).await?;
+    let instruction4 = vesting_release_to_beneficiary(&api, &user, &custody)?;
+    env.execute_transaction(&[instruction4], &["API"]).await?;
+    let released = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    assert_eq!(
+        env.get_token_amount(&bgk_ata(&custody)).await,
+        Some(released)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,343 @@
+// File: bangk-ico/tests/bonus.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:05:57
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+use std::{error, result};
+
+use bangk_ico::{
+    cancel_investment, cancel_investment_entry, set_bonus_rules, user_investment, BonusRule,
+    ConfigurationPda, InvestmentLink, InvestmentSelector, UnvestingScheme, UnvestingType,
+    UserInvestmentPda,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, issue_kyc, PROGRAM_ID, TOTAL_ICO_TOKENS};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer as _};
+use tests_utilities::onchain::Environment;
+
+pub mod common;
+
+const SMALL_AMOUNT: u64 = 500_000_000;
+const TIER_AMOUNT: u64 = 1_000_000_000;
+// 10% of `SMALL_AMOUNT`
+const SMALL_BONUS: u64 = 50_000_000;
+// 20% of `TIER_AMOUNT`
+const TIER_BONUS: u64 = 200_000_000;
+
+const BONUS_UNVESTING: UnvestingScheme = UnvestingScheme {
+    kind: UnvestingType::AdvisersPartners,
+    start: 4,
+    duration: 13,
+    initial_unvesting: 20_000,
+    weekly_unvesting: 10_000,
+    final_unvesting: 0,
+};
+
+/// 10% bonus during the private sale phase, 20% from `TIER_AMOUNT` tokens.
+const BONUS_RULES: [BonusRule; 2] = [
+    BonusRule {
+        kind: UnvestingType::PrivateSells,
+        phase_start: Some(0),
+        min_amount: 0,
+        rate: 10_000,
+        unvesting: BONUS_UNVESTING,
+    },
+    BonusRule {
+        kind: UnvestingType::PrivateSells,
+        phase_start: None,
+        min_amount: TIER_AMOUNT,
+        rate: 20_000,
+        unvesting: BONUS_UNVESTING,
+    },
+];
+
+async fn define_bonus_rules(env: &mut Environment, rules: Vec<BonusRule>) -> Result<()> {
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let instruction = set_bonus_rules(&admin1, &admin2, &admin3, rules)?;
+    env.execute_transaction(&[instruction], &["Admin 1", "Admin 2", "Admin 3"])
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn bonus_per_phase_and_tier() -> Result<()> {
+    let mut env = common::init_default().await?;
+    define_bonus_rules(&mut env, BONUS_RULES.to_vec()).await?;
+    let user = Pubkey::new_unique();
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+
+    add_investment(
+        &mut env,
+        &user,
+        SMALL_AMOUNT,
+        UnvestingType::PrivateSells,
+        None,
+    )
+    .await?;
+    add_investment(
+        &mut env,
+        &user,
+        TIER_AMOUNT,
+        UnvestingType::PrivateSells,
+        None,
+    )
+    .await?;
+    add_investment(
+        &mut env,
+        &user,
+        TIER_AMOUNT,
+        UnvestingType::PublicSells1,
+        None,
+    )
+    .await?;
+
+    let investment: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the user's investment")?;
+    let amounts: Vec<(UnvestingType, u64)> = investment
+        .investment
+        .investments
+        .iter()
+        .map(|invest| (invest.kind, invest.amount_bought))
+        .collect();
+    assert_eq!(
+        amounts,
+        vec![
+            (UnvestingType::PrivateSells, SMALL_AMOUNT),
+            (UnvestingType::AdvisersPartners, SMALL_BONUS),
+            (UnvestingType::PrivateSells, TIER_AMOUNT),
+            (UnvestingType::AdvisersPartners, TIER_BONUS),
+            (UnvestingType::PublicSells1, TIER_AMOUNT),
+        ]
+    );
+    assert!(investment
+        .investment
+        .investments
+        .get(1)
+        .is_some_and(|invest| invest.custom_rule == Some(BONUS_UNVESTING)));
+
+    // The bonus counts toward the ICO allocation, but not toward the sale phase
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration")?;
+    assert_eq!(
+        config.amount_invested,
+        SMALL_AMOUNT + SMALL_BONUS + 2 * TIER_AMOUNT + TIER_BONUS
+    );
+    assert_eq!(
+        config.sale_phases.first().map(|phase| phase.sold),
+        Some(SMALL_AMOUNT + TIER_AMOUNT)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bonus_follows_cancellation() -> Result<()> {
+    let mut env = common::init_default().await?;
+    define_bonus_rules(&mut env, BONUS_RULES.to_vec()).await?;
+    let user = Pubkey::new_unique();
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+    add_investment(
+        &mut env,
+        &user,
+        SMALL_AMOUNT,
+        UnvestingType::PrivateSells,
+        None,
+    )
+    .await?;
+
+    // The bonus is linked to its investment by a generated reference
+    let investment: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the user's investment")?;
+    let reference = investment
+        .investment
+        .investments
+        .first()
+        .and_then(|invest| invest.reference)
+        .ok_or("the investment has no reference")?;
+    assert!(investment
+        .investment
+        .investments
+        .get(1)
+        .is_some_and(|invest| invest.link == Some(InvestmentLink::Granted { user, reference })));
+
+    // And is cancelled in the same proportion
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let partial = cancel_investment(
+        &api,
+        &admin2,
+        &user,
+        UnvestingType::PrivateSells,
+        SMALL_AMOUNT / 2,
+    )?;
+    env.execute_transaction(&[partial], &["API", "Admin 2"])
+        .await?;
+    let halved: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the user's investment")?;
+    let amounts: Vec<(UnvestingType, u64)> = halved
+        .investment
+        .investments
+        .iter()
+        .map(|invest| (invest.kind, invest.amount_bought))
+        .collect();
+    assert_eq!(
+        amounts,
+        vec![
+            (UnvestingType::AdvisersPartners, SMALL_BONUS / 2),
+            (UnvestingType::PrivateSells, SMALL_AMOUNT / 2),
+        ]
+    );
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration")?;
+    assert_eq!(config.amount_invested, (SMALL_AMOUNT + SMALL_BONUS) / 2);
+
+    // Or entirely with it
+    let remaining = cancel_investment(
+        &api,
+        &admin2,
+        &user,
+        UnvestingType::PrivateSells,
+        SMALL_AMOUNT / 2,
+    )?;
+    env.execute_transaction(&[remaining], &["API", "Admin 2"])
+        .await?;
+    assert!(env
+        .from_account::<UserInvestmentPda>(&investment_pda)
+        .await
+        .is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bonus_follows_entry_cancellation() -> Result<()> {
+    let mut env = common::init_default().await?;
+    define_bonus_rules(&mut env, BONUS_RULES.to_vec()).await?;
+    let user = Pubkey::new_unique();
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    add_investment(
+        &mut env,
+        &user,
+        SMALL_AMOUNT,
+        UnvestingType::PrivateSells,
+        None,
+    )
+    .await?;
+    add_investment(
+        &mut env,
+        &user,
+        TIER_AMOUNT,
+        UnvestingType::PublicSells1,
+        None,
+    )
+    .await?;
+
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let instruction =
+        cancel_investment_entry(&api, &admin2, &user, InvestmentSelector::Index(0), None)?;
+    env.execute_transaction(&[instruction], &["API", "Admin 2"])
+        .await?;
+    let investment: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the user's investment")?;
+    let amounts: Vec<(UnvestingType, u64)> = investment
+        .investment
+        .investments
+        .iter()
+        .map(|invest| (invest.kind, invest.amount_bought))
+        .collect();
+    assert_eq!(amounts, vec![(UnvestingType::PublicSells1, TIER_AMOUNT)]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bonus_exceeding_allocation() -> Result<()> {
+    let mut env = common::init_default().await?;
+    define_bonus_rules(&mut env, BONUS_RULES.to_vec()).await?;
+    let user = Pubkey::new_unique();
+
+    issue_kyc(&mut env, &user).await?;
+    let api = env.wallets["API"].pubkey();
+    let instruction = user_investment(
+        &api,
+        &user,
+        UnvestingType::PrivateSells,
+        None,
+        TOTAL_ICO_TOKENS,
+    )?;
+    let res = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidAmount),
+        "the bonus exceeded the ICO allocation"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_bonus_rules() -> Result<()> {
+    let mut env = common::init_default().await?;
+
+    let invalid = [
+        BonusRule {
+            kind: UnvestingType::TeamFounders,
+            ..BONUS_RULES[0]
+        },
+        BonusRule {
+            rate: 0,
+            ..BONUS_RULES[0]
+        },
+        BonusRule {
+            unvesting: UnvestingScheme {
+                initial_unvesting: 0,
+                ..BONUS_UNVESTING
+            },
+            ..BONUS_RULES[0]
+        },
+    ];
+    for rule in invalid {
+        let admin1 = env.wallets["Admin 1"].pubkey();
+        let admin2 = env.wallets["Admin 2"].pubkey();
+        let admin3 = env.wallets["Admin 3"].pubkey();
+        let instruction = set_bonus_rules(&admin1, &admin2, &admin3, vec![rule])?;
+        let res = env
+            .execute_transaction(&[instruction], &["Admin 1", "Admin 2", "Admin 3"])
+            .await;
+        assert!(
+            res.is_err_and(|err| err == BangkError::InvalidBonusRule),
+            "an invalid bonus rule was accepted"
+        );
+    }
+
+    Ok(())
+}
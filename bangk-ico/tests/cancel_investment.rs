@@ -3,7 +3,7 @@
 // Creation date: Thursday 13 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Wednesday 21 August 2024 @ 19:33:07
+// Last modified: Sunday 18 October 2026 @ 17:16:50
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -17,7 +17,11 @@ type Result<T> = result::Result<T, Error>;
 
 use std::{error, result};
 
-use bangk_ico::{cancel_investment, user_investment, UnvestingType, UserInvestmentPda};
+use bangk_ico::{
+    cancel_investment, cancel_investment_entry, user_investment, user_investment_with_reference,
+    InvestmentSelector, UnvestingType, UserInvestmentPda,
+};
+use bangk_onchain_common::Error as BangkError;
 use solana_program_test::tokio;
 use solana_sdk::{pubkey::Pubkey, signer::Signer as _};
 
@@ -33,6 +37,7 @@ async fn one_investment() -> Result<()> {
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     // Create the investment
     let Ok(instruction1) = user_investment(
@@ -81,6 +86,7 @@ async fn partial() -> Result<()> {
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     // Create the investment
     let instruction1 = user_investment(
@@ -116,12 +122,47 @@ async fn partial() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn without_purchases_account() -> Result<()> {
+    let mut env = common::init_default().await?;
+
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
+    let instruction1 = user_investment(
+        &api,
+        &user,
+        UnvestingType::TeamFounders,
+        None,
+        INVESTED_AMOUNT,
+    )?;
+    env.execute_transaction(&[instruction1], &["API"]).await?;
+
+    // The clients predating the on-chain purchases don't give the account of the purchases
+    let mut instruction2 = cancel_investment(
+        &api,
+        &admin2,
+        &user,
+        UnvestingType::TeamFounders,
+        INVESTED_AMOUNT,
+    )?;
+    instruction2.accounts.truncate(6);
+    env.execute_transaction(&[instruction2], &["API", "Admin 2"])
+        .await?;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    assert!(env.get_account(&investment_pda).await.is_none());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn two_same_kind() -> Result<()> {
     let mut env = common::init_default().await?;
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     // Create the investment
     let instruction1 = user_investment(
@@ -152,3 +193,106 @@ async fn two_same_kind() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn specific_entry() -> Result<()> {
+    let mut env = common::init_default().await?;
+
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
+
+    let investments = [
+        (INVESTED_AMOUNT, [1; 32]),
+        (2 * INVESTED_AMOUNT, [2; 32]),
+        (INVESTED_AMOUNT, [3; 32]),
+    ];
+    for (amount, reference) in investments {
+        let instruction = user_investment_with_reference(
+            &api,
+            &user,
+            UnvestingType::PublicSells1,
+            None,
+            amount,
+            reference,
+        )?;
+        env.execute_transaction(&[instruction], &["API"]).await?;
+    }
+
+    // Partial cancellation of the second investment only
+    let partial = cancel_investment_entry(
+        &api,
+        &admin2,
+        &user,
+        InvestmentSelector::Index(1),
+        Some(INVESTED_AMOUNT / 2),
+    )?;
+    env.execute_transaction(&[partial], &["API", "Admin 2"])
+        .await?;
+
+    // Full cancellation of the first one, found by its reference
+    let full = cancel_investment_entry(
+        &api,
+        &admin2,
+        &user,
+        InvestmentSelector::Reference([1; 32]),
+        None,
+    )?;
+    env.execute_transaction(&[full], &["API", "Admin 2"])
+        .await?;
+
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    let remaining: Vec<(u64, Option<[u8; 32]>)> = pda
+        .investment
+        .investments
+        .iter()
+        .map(|invest| (invest.amount_bought, invest.reference))
+        .collect();
+    assert_eq!(
+        remaining,
+        vec![
+            (3 * INVESTED_AMOUNT / 2, Some([2; 32])),
+            (INVESTED_AMOUNT, Some([3; 32])),
+        ]
+    );
+
+    let invalid = [
+        (
+            InvestmentSelector::Index(2),
+            None,
+            BangkError::InvestmentDoesNotExist,
+        ),
+        (
+            InvestmentSelector::Reference([1; 32]),
+            None,
+            BangkError::InvestmentDoesNotExist,
+        ),
+        (
+            InvestmentSelector::Index(1),
+            Some(INVESTED_AMOUNT + 1),
+            BangkError::InvalidAmount,
+        ),
+        (
+            InvestmentSelector::Index(1),
+            Some(0),
+            BangkError::InvalidAmount,
+        ),
+    ];
+    for (target, amount, expected) in invalid {
+        let instruction = cancel_investment_entry(&api, &admin2, &user, target, amount)?;
+        let res = env
+            .execute_transaction(&[instruction], &["API", "Admin 2"])
+            .await;
+        assert!(
+            res.is_err_and(|err| err == expected),
+            "an invalid cancellation was accepted"
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,295 @@
+// File: bangk-ico/tests/caps.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 13:41:49
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+use std::{error, result, thread::sleep, time::Duration};
+
+use bangk_ico::{
+    cancel_investment, process_adviser_post_launch_investment,
+    queue_adviser_post_launch_investment, set_investment_caps, user_investment, ConfigurationPda,
+    InvestmentCap, UnvestingType, TIMELOCK_DELAY,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, launch_tokens, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer as _};
+use tests_utilities::onchain::Environment;
+
+pub mod common;
+
+const INVESTED_AMOUNT: u64 = 1_000_000_000;
+
+async fn define_caps(env: &mut Environment, caps: Vec<InvestmentCap>) -> Result<()> {
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let instruction = set_investment_caps(&admin1, &admin2, &admin3, caps)?;
+    env.execute_transaction(&[instruction], &["Admin 1", "Admin 2", "Admin 3"])
+        .await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn category_caps() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+    define_caps(
+        &mut env,
+        vec![InvestmentCap {
+            kind: UnvestingType::PrivateSells,
+            total: Some(2 * INVESTED_AMOUNT),
+            per_user: None,
+        }],
+    )
+    .await?;
+
+    let user1 = Pubkey::new_unique();
+    let user2 = Pubkey::new_unique();
+    add_investment(
+        &mut env,
+        &user1,
+        INVESTED_AMOUNT,
+        UnvestingType::PrivateSells,
+        None,
+    )
+    .await?;
+    add_investment(
+        &mut env,
+        &user2,
+        INVESTED_AMOUNT,
+        UnvestingType::PrivateSells,
+        None,
+    )
+    .await?;
+    // Other types of investment are not concerned
+    add_investment(
+        &mut env,
+        &user1,
+        INVESTED_AMOUNT,
+        UnvestingType::PublicSells1,
+        None,
+    )
+    .await?;
+
+    let api = env.wallets["API"].pubkey();
+    let instruction = user_investment(&api, &user1, UnvestingType::PrivateSells, None, 1)?;
+    let res = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvestmentCapExceeded),
+        "the private sale cap was exceeded"
+    );
+
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration")?;
+    assert_eq!(
+        config
+            .invested_per_kind
+            .get(&UnvestingType::PrivateSells)
+            .copied(),
+        Some(2 * INVESTED_AMOUNT)
+    );
+    assert_eq!(
+        config
+            .invested_per_kind
+            .get(&UnvestingType::PublicSells1)
+            .copied(),
+        Some(INVESTED_AMOUNT)
+    );
+
+    // Cancelled investments free the cap
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let cancel = cancel_investment(
+        &api,
+        &admin2,
+        &user2,
+        UnvestingType::PrivateSells,
+        INVESTED_AMOUNT,
+    )?;
+    env.execute_transaction(&[cancel], &["API", "Admin 2"])
+        .await?;
+    add_investment(
+        &mut env,
+        &user1,
+        INVESTED_AMOUNT,
+        UnvestingType::PrivateSells,
+        None,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn user_caps() -> Result<()> {
+    let mut env = common::init_default().await?;
+    define_caps(
+        &mut env,
+        vec![InvestmentCap {
+            kind: UnvestingType::PublicSells1,
+            total: None,
+            per_user: Some(INVESTED_AMOUNT),
+        }],
+    )
+    .await?;
+
+    let user = Pubkey::new_unique();
+    add_investment(
+        &mut env,
+        &user,
+        INVESTED_AMOUNT,
+        UnvestingType::PublicSells1,
+        None,
+    )
+    .await?;
+    // Another user can still invest
+    let other = Pubkey::new_unique();
+    add_investment(
+        &mut env,
+        &other,
+        INVESTED_AMOUNT,
+        UnvestingType::PublicSells1,
+        None,
+    )
+    .await?;
+
+    let api = env.wallets["API"].pubkey();
+    let instruction = user_investment(&api, &user, UnvestingType::PublicSells1, None, 1)?;
+    let res = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvestmentCapExceeded),
+        "the cap of a single user was exceeded"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn post_launch_advisers_caps() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    define_caps(
+        &mut env,
+        vec![InvestmentCap {
+            kind: UnvestingType::AdvisersPartners,
+            total: Some(3 * INVESTED_AMOUNT),
+            per_user: Some(INVESTED_AMOUNT),
+        }],
+    )
+    .await?;
+    let user = Pubkey::new_unique();
+    add_investment(
+        &mut env,
+        &user,
+        INVESTED_AMOUNT,
+        UnvestingType::AdvisersPartners,
+        None,
+    )
+    .await?;
+    launch_tokens(&mut env, chrono::Utc::now().timestamp() - 4 * 7 * 24 * 3600).await?;
+
+    let api = env.wallets["API"].pubkey();
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+
+    // The overall cap is checked when queuing
+    let too_much = queue_adviser_post_launch_investment(
+        &admin1,
+        &admin2,
+        &admin3,
+        &user,
+        None,
+        3 * INVESTED_AMOUNT,
+    )?;
+    let res = env
+        .execute_transaction(&[too_much], &["Admin 1", "Admin 2", "Admin 3"])
+        .await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvestmentCapExceeded),
+        "the advisers cap was exceeded"
+    );
+
+    // The cap of the user is checked when processing
+    let instruction = queue_adviser_post_launch_investment(
+        &admin1,
+        &admin2,
+        &admin3,
+        &user,
+        None,
+        INVESTED_AMOUNT,
+    )?;
+    env.execute_transaction(&[instruction], &["Admin 1", "Admin 2", "Admin 3"])
+        .await?;
+    sleep(Duration::from_secs(TIMELOCK_DELAY as u64));
+    let process = process_adviser_post_launch_investment(&api, &user, None, INVESTED_AMOUNT)?;
+    let res_user = env.execute_transaction(&[process], &["API"]).await;
+    assert!(
+        res_user.is_err_and(|err| err == BangkError::InvestmentCapExceeded),
+        "the cap of a single adviser was exceeded"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_caps() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let user = Pubkey::new_unique();
+    add_investment(
+        &mut env,
+        &user,
+        INVESTED_AMOUNT,
+        UnvestingType::PrivateSells,
+        None,
+    )
+    .await?;
+
+    let invalid = [
+        vec![InvestmentCap {
+            kind: UnvestingType::PrivateSells,
+            total: Some(INVESTED_AMOUNT - 1),
+            per_user: None,
+        }],
+        vec![InvestmentCap {
+            kind: UnvestingType::PublicSells1,
+            total: Some(INVESTED_AMOUNT),
+            per_user: Some(2 * INVESTED_AMOUNT),
+        }],
+        vec![InvestmentCap {
+            kind: UnvestingType::PublicSells1,
+            total: None,
+            per_user: Some(0),
+        }],
+        vec![
+            InvestmentCap {
+                kind: UnvestingType::PublicSells1,
+                total: None,
+                per_user: Some(INVESTED_AMOUNT),
+            },
+            InvestmentCap {
+                kind: UnvestingType::PublicSells1,
+                total: Some(INVESTED_AMOUNT),
+                per_user: None,
+            },
+        ],
+    ];
+    for caps in invalid {
+        let res = define_caps(&mut env, caps).await;
+        assert!(res.is_err(), "invalid investment caps were accepted");
+    }
+
+    Ok(())
+}
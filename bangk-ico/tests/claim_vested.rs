@@ -0,0 +1,269 @@
+// File: bangk-ico/tests/claim_vested.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:42:24
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::{error, result};
+
+use bangk_ico::{
+    claim_vested, claim_vested_partially, UnvestingType, UserInvestmentPda, WalletType,
+};
+use bangk_onchain_common::{
+    security::{MultiSigPda, MultiSigType},
+    Error as BangkError,
+};
+use common::{add_investment, get_unvesting_def, launch_tokens, PROGRAM_ID, TOTAL_ICO_TOKENS};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+const INVEST_TYPE: UnvestingType = UnvestingType::TeamFounders;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const WEEK: i64 = 7 * 86_400;
+
+#[tokio::test]
+async fn before_launch() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+
+    // Claim the tokens
+    let instruction1 = claim_vested(&user, &user)?;
+    let res = env.execute_transaction(&[instruction1], &["User"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::IcoUnvestBeforeLaunch),
+        "there was an unexpected error in the instruction"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn claim() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let user_ata =
+        get_associated_token_address_with_program_id(&user, &mint_address, &spl_token_2022::ID);
+    let ico_pda = WalletType::Ico.get_pda().0;
+
+    // Claim the tokens, the user paying for their ATA
+    let instruction1 = claim_vested(&user, &user)?;
+    env.execute_transaction(&[instruction1], &["User"]).await?;
+
+    // Check results
+    let target = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert_eq!(
+        pda.investment
+            .investments
+            .iter()
+            .find(|invest| invest.kind == INVEST_TYPE)
+            .map(|invest| invest.amount_released),
+        Some(target)
+    );
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(target));
+    assert_eq!(
+        env.get_token_amount(&ico_pda).await,
+        Some(TOTAL_ICO_TOKENS - target)
+    );
+
+    // Claiming a second time doesn't release anything more
+    let instruction2 = claim_vested(&user, &user)?;
+    env.execute_transaction(&[instruction2], &["User"]).await?;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(target));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn sponsored_claim() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    let api = env.wallets["API"].pubkey();
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let user_ata =
+        get_associated_token_address_with_program_id(&user, &mint_address, &spl_token_2022::ID);
+
+    // Claim the tokens, Bangk paying for the transaction
+    let instruction1 = claim_vested(&api, &user)?;
+    env.execute_transaction(&[instruction1], &["API", "User"])
+        .await?;
+
+    let target = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(target));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn not_signed_by_user() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let user = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    let thief = env.add_wallet("Thief").await;
+    let mut instruction1 = claim_vested(&thief, &user)?;
+    for account in &mut instruction1.accounts {
+        if account.pubkey == user {
+            account.is_signer = false;
+        }
+    }
+    let res = env.execute_transaction(&[instruction1], &["Thief"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidSigner),
+        "there was an unexpected error in the instruction"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn foreign_multisig() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    // Only the admin MultiSig can be given as the authority of the transfer
+    let (compliance_pda, _compliance_bump) =
+        MultiSigPda::get_address(MultiSigType::Compliance, &PROGRAM_ID);
+    let mut instruction1 = claim_vested(&user, &user)?;
+    if let Some(sig_admin) = instruction1.accounts.get_mut(2) {
+        sig_admin.pubkey = compliance_pda;
+    }
+    let res = env.execute_transaction(&[instruction1], &["User"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidPdaAddress),
+        "there was an unexpected error in the instruction"
+    );
+
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert!(pda
+        .investment
+        .investments
+        .iter()
+        .all(|invest| invest.amount_released == 0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn partial_claim() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let user_ata =
+        get_associated_token_address_with_program_id(&user, &mint_address, &spl_token_2022::ID);
+    let available = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    let claimed = available / 4;
+
+    // Nothing to claim is not a claim
+    let instruction1 = claim_vested_partially(&user, &user, None, 0)?;
+    let res = env.execute_transaction(&[instruction1], &["User"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidAmount),
+        "there was an unexpected error in the instruction"
+    );
+
+    // Claim part of the tokens
+    let instruction2 = claim_vested_partially(&user, &user, None, claimed)?;
+    env.execute_transaction(&[instruction2], &["User"]).await?;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(claimed));
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert_eq!(pda.investment.investments[0].amount_released, claimed);
+
+    // The rest stays available
+    let instruction3 = claim_vested(&user, &user)?;
+    env.execute_transaction(&[instruction3], &["User"]).await?;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(available));
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+// File: bangk-ico/tests/clawback.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 12:58:42
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+use std::{error, result};
+
+use bangk_ico::{
+    execute_clawback, queue_clawback, vesting_release, ConfigurationPda, UnvestingType,
+    UserInvestmentPda, WalletType, TIMELOCK_DELAY,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{
+    add_investment, get_unvesting_def, launch_tokens, PROGRAM_ID, TOTAL_ICO_TOKENS,
+    TOTAL_RESERVE_TOKENS,
+};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+const INVEST_TYPE: UnvestingType = UnvestingType::TeamFounders;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const WEEK: i64 = 7 * 86_400;
+
+#[tokio::test]
+async fn before_launch() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let user = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+
+    let instruction1 = queue_clawback(
+        &api,
+        &admin2,
+        &admin4,
+        &user,
+        None,
+        chrono::Utc::now().timestamp(),
+        WalletType::Reserve,
+    )?;
+    let res = env
+        .execute_transaction(&[instruction1], &["API", "Admin 2", "Admin 4"])
+        .await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::IcoUnvestBeforeLaunch),
+        "there was an unexpected error in the instruction"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn not_enough_signers() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let user = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(&mut env, chrono::Utc::now().timestamp() - WEEK).await?;
+
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let instruction1 = queue_clawback(
+        &api,
+        &admin2,
+        &admin2,
+        &user,
+        None,
+        chrono::Utc::now().timestamp(),
+        WalletType::Reserve,
+    )?;
+    let res = env
+        .execute_transaction(&[instruction1], &["API", "Admin 2"])
+        .await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidSigner),
+        "there was an unexpected error in the instruction"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn clawback_to_reserve() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let user = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+    let user_ata =
+        get_associated_token_address_with_program_id(&user, &mint_address, &spl_token_2022::ID);
+    let ico_pda = WalletType::Ico.get_pda().0;
+    let reserve_pda = WalletType::Reserve.get_pda().0;
+
+    // Queue the clawback
+    let date = chrono::Utc::now().timestamp();
+    let instruction1 = queue_clawback(
+        &api,
+        &admin2,
+        &admin4,
+        &user,
+        Some(INVEST_TYPE),
+        date,
+        WalletType::Reserve,
+    )?;
+    env.execute_transaction(&[instruction1], &["API", "Admin 2", "Admin 4"])
+        .await?;
+
+    // Executing it right away fails
+    let instruction2 = execute_clawback(&api, &user, Some(INVEST_TYPE), date, WalletType::Reserve)?;
+    let res = env.execute_transaction(&[instruction2], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::QueuedInstructionNotReady),
+        "there was an unexpected error in the instruction"
+    );
+
+    // Wait for the timeout
+    sleep(Duration::from_secs(TIMELOCK_DELAY as u64));
+    let instruction3 = execute_clawback(&api, &user, Some(INVEST_TYPE), date, WalletType::Reserve)?;
+    env.execute_transaction(&[instruction3], &["API"]).await?;
+
+    // Only the unvested tokens are left in the investment
+    let kept = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    let clawed_back = INVESTED_AMOUNT - kept;
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    let investment = pda
+        .investment
+        .investments
+        .first()
+        .copied()
+        .ok_or("no investment left")?;
+    assert_eq!(investment.amount_bought, kept);
+    assert!(investment.fully_vested, "the vesting should be stopped");
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration PDA")?;
+    assert_eq!(config.amount_invested, kept);
+    assert_eq!(
+        env.get_token_amount(&ico_pda).await,
+        Some(TOTAL_ICO_TOKENS - clawed_back)
+    );
+    assert_eq!(
+        env.get_token_amount(&reserve_pda).await,
+        Some(TOTAL_RESERVE_TOKENS + clawed_back)
+    );
+
+    // The remaining tokens can still be released
+    let instruction4 = vesting_release(&api, &user)?;
+    env.execute_transaction(&[instruction4], &["API"]).await?;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(kept));
+    assert_eq!(
+        env.get_token_amount(&ico_pda).await,
+        Some(TOTAL_ICO_TOKENS - clawed_back - kept)
+    );
+
+    Ok(())
+}
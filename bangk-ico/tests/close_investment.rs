@@ -0,0 +1,93 @@
+// File: bangk-ico/tests/close_investment.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 13:49:15
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::{error, result};
+
+use bangk_ico::{claim_vested, close_user_investment, UnvestingType, UserInvestmentPda};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, get_unvesting_def, launch_tokens, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::signer::Signer;
+
+const INVEST_TYPE: UnvestingType = UnvestingType::TeamFounders;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const WEEK: i64 = 7 * 86_400;
+
+#[tokio::test]
+async fn close_fully_released() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+
+    // Nothing was released yet
+    let early = close_user_investment(&api, &user, &api)?;
+    let res = env.execute_transaction(&[early], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvestmentNotFullyReleased),
+        "an investment that was not released was closed"
+    );
+
+    // Everything is released
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - (i64::from(scheme.duration) + 1) * WEEK,
+    )
+    .await?;
+    let claim = claim_vested(&user, &user)?;
+    env.execute_transaction(&[claim], &["User"]).await?;
+
+    // The rent goes back to whoever paid for the account
+    let wrong_payer = close_user_investment(&api, &user, &user)?;
+    let res_payer = env.execute_transaction(&[wrong_payer], &["API"]).await;
+    assert!(
+        res_payer.is_err_and(|err| err == BangkError::AccountOwnerMismatch),
+        "the rent was refunded to the wrong wallet"
+    );
+
+    let rent = env
+        .get_account(&investment_pda)
+        .await
+        .ok_or("missing investment PDA")?
+        .lamports;
+    let before = env
+        .get_account(&api)
+        .await
+        .ok_or("missing API wallet")?
+        .lamports;
+    let instruction = close_user_investment(&api, &user, &api)?;
+    env.execute_transaction(&[instruction], &["API"]).await?;
+    assert!(env.get_account(&investment_pda).await.is_none());
+    let after = env
+        .get_account(&api)
+        .await
+        .ok_or("missing API wallet")?
+        .lamports;
+    // The API also paid for the transaction's fees
+    assert!(after > before && after <= before + rent);
+
+    Ok(())
+}
@@ -3,7 +3,7 @@
 // Creation date: Monday 17 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Thursday 22 August 2024 @ 12:45:28
+// Last modified: Sunday 18 October 2026 @ 13:17:15
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -17,8 +17,9 @@ type Result<T> = result::Result<T, Error>;
 use std::{error, result};
 
 use bangk_ico::{
-    create_mint, initialize, launch_bgk, process_instruction, queue_transfer_from_internal_wallet,
-    user_investment, UnvestingScheme, UnvestingType, WalletType,
+    create_mint, initialize, initialize_sale_treasury, issue_kyc_attestation, launch_bgk,
+    process_instruction, queue_transfer_from_internal_wallet, set_compliance_multisig,
+    set_sale_phases, user_investment, SalePhase, UnvestingScheme, UnvestingType, WalletType,
 };
 use solana_program_test::processor;
 use solana_sdk::{pubkey::Pubkey, signer::Signer as _};
@@ -29,6 +30,7 @@ pub const PROGRAM_ID: Pubkey =
 pub const TOTAL_BGK_TOKENS: u64 = 177_000_000_000_000;
 pub const TOTAL_RESERVE_TOKENS: u64 = 30_000_000_000_000;
 pub const TOTAL_ICO_TOKENS: u64 = 50_000_000_000_000;
+pub const KYC_VALIDITY: i64 = 365 * 86_400;
 
 /// Get the default unvesting schemes definitions
 #[must_use]
@@ -101,13 +103,115 @@ pub async fn init_default() -> Result<Environment> {
     let admin2 = env.add_wallet("Admin 2").await;
     let admin3 = env.add_wallet("Admin 3").await;
     let admin4 = env.add_wallet("Admin 4").await;
+    let compliance = env.add_wallet("Compliance").await;
 
     let instruction = initialize(&api_pub, &api_pub, &admin1, &admin2, &admin3, &admin4)?;
     env.execute_transaction(&[instruction], &["API"]).await?;
+    open_sale_phases(&mut env).await?;
+
+    let compliance_keys = set_compliance_multisig(&admin1, &admin2, &admin3, vec![compliance])?;
+    env.execute_transaction(&[compliance_keys], &["Admin 1", "Admin 2", "Admin 3"])
+        .await?;
 
     Ok(env)
 }
 
+/// Issue a KYC attestation valid for a year to a user.
+///
+/// # Errors
+/// If the instruction failed
+pub async fn issue_kyc(env: &mut Environment, user: &Pubkey) -> Result<()> {
+    let compliance = env.wallets["Compliance"].pubkey();
+    let expiry = chrono::Utc::now().timestamp().saturating_add(KYC_VALIDITY);
+
+    let instruction = issue_kyc_attestation(&compliance, user, 1, expiry, *b"FR")?;
+    env.execute_transaction(&[instruction], &["Compliance"])
+        .await?;
+
+    Ok(())
+}
+
+/// Open a never ending sale phase for all the types of investments that are sold.
+///
+/// # Errors
+/// If the instruction failed
+pub async fn open_sale_phases(env: &mut Environment) -> Result<()> {
+    let phases = [
+        UnvestingType::PrivateSells,
+        UnvestingType::PublicSells1,
+        UnvestingType::PublicSells2,
+        UnvestingType::PublicSells3,
+    ]
+    .into_iter()
+    .map(|kind| SalePhase {
+        kind,
+        start: 0,
+        end: i64::MAX,
+        price: 1,
+        cap: TOTAL_ICO_TOKENS,
+        sold: 0,
+    })
+    .collect();
+    update_sale_phases(env, phases).await
+}
+
+/// Replace the sale phases.
+///
+/// # Errors
+/// If the instruction failed
+pub async fn update_sale_phases(env: &mut Environment, phases: Vec<SalePhase>) -> Result<()> {
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let instruction = set_sale_phases(&admin1, &admin2, &admin3, phases)?;
+    env.execute_transaction(&[instruction], &["Admin 1", "Admin 2", "Admin 3"])
+        .await?;
+
+    Ok(())
+}
+
+/// Initializes the testing environment with a mock stablecoin accepted for the purchases,
+/// and a sale phase for `PublicSells1` running for a day.
+///
+/// # Returns
+/// The environment and the mint of the mock stablecoin.
+///
+/// # Errors
+/// If the initialization failed
+pub async fn init_stablecoin_sale(
+    token_program: &Pubkey,
+    price: u64,
+) -> Result<(Environment, Pubkey)> {
+    const DAY: i64 = 86400;
+    let mut env = init_default().await?;
+    let now = chrono::Utc::now().timestamp();
+
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let mint = env
+        .create_mock_stablecoin("Mock USD", token_program, 6)
+        .await;
+    let instruction = initialize_sale_treasury(&admin1, &admin2, &admin3, &mint, token_program)?;
+    env.execute_transaction(&[instruction], &["Admin 1", "Admin 2", "Admin 3"])
+        .await?;
+
+    update_sale_phases(
+        &mut env,
+        vec![SalePhase {
+            kind: UnvestingType::PublicSells1,
+            start: now.saturating_sub(DAY),
+            end: now.saturating_add(DAY),
+            price,
+            cap: TOTAL_ICO_TOKENS,
+            sold: 0,
+        }],
+    )
+    .await?;
+
+    Ok((env, mint))
+}
+
 /// Initializes the testing environment with the mint created and the tokens minted
 ///
 /// # Errors
@@ -136,6 +240,7 @@ pub async fn add_investment(
     custom_rule: Option<UnvestingScheme>,
 ) -> Result<()> {
     println!("adding investment for wallet {user}");
+    issue_kyc(env, user).await?;
     let api = env.wallets["API"].pubkey();
 
     let instruction = user_investment(&api, user, kind, custom_rule, amount)?;
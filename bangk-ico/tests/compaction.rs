@@ -0,0 +1,94 @@
+// File: bangk-ico/tests/compaction.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:58:37
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::{error, result};
+
+use bangk_ico::{compact_investments, UnvestingType, UserInvestmentPda};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+const INVEST_TYPE: UnvestingType = UnvestingType::PrivateSells;
+const INVESTED_AMOUNT: u64 = 1_000_000_000;
+
+#[tokio::test]
+async fn unauthorized_compaction() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+
+    let instructions = compact_investments(&user, &[user])?;
+    let res = env.execute_transaction(&instructions, &["User"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidSigner),
+        "the investments were compacted by an unknown wallet"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn compact_entries() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = Pubkey::new_unique();
+    for idx in 1..=3 {
+        add_investment(&mut env, &user, idx * INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    }
+    add_investment(
+        &mut env,
+        &user,
+        INVESTED_AMOUNT,
+        UnvestingType::TeamFounders,
+        None,
+    )
+    .await?;
+
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let before: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert_eq!(before.investment.investments.len(), 4);
+
+    let instructions = compact_investments(&api, &[user])?;
+    env.execute_transaction(&instructions, &["API"]).await?;
+
+    let after: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert_eq!(after.investment.investments.len(), 2);
+    assert_eq!(after.investment.investments[0].kind, INVEST_TYPE);
+    assert_eq!(
+        after.investment.investments[0].timestamp,
+        before.investment.investments[0].timestamp
+    );
+    for kind in [INVEST_TYPE, UnvestingType::TeamFounders] {
+        assert_eq!(
+            after.investment.invested(kind),
+            before.investment.invested(kind),
+            "the totals should be kept"
+        );
+    }
+
+    Ok(())
+}
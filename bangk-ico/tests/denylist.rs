@@ -0,0 +1,155 @@
+// File: bangk-ico/tests/denylist.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 13:21:40
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+use std::thread::sleep;
+use std::time::Duration;
+use std::{error, result};
+
+use bangk_ico::{
+    add_to_denylist, execute_transfer_from_internal_wallet, get_denylist_shard,
+    queue_transfer_from_internal_wallet, remove_from_denylist, vesting_release, DenylistPda,
+    UnvestingType, WalletType, TIMELOCK_DELAY,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, launch_tokens, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer as _};
+
+pub mod common;
+
+const INVESTED_AMOUNT: u64 = 1_000_000_000;
+const AMOUNT: u64 = 10_000_000;
+
+#[tokio::test]
+async fn add_and_remove() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let compliance = env.wallets["Compliance"].pubkey();
+    let wallet = Pubkey::new_unique();
+    let (denylist_pda, _denylist_bump) =
+        DenylistPda::get_address(get_denylist_shard(&wallet), &PROGRAM_ID);
+
+    let instruction = add_to_denylist(&compliance, &wallet)?;
+    env.execute_transaction(&[instruction], &["Compliance"])
+        .await?;
+    let denylist: DenylistPda = env
+        .from_account(&denylist_pda)
+        .await
+        .ok_or("could not load the denylist")?;
+    assert_eq!(denylist.shard, get_denylist_shard(&wallet));
+    assert!(denylist.contains(&wallet), "the wallet was not denylisted");
+
+    // A wallet can't be added twice
+    let twice = add_to_denylist(&compliance, &wallet)?;
+    let res = env.execute_transaction(&[twice], &["Compliance"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::UniqueOperationAlreadyExecuted),
+        "a wallet was added twice to the denylist"
+    );
+
+    let remove = remove_from_denylist(&compliance, &wallet)?;
+    env.execute_transaction(&[remove], &["Compliance"]).await?;
+    let updated: DenylistPda = env
+        .from_account(&denylist_pda)
+        .await
+        .ok_or("could not load the denylist")?;
+    assert!(updated.wallets.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn release_to_denylisted_user() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let compliance = env.wallets["Compliance"].pubkey();
+    let user = Pubkey::new_unique();
+    add_investment(
+        &mut env,
+        &user,
+        INVESTED_AMOUNT,
+        UnvestingType::PublicSells1,
+        None,
+    )
+    .await?;
+    launch_tokens(&mut env, chrono::Utc::now().timestamp() - 3 * 7 * 86_400).await?;
+
+    let add = add_to_denylist(&compliance, &user)?;
+    env.execute_transaction(&[add], &["Compliance"]).await?;
+
+    let blocked = vesting_release(&api, &user)?;
+    let res = env.execute_transaction(&[blocked], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::DenylistedWallet),
+        "tokens were released to a denylisted user"
+    );
+
+    let remove = remove_from_denylist(&compliance, &user)?;
+    env.execute_transaction(&[remove], &["Compliance"]).await?;
+    let instruction = vesting_release(&api, &user)?;
+    env.execute_transaction(&[instruction], &["API"]).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn transfer_to_denylisted_wallet() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let compliance = env.wallets["Compliance"].pubkey();
+    let user = Pubkey::new_unique();
+
+    let queue = queue_transfer_from_internal_wallet(
+        &api,
+        &admin2,
+        &admin4,
+        &user,
+        WalletType::Reserve,
+        AMOUNT,
+    )?;
+    env.execute_transaction(&[queue], &["API", "Admin 2", "Admin 4"])
+        .await?;
+    let add = add_to_denylist(&compliance, &user)?;
+    env.execute_transaction(&[add], &["Compliance"]).await?;
+    sleep(Duration::from_secs(TIMELOCK_DELAY as u64));
+
+    let instruction =
+        execute_transfer_from_internal_wallet(&api, &user, WalletType::Reserve, AMOUNT)?;
+    let res = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::DenylistedWallet),
+        "tokens were transferred to a denylisted wallet"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unauthorized_compliance() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let api = env.wallets["API"].pubkey();
+    let wallet = Pubkey::new_unique();
+
+    let instruction = add_to_denylist(&api, &wallet)?;
+    let res = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidSigner),
+        "a wallet was denylisted without the compliance key"
+    );
+
+    Ok(())
+}
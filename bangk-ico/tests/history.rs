@@ -0,0 +1,189 @@
+// File: bangk-ico/tests/history.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 15:57:24
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::{error, result};
+
+use bangk_ico::{
+    batch_vesting_release, claim_vested, claim_vested_partially, enable_release_history,
+    ReleaseHistoryPda, UnvestingType, UserInvestmentPda, YearlyStatement,
+    BATCH_RELEASE_ACCOUNTS_PER_USER,
+};
+use bangk_onchain_common::Error as BangkError;
+use chrono::Datelike as _;
+use common::{add_investment, get_unvesting_def, launch_tokens, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+const INVEST_TYPE: UnvestingType = UnvestingType::PrivateSells;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const PARTIAL_AMOUNT: u64 = 1_000_000;
+const WEEK: i64 = 7 * 86_400;
+const BATCH_BUDGET: u32 = 1_400_000;
+
+#[tokio::test]
+async fn enable_history_twice() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = env.add_wallet("User").await;
+
+    let instruction1 = enable_release_history(&api, &user)?;
+    env.execute_transaction(&[instruction1], &["API", "User"])
+        .await?;
+
+    let instruction2 = enable_release_history(&api, &user)?;
+    let res = env
+        .execute_transaction(&[instruction2], &["API", "User"])
+        .await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::AccountAlreadyExists),
+        "the release history was created twice"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn record_releases() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let instruction = enable_release_history(&user, &user)?;
+    env.execute_transaction(&[instruction], &["User"]).await?;
+
+    // Release the tokens in two steps
+    let instruction1 = claim_vested_partially(&user, &user, None, PARTIAL_AMOUNT)?;
+    env.execute_transaction(&[instruction1], &["User"]).await?;
+    let instruction2 = claim_vested(&user, &user)?;
+    env.execute_transaction(&[instruction2], &["User"]).await?;
+
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let user_ata =
+        get_associated_token_address_with_program_id(&user, &mint_address, &spl_token_2022::ID);
+    let target = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(target));
+
+    // Check the history
+    let (history_pda, _history_bump) = ReleaseHistoryPda::get_address(user, &PROGRAM_ID);
+    let history: ReleaseHistoryPda = env
+        .from_account(&history_pda)
+        .await
+        .ok_or("could not load the release history PDA")?;
+    assert_eq!(history.user, user);
+    assert_eq!(
+        history
+            .records
+            .iter()
+            .map(|record| record.amount)
+            .collect::<Vec<_>>(),
+        vec![PARTIAL_AMOUNT, target - PARTIAL_AMOUNT]
+    );
+    for record in &history.records {
+        assert_eq!(record.entries.len(), 1);
+        assert_eq!(record.entries[0].index, 0);
+        assert_eq!(record.entries[0].kind, INVEST_TYPE);
+        assert_eq!(record.entries[0].amount, record.amount);
+    }
+
+    let year = chrono::Utc::now().year();
+    let statement = YearlyStatement::new(&history.records, year);
+    assert_eq!(statement.total, target);
+    assert_eq!(statement.by_kind, vec![(INVEST_TYPE, target)]);
+    assert_eq!(history.yearly_statement(year), statement);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn batch_with_invalid_history() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = env.add_wallet("User").await;
+    let other = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    add_investment(&mut env, &other, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let instruction = enable_release_history(&user, &user)?;
+    env.execute_transaction(&[instruction], &["User"]).await?;
+
+    // The other user's entry points to the history of the first one
+    let (history_pda, _history_bump) = ReleaseHistoryPda::get_address(user, &PROGRAM_ID);
+    let mut instructions = batch_vesting_release(&api, &[user, other])?;
+    for batch in &mut instructions {
+        if let Some(history) = batch
+            .accounts
+            .get_mut(8 + 2 * BATCH_RELEASE_ACCOUNTS_PER_USER - 1)
+        {
+            history.pubkey = history_pda;
+        }
+    }
+    env.execute_transaction_custom_budget(&instructions, &["API"], BATCH_BUDGET)
+        .await?;
+
+    // The first user got their tokens, the other one was skipped without any change
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let user_ata =
+        get_associated_token_address_with_program_id(&user, &mint_address, &spl_token_2022::ID);
+    let other_ata =
+        get_associated_token_address_with_program_id(&other, &mint_address, &spl_token_2022::ID);
+    let target = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(target));
+    assert_eq!(env.get_token_amount(&other_ata).await, None);
+
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(other, &PROGRAM_ID);
+    let investment: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert!(investment
+        .investment
+        .investments
+        .iter()
+        .all(|invest| invest.amount_released == 0));
+
+    let history: ReleaseHistoryPda = env
+        .from_account(&history_pda)
+        .await
+        .ok_or("could not load the release history PDA")?;
+    assert_eq!(history.records.len(), 1);
+
+    Ok(())
+}
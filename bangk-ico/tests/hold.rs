@@ -0,0 +1,186 @@
+// File: bangk-ico/tests/hold.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:45:31
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::{error, result};
+
+use bangk_ico::{
+    admin_clear_compliance_hold, admin_set_compliance_hold, claim_vested, clear_compliance_hold,
+    set_compliance_hold, vesting_release, ComplianceHold, UnvestingType, UserInvestmentPda,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, get_unvesting_def, launch_tokens, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+const INVEST_TYPE: UnvestingType = UnvestingType::TeamFounders;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const WEEK: i64 = 7 * 86_400;
+const REASON: u16 = 42;
+
+#[tokio::test]
+async fn invalid_hold() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let compliance = env.wallets["Compliance"].pubkey();
+    let user = env.add_wallet("User").await;
+    let expiry = chrono::Utc::now().timestamp() + WEEK;
+
+    // There is nothing to hold
+    let instruction1 = set_compliance_hold(&compliance, &user, REASON, expiry)?;
+    let res1 = env
+        .execute_transaction(&[instruction1], &["Compliance"])
+        .await;
+    assert!(
+        res1.is_err_and(|err| err == BangkError::InvestmentDoesNotExist),
+        "there was an unexpected error in the instruction"
+    );
+
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+
+    // Only compliance or the admins can put a hold
+    let instruction2 = set_compliance_hold(&user, &user, REASON, expiry)?;
+    let res2 = env.execute_transaction(&[instruction2], &["User"]).await;
+    assert!(
+        res2.is_err_and(|err| err == BangkError::InvalidSigner),
+        "the user put a hold on their own releases"
+    );
+
+    // A hold must expire in the future
+    let past = chrono::Utc::now().timestamp() - 1;
+    let instruction3 = set_compliance_hold(&compliance, &user, REASON, past)?;
+    let res3 = env
+        .execute_transaction(&[instruction3], &["Compliance"])
+        .await;
+    assert!(
+        res3.is_err_and(|err| err == BangkError::InvalidOperation),
+        "an expired hold was set"
+    );
+
+    // There is no hold to clear
+    let instruction4 = clear_compliance_hold(&compliance, &user)?;
+    let res4 = env
+        .execute_transaction(&[instruction4], &["Compliance"])
+        .await;
+    assert!(
+        res4.is_err_and(|err| err == BangkError::InvalidOperation),
+        "there was an unexpected error in the instruction"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn releases_on_hold() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let compliance = env.wallets["Compliance"].pubkey();
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let user_ata =
+        get_associated_token_address_with_program_id(&user, &mint_address, &spl_token_2022::ID);
+
+    let expiry = chrono::Utc::now().timestamp() + WEEK;
+    let instruction1 = set_compliance_hold(&compliance, &user, REASON, expiry)?;
+    env.execute_transaction(&[instruction1], &["Compliance"])
+        .await?;
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert_eq!(
+        pda.hold,
+        Some(ComplianceHold {
+            reason: REASON,
+            expiry
+        })
+    );
+
+    // Neither Bangk nor the user can release the tokens
+    let instruction2 = vesting_release(&api, &user)?;
+    let res2 = env.execute_transaction(&[instruction2], &["API"]).await;
+    assert!(
+        res2.is_err_and(|err| err == BangkError::ReleasesOnHold),
+        "tokens were released during a hold"
+    );
+    let instruction3 = claim_vested(&user, &user)?;
+    let res3 = env.execute_transaction(&[instruction3], &["User"]).await;
+    assert!(
+        res3.is_err_and(|err| err == BangkError::ReleasesOnHold),
+        "tokens were claimed during a hold"
+    );
+
+    // Everything vested in the meantime is released once the hold is lifted
+    let instruction4 = clear_compliance_hold(&compliance, &user)?;
+    env.execute_transaction(&[instruction4], &["Compliance"])
+        .await?;
+    let instruction5 = vesting_release(&api, &user)?;
+    env.execute_transaction(&[instruction5], &["API"]).await?;
+    let released = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    assert_eq!(env.get_token_amount(&user_ata).await, Some(released));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn admin_hold() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let user = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let expiry = chrono::Utc::now().timestamp() + WEEK;
+
+    // A Sensitive operation needs two admins
+    let mut instruction1 = admin_set_compliance_hold(&api, &admin2, &user, REASON, expiry)?;
+    instruction1.accounts.pop();
+    let res1 = env.execute_transaction(&[instruction1], &["API"]).await;
+    assert!(
+        res1.is_err_and(|err| err == BangkError::InvalidSigner),
+        "a hold was set by a single admin"
+    );
+
+    let instruction2 = admin_set_compliance_hold(&api, &admin2, &user, REASON, expiry)?;
+    env.execute_transaction(&[instruction2], &["API", "Admin 2"])
+        .await?;
+    let instruction3 = admin_clear_compliance_hold(&api, &admin2, &user)?;
+    env.execute_transaction(&[instruction3], &["API", "Admin 2"])
+        .await?;
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert!(pda.hold.is_none());
+
+    Ok(())
+}
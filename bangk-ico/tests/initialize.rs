@@ -3,7 +3,7 @@
 // Creation date: Thursday 13 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Wednesday 21 August 2024 @ 19:33:07
+// Last modified: Sunday 18 October 2026 @ 16:57:25
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -19,7 +19,7 @@ use std::{error, result};
 
 pub mod common;
 
-use bangk_ico::{initialize, process_instruction, ConfigurationPda};
+use bangk_ico::{initialize, migrate_configuration, process_instruction, ConfigurationPda};
 use bangk_onchain_common::{
     security::{MultiSigPda, MultiSigType},
     Error as BangkError,
@@ -145,6 +145,21 @@ async fn double_init() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn migrate_current_configuration() -> Result<()> {
+    let mut env = init_default().await?;
+    let api = env.wallets["API"].pubkey();
+
+    let instruction = migrate_configuration(&api, &[])?;
+    let res = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::UniqueOperationAlreadyExecuted),
+        "the configuration shouldn't be migrated twice"
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn duplicated_key_in_multisig() -> Result<()> {
     let mut env = Environment::new(PROGRAM_ID, "bangk_ico", processor!(process_instruction)).await;
@@ -3,7 +3,7 @@
 // Creation date: Monday 17 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Thursday 22 August 2024 @ 13:08:32
+// Last modified: Sunday 18 October 2026 @ 17:43:53
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -17,9 +17,11 @@ type Result<T> = result::Result<T, Error>;
 use std::{error, result, thread::sleep, time::Duration};
 
 use bangk_ico::{
-    process_adviser_post_launch_investment, queue_adviser_post_launch_investment, user_investment,
-    BangkIcoInstruction, ConfigurationPda, TimelockPda, UnvestingScheme, UnvestingType,
-    UserInvestmentArgs, UserInvestmentPda, TIMELOCK_DELAY,
+    migrate_user_investment, process_adviser_post_launch_investment,
+    queue_adviser_post_launch_investment, user_investment, user_investment_with_args,
+    user_investment_with_reference, BangkIcoInstruction, ConfigurationPda, PaymentMetadata,
+    TimelockPda, UnvestingScheme, UnvestingType, UserInvestmentArgs, UserInvestmentPda,
+    TIMELOCK_DELAY,
 };
 use bangk_onchain_common::{
     pda::PdaType,
@@ -47,6 +49,7 @@ async fn default() -> Result<()> {
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     let instruction = user_investment(
         &api,
@@ -76,12 +79,27 @@ async fn default() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn migrate_missing_investment() -> Result<()> {
+    let mut env = common::init_default().await?;
+
+    let api = env.wallets["API"].pubkey();
+    let user = Pubkey::new_unique();
+
+    let migrate = migrate_user_investment(&api, &user)?;
+    let res = env.execute_transaction(&[migrate], &["API"]).await;
+    assert!(res.is_err_and(|err| err == BangkError::InvestmentDoesNotExist));
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn add_investment() -> Result<()> {
     let mut env = common::init_default().await?;
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     let instruction = user_investment(
         &api,
@@ -124,6 +142,7 @@ async fn two_investments1() -> Result<()> {
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     let instruction1 = user_investment(
         &api,
@@ -174,6 +193,7 @@ async fn two_investments2() -> Result<()> {
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     let instruction1 = user_investment(
         &api,
@@ -224,6 +244,7 @@ async fn custom_scheme() -> Result<()> {
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     let custom_scheme = UnvestingScheme {
         kind: UnvestingType::AdvisersPartners,
@@ -271,6 +292,7 @@ async fn invalid_custom_scheme() -> Result<()> {
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     let custom_scheme1 = UnvestingScheme {
         kind: UnvestingType::AdvisersPartners,
@@ -325,6 +347,7 @@ async fn too_many() -> Result<()> {
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     let instruction = user_investment(&api, &user, UnvestingType::TeamFounders, None, TOO_MANY)?;
     let res = env.execute_transaction(&[instruction], &["API"]).await;
@@ -337,6 +360,121 @@ async fn too_many() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn idempotency_key() -> Result<()> {
+    let mut env = common::init_default().await?;
+
+    let api = env.wallets["API"].pubkey();
+    let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
+
+    let instruction = user_investment_with_reference(
+        &api,
+        &user,
+        UnvestingType::PublicSells1,
+        None,
+        INVESTED_AMOUNT,
+        [1; 32],
+    )?;
+    env.execute_transaction(&[instruction], &["API"]).await?;
+
+    // A retry with the same key is rejected (the amount differs so that the transaction does too)
+    let retry = user_investment_with_reference(
+        &api,
+        &user,
+        UnvestingType::PublicSells1,
+        None,
+        INVESTED_AMOUNT + 1,
+        [1; 32],
+    )?;
+    let res = env.execute_transaction(&[retry], &["API"]).await;
+    assert!(res.is_err_and(|err| err == BangkError::DuplicateInvestmentReference));
+
+    let other = user_investment_with_reference(
+        &api,
+        &user,
+        UnvestingType::PublicSells1,
+        None,
+        INVESTED_AMOUNT,
+        [2; 32],
+    )?;
+    env.execute_transaction(&[other], &["API"]).await?;
+
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    let references: Vec<Option<[u8; 32]>> = pda
+        .investment
+        .investments
+        .iter()
+        .map(|invest| invest.reference)
+        .collect();
+    assert_eq!(references, vec![Some([1; 32]), Some([2; 32])]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn payment_metadata() -> Result<()> {
+    let mut env = common::init_default().await?;
+
+    let api = env.wallets["API"].pubkey();
+    let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
+
+    let payment = PaymentMetadata {
+        currency: *b"EUR",
+        unit_price: 50_000,
+        total_paid: 50_000_000,
+        reference_hash: [7; 32],
+    };
+    let args = UserInvestmentArgs {
+        user,
+        invest_kind: UnvestingType::PublicSells1,
+        custom_rule: None,
+        amount: INVESTED_AMOUNT,
+        referrer: None,
+        reference: None,
+        payment: Some(payment),
+        revocable: false,
+    };
+    let invalid = user_investment_with_args(
+        &api,
+        UserInvestmentArgs {
+            payment: Some(PaymentMetadata {
+                currency: *b"eu1",
+                ..payment
+            }),
+            ..args
+        },
+    )?;
+    let res = env.execute_transaction(&[invalid], &["API"]).await;
+    assert!(res.is_err_and(|err| err == BangkError::InvalidOperation));
+
+    let instruction = user_investment_with_args(&api, args)?;
+    env.execute_transaction(&[instruction], &["API"]).await?;
+
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert!(pda
+        .investment
+        .investments
+        .first()
+        .is_some_and(|invest| invest.payment == Some(payment)));
+
+    // Accounts already using the current layout can't be migrated
+    let migrate = migrate_user_investment(&api, &user)?;
+    let migrated = env.execute_transaction(&[migrate], &["API"]).await;
+    assert!(migrated.is_err_and(|err| err == BangkError::UniqueOperationAlreadyExecuted));
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn post_launch_advisers_investment() -> Result<()> {
     let mut env = common::init_with_mint().await?;
@@ -346,6 +484,7 @@ async fn post_launch_advisers_investment() -> Result<()> {
     let admin2 = env.wallets["Admin 2"].pubkey();
     let admin3 = env.wallets["Admin 3"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     launch_tokens(&mut env, chrono::Utc::now().timestamp() - 4 * 7 * 24 * 3600).await?;
 
@@ -393,6 +532,7 @@ async fn post_launch_advisers_investment_before_launch() -> Result<()> {
     let admin2 = env.wallets["Admin 2"].pubkey();
     let admin3 = env.wallets["Admin 3"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     let instruction = queue_adviser_post_launch_investment(
         &admin1,
@@ -441,6 +581,10 @@ pub fn custom_non_adviser_post_launch(
                 invest_kind: UnvestingType::TeamFounders,
                 custom_rule,
                 amount,
+                referrer: None,
+                reference: None,
+                payment: None,
+                revocable: false,
             },
         ))?,
     })
@@ -454,6 +598,7 @@ async fn post_launch_advisers_investment_non_adviser() -> Result<()> {
     let admin2 = env.wallets["Admin 2"].pubkey();
     let admin3 = env.wallets["Admin 3"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     launch_tokens(&mut env, chrono::Utc::now().timestamp() - 4 * 7 * 24 * 3600).await?;
 
@@ -473,6 +618,7 @@ async fn add_investment_post_launch() -> Result<()> {
 
     let api = env.wallets["API"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     let instruction1 = user_investment(
         &api,
@@ -508,6 +654,7 @@ async fn post_launch_advisers_investment_too_many() -> Result<()> {
     let admin2 = env.wallets["Admin 2"].pubkey();
     let admin3 = env.wallets["Admin 3"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     launch_tokens(&mut env, chrono::Utc::now().timestamp() - 4 * 7 * 24 * 3600).await?;
 
@@ -534,6 +681,7 @@ async fn post_launch_advisers_investment_double() -> Result<()> {
     let admin2 = env.wallets["Admin 2"].pubkey();
     let admin3 = env.wallets["Admin 3"].pubkey();
     let user = Pubkey::new_unique();
+    common::issue_kyc(&mut env, &user).await?;
 
     let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
 
@@ -0,0 +1,208 @@
+// File: bangk-ico/tests/kyc.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 16:21:26
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+use std::{error, result};
+
+use bangk_ico::{
+    issue_kyc_attestation, revoke_kyc_attestation, set_compliance_multisig, user_investment,
+    vesting_release, KycAttestationPda, UnvestingType,
+};
+use bangk_onchain_common::{
+    pda::PdaType,
+    security::{MultiSigPda, MultiSigType},
+    Error as BangkError,
+};
+use common::{add_investment, issue_kyc, launch_tokens, KYC_VALIDITY, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer as _};
+
+pub mod common;
+
+const INVESTED_AMOUNT: u64 = 1_000_000_000;
+
+#[tokio::test]
+async fn issue_and_revoke() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let compliance = env.wallets["Compliance"].pubkey();
+    let user = Pubkey::new_unique();
+    let (kyc_pda, _kyc_bump) = KycAttestationPda::get_address(user, &PROGRAM_ID);
+    let expiry = chrono::Utc::now().timestamp() + KYC_VALIDITY;
+
+    let instruction = issue_kyc_attestation(&compliance, &user, 2, expiry, *b"DE")?;
+    env.execute_transaction(&[instruction], &["Compliance"])
+        .await?;
+
+    let attestation: KycAttestationPda = env
+        .from_account(&kyc_pda)
+        .await
+        .ok_or("could not load the KYC attestation")?;
+    assert_eq!(attestation.pda_type, PdaType::KycAttestation);
+    assert_eq!(attestation.user, user);
+    assert_eq!(attestation.level, 2);
+    assert_eq!(attestation.expiry, expiry);
+    assert_eq!(&attestation.jurisdiction, b"DE");
+
+    let revoke = revoke_kyc_attestation(&compliance, &user)?;
+    env.execute_transaction(&[revoke], &["Compliance"]).await?;
+    assert!(env.get_account(&kyc_pda).await.is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn investment_without_attestation() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = Pubkey::new_unique();
+
+    let instruction = user_investment(
+        &api,
+        &user,
+        UnvestingType::TeamFounders,
+        None,
+        INVESTED_AMOUNT,
+    )?;
+    let res = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::MissingKycAttestation),
+        "an investment was made without KYC attestation"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn compliance_multisig_is_not_admin() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let compliance = env.wallets["Compliance"].pubkey();
+    let user = Pubkey::new_unique();
+    issue_kyc(&mut env, &user).await?;
+
+    // The compliance keys can't stand in for the admin ones
+    let (compliance_pda, _compliance_bump) =
+        MultiSigPda::get_address(MultiSigType::Compliance, &PROGRAM_ID);
+    let mut instruction = user_investment(
+        &compliance,
+        &user,
+        UnvestingType::TeamFounders,
+        None,
+        INVESTED_AMOUNT,
+    )?;
+    instruction.accounts[2].pubkey = compliance_pda;
+    let res = env
+        .execute_transaction(&[instruction], &["Compliance"])
+        .await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidPdaAddress),
+        "the compliance MultiSig signed an admin instruction"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn release_after_revocation() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let compliance = env.wallets["Compliance"].pubkey();
+    let user = Pubkey::new_unique();
+    add_investment(
+        &mut env,
+        &user,
+        INVESTED_AMOUNT,
+        UnvestingType::PublicSells1,
+        None,
+    )
+    .await?;
+    launch_tokens(&mut env, chrono::Utc::now().timestamp() - 3 * 7 * 86_400).await?;
+
+    let revoke = revoke_kyc_attestation(&compliance, &user)?;
+    env.execute_transaction(&[revoke], &["Compliance"]).await?;
+
+    let instruction = vesting_release(&api, &user)?;
+    let res = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::MissingKycAttestation),
+        "tokens were released without KYC attestation"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_attestations() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let compliance = env.wallets["Compliance"].pubkey();
+    let user = Pubkey::new_unique();
+    let now = chrono::Utc::now().timestamp();
+
+    let invalid = [
+        (0, now + KYC_VALIDITY, *b"FR"),
+        (1, now - 1, *b"FR"),
+        (1, now + KYC_VALIDITY, *b"fr"),
+    ];
+    for (level, expiry, jurisdiction) in invalid {
+        let instruction = issue_kyc_attestation(&compliance, &user, level, expiry, jurisdiction)?;
+        let res = env
+            .execute_transaction(&[instruction], &["Compliance"])
+            .await;
+        assert!(
+            res.is_err_and(|err| err == BangkError::InvalidKycAttestation),
+            "an invalid KYC attestation was issued"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unauthorized_issuer() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = Pubkey::new_unique();
+    let expiry = chrono::Utc::now().timestamp() + KYC_VALIDITY;
+
+    // The admin keys are not part of the compliance multisig
+    let instruction = issue_kyc_attestation(&api, &user, 1, expiry, *b"FR")?;
+    let res = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidSigner),
+        "a KYC attestation was issued by an unauthorized key"
+    );
+
+    // Nor are the previous compliance keys once replaced
+    issue_kyc(&mut env, &user).await?;
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let new_compliance = env.add_wallet("Compliance 2").await;
+    let update = set_compliance_multisig(&admin1, &admin2, &admin3, vec![new_compliance])?;
+    env.execute_transaction(&[update], &["Admin 1", "Admin 2", "Admin 3"])
+        .await?;
+
+    let compliance = env.wallets["Compliance"].pubkey();
+    let revoke = revoke_kyc_attestation(&compliance, &user)?;
+    let revoked = env.execute_transaction(&[revoke], &["Compliance"]).await;
+    assert!(
+        revoked.is_err_and(|err| err == BangkError::InvalidSigner),
+        "a KYC attestation was revoked by a former compliance key"
+    );
+    let authorized = revoke_kyc_attestation(&new_compliance, &user)?;
+    env.execute_transaction(&[authorized], &["Compliance 2"])
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+// File: bangk-ico/tests/leaver.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:44:32
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::{error, result};
+
+use bangk_ico::{
+    apply_leaver_event, revocable_user_investment, vesting_release, LeaverPolicy, UnvestingType,
+    UserInvestmentPda, WalletType,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, get_unvesting_def, issue_kyc, launch_tokens, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+const INVEST_TYPE: UnvestingType = UnvestingType::TeamFounders;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const WEEK: i64 = 7 * 86_400;
+
+#[tokio::test]
+async fn irrevocable_investment() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(&mut env, chrono::Utc::now().timestamp() - WEEK).await?;
+
+    let now = chrono::Utc::now().timestamp();
+    let instruction1 =
+        apply_leaver_event(&api, &admin2, &admin2, &user, now, LeaverPolicy::Forfeit)?;
+    let res1 = env
+        .execute_transaction(&[instruction1], &["API", "Admin 2"])
+        .await;
+    assert!(
+        res1.is_err_and(|err| err == BangkError::InvalidSigner),
+        "a leaver event was applied without enough signers"
+    );
+
+    let instruction2 =
+        apply_leaver_event(&api, &admin2, &admin4, &user, now, LeaverPolicy::Forfeit)?;
+    let res2 = env
+        .execute_transaction(&[instruction2], &["API", "Admin 2", "Admin 4"])
+        .await;
+    assert!(
+        res2.is_err_and(|err| err == BangkError::InvalidOperation),
+        "an irrevocable investment was revoked"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn revocable_team_only() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = Pubkey::new_unique();
+    issue_kyc(&mut env, &user).await?;
+
+    let grant = revocable_user_investment(
+        &api,
+        &user,
+        UnvestingType::PrivateSells,
+        None,
+        INVESTED_AMOUNT,
+    )?;
+    let res = env.execute_transaction(&[grant], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidOperation),
+        "a private sale investment was made revocable"
+    );
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    assert!(env
+        .from_account::<UserInvestmentPda>(&investment_pda)
+        .await
+        .is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn good_leaver() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = Pubkey::new_unique();
+    issue_kyc(&mut env, &user).await?;
+    let grant = revocable_user_investment(&api, &user, INVEST_TYPE, None, INVESTED_AMOUNT)?;
+    env.execute_transaction(&[grant], &["API"]).await?;
+    add_investment(
+        &mut env,
+        &user,
+        INVESTED_AMOUNT,
+        UnvestingType::PrivateSells,
+        None,
+    )
+    .await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let teams_pda = WalletType::TeamsAdvisers.get_pda().0;
+    let teams_before = env.get_token_amount(&teams_pda).await.unwrap_or_default();
+
+    let instruction = apply_leaver_event(
+        &api,
+        &admin2,
+        &admin4,
+        &user,
+        chrono::Utc::now().timestamp(),
+        LeaverPolicy::KeepVested,
+    )?;
+    env.execute_transaction(&[instruction], &["API", "Admin 2", "Admin 4"])
+        .await?;
+
+    // Only the vested part of the revocable investment is kept
+    let kept = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    let revoked = pda.investment.investments[0];
+    assert_eq!(revoked.amount_bought, kept);
+    assert!(revoked.fully_vested, "the vesting should be frozen");
+    let other = pda.investment.investments[1];
+    assert_eq!(other.amount_bought, INVESTED_AMOUNT);
+    assert!(
+        !other.fully_vested,
+        "the irrevocable investment was changed"
+    );
+    assert_eq!(
+        env.get_token_amount(&teams_pda).await,
+        Some(teams_before + INVESTED_AMOUNT - kept)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn bad_leaver() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = Pubkey::new_unique();
+    issue_kyc(&mut env, &user).await?;
+    let grant = revocable_user_investment(&api, &user, INVEST_TYPE, None, INVESTED_AMOUNT)?;
+    env.execute_transaction(&[grant], &["API"]).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let release = vesting_release(&api, &user)?;
+    env.execute_transaction(&[release], &["API"]).await?;
+    let released = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+
+    let instruction = apply_leaver_event(
+        &api,
+        &admin2,
+        &admin4,
+        &user,
+        chrono::Utc::now().timestamp(),
+        LeaverPolicy::Forfeit,
+    )?;
+    env.execute_transaction(&[instruction], &["API", "Admin 2", "Admin 4"])
+        .await?;
+
+    // What was already released can't be taken back
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert_eq!(pda.investment.investments[0].amount_bought, released);
+    assert!(pda.is_fully_released());
+
+    Ok(())
+}
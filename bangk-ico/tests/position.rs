@@ -0,0 +1,459 @@
+// File: bangk-ico/tests/position.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 18:08:28
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+use std::{error, result};
+
+use bangk_ico::{
+    accelerate_positions, claim_position, claim_position_partially, clear_compliance_hold,
+    close_user_investment, enable_release_history, execute_clawback,
+    execute_clawback_with_positions, get_position_mint_pda, queue_clawback,
+    revocable_user_investment, set_compliance_hold, set_position_transfer_policy,
+    tokenize_position, transfer_position, Acceleration, PositionTransferPolicy, ReleaseHistoryPda,
+    UnvestingType, UserInvestmentPda, VestingPositionPda, WalletType, TIMELOCK_DELAY,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, get_unvesting_def, issue_kyc, launch_tokens, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+const INVEST_TYPE: UnvestingType = UnvestingType::PrivateSells;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const WEEK: i64 = 7 * 86_400;
+
+fn get_ata(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, &spl_token_2022::ID)
+}
+
+#[tokio::test]
+async fn invalid_tokenization() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = env.add_wallet("User").await;
+    issue_kyc(&mut env, &user).await?;
+    let grant = revocable_user_investment(
+        &api,
+        &user,
+        UnvestingType::TeamFounders,
+        None,
+        INVESTED_AMOUNT,
+    )?;
+    env.execute_transaction(&[grant], &["API"]).await?;
+
+    let instruction1 = tokenize_position(&api, &user, 1, 0)?;
+    let res1 = env
+        .execute_transaction(&[instruction1], &["API", "User"])
+        .await;
+    assert!(
+        res1.is_err_and(|err| err == BangkError::InvestmentDoesNotExist),
+        "a missing investment was tokenised"
+    );
+
+    let instruction2 = tokenize_position(&api, &user, 0, 0)?;
+    let res2 = env
+        .execute_transaction(&[instruction2], &["API", "User"])
+        .await;
+    assert!(
+        res2.is_err_and(|err| err == BangkError::InvalidOperation),
+        "a revocable investment was tokenised"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn tokenize_and_claim() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+
+    let instruction = tokenize_position(&api, &user, 0, 0)?;
+    env.execute_transaction(&[instruction], &["API", "User"])
+        .await?;
+
+    // The investment was moved to the position, and the user holds its NFT
+    let (position_mint, _position_mint_bump) = get_position_mint_pda(&user, 0);
+    let (position_pda, _position_bump) =
+        VestingPositionPda::get_address(position_mint, &PROGRAM_ID);
+    let position: VestingPositionPda = env
+        .from_account(&position_pda)
+        .await
+        .ok_or("could not load the position PDA")?;
+    assert_eq!(position.user, user);
+    assert_eq!(position.investment.amount_bought, INVESTED_AMOUNT);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let pda: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert!(pda.investment.investments.is_empty());
+    assert_eq!(
+        env.get_token_amount(&get_ata(&user, &position_mint)).await,
+        Some(1)
+    );
+
+    // The holder claims the vested tokens
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let instruction1 = enable_release_history(&user, &user)?;
+    env.execute_transaction(&[instruction1], &["User"]).await?;
+    let instruction2 = claim_position_partially(&user, &user, &user, &position_mint, 1_000)?;
+    env.execute_transaction(&[instruction2], &["User"]).await?;
+    let instruction3 = claim_position(&user, &user, &user, &position_mint)?;
+    env.execute_transaction(&[instruction3], &["User"]).await?;
+
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let target = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    assert_eq!(
+        env.get_token_amount(&get_ata(&user, &mint_address)).await,
+        Some(target)
+    );
+
+    // The releases are kept in the history of the holder
+    let (history_pda, _history_bump) = ReleaseHistoryPda::get_address(user, &PROGRAM_ID);
+    let history: ReleaseHistoryPda = env
+        .from_account(&history_pda)
+        .await
+        .ok_or("could not load the release history PDA")?;
+    assert_eq!(
+        history
+            .records
+            .iter()
+            .map(|record| (record.amount, record.position))
+            .collect::<Vec<_>>(),
+        vec![
+            (1_000, Some(position_mint)),
+            (target - 1_000, Some(position_mint))
+        ]
+    );
+
+    // The same position can't be tokenised twice
+    let instruction4 = tokenize_position(&api, &user, 0, 0)?;
+    let res = env
+        .execute_transaction(&[instruction4], &["API", "User"])
+        .await;
+    assert!(
+        res.is_err(),
+        "a position was tokenised with an existing identifier"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn restricted_transfers() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = env.add_wallet("User").await;
+    let recipient = env.add_wallet("Recipient").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let instruction = tokenize_position(&api, &user, 0, 0)?;
+    env.execute_transaction(&[instruction], &["API", "User"])
+        .await?;
+    let (position_mint, _position_mint_bump) = get_position_mint_pda(&user, 0);
+
+    // Transfers are disabled by default
+    let instruction1 = transfer_position(&user, &user, &recipient, &user, &position_mint)?;
+    let res1 = env.execute_transaction(&[instruction1], &["User"]).await;
+    assert!(
+        res1.is_err_and(|err| err == BangkError::PositionNotTransferable),
+        "a position was transferred without a transfer policy"
+    );
+
+    // The position must be held long enough
+    let locked = PositionTransferPolicy {
+        lockup: WEEK,
+        require_kyc: true,
+    };
+    let instruction2 = set_position_transfer_policy(&api, &admin2, &admin4, Some(locked))?;
+    env.execute_transaction(&[instruction2], &["API", "Admin 2", "Admin 4"])
+        .await?;
+    let instruction3 = transfer_position(&user, &user, &recipient, &user, &position_mint)?;
+    let res3 = env.execute_transaction(&[instruction3], &["User"]).await;
+    assert!(
+        res3.is_err_and(|err| err == BangkError::PositionNotTransferable),
+        "a position was transferred during its lockup"
+    );
+
+    // The recipient must have passed the KYC procedure
+    let policy = PositionTransferPolicy {
+        lockup: 0,
+        require_kyc: true,
+    };
+    let instruction4 = set_position_transfer_policy(&api, &admin2, &admin4, Some(policy))?;
+    env.execute_transaction(&[instruction4], &["API", "Admin 2", "Admin 4"])
+        .await?;
+    let instruction5 = transfer_position(&user, &user, &recipient, &user, &position_mint)?;
+    let res5 = env.execute_transaction(&[instruction5], &["User"]).await;
+    assert!(
+        res5.is_err_and(|err| err == BangkError::MissingKycAttestation),
+        "a position was transferred to a wallet without KYC"
+    );
+
+    issue_kyc(&mut env, &recipient).await?;
+    let instruction6 = transfer_position(&api, &user, &recipient, &user, &position_mint)?;
+    env.execute_transaction(&[instruction6], &["API", "User"])
+        .await?;
+    assert_eq!(
+        env.get_token_amount(&get_ata(&user, &position_mint)).await,
+        Some(0)
+    );
+    assert_eq!(
+        env.get_token_amount(&get_ata(&recipient, &position_mint))
+            .await,
+        Some(1)
+    );
+
+    // Only the new holder can claim the tokens
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let instruction7 = claim_position(&user, &user, &user, &position_mint)?;
+    let res7 = env.execute_transaction(&[instruction7], &["User"]).await;
+    assert!(
+        res7.is_err_and(|err| err == BangkError::AccountOwnerMismatch),
+        "the former holder claimed the tokens of the position"
+    );
+    let instruction8 = claim_position(&recipient, &recipient, &user, &position_mint)?;
+    env.execute_transaction(&[instruction8], &["Recipient"])
+        .await?;
+
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let target = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    assert_eq!(
+        env.get_token_amount(&get_ata(&recipient, &mint_address))
+            .await,
+        Some(target)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn hold_follows_position() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let compliance = env.wallets["Compliance"].pubkey();
+    let user = env.add_wallet("User").await;
+    let recipient = env.add_wallet("Recipient").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    add_investment(&mut env, &recipient, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let instruction = tokenize_position(&api, &user, 0, 0)?;
+    env.execute_transaction(&[instruction], &["API", "User"])
+        .await?;
+    let (position_mint, _position_mint_bump) = get_position_mint_pda(&user, 0);
+    let policy = PositionTransferPolicy {
+        lockup: 0,
+        require_kyc: false,
+    };
+    let set_policy = set_position_transfer_policy(&api, &admin2, &admin4, Some(policy))?;
+    env.execute_transaction(&[set_policy], &["API", "Admin 2", "Admin 4"])
+        .await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    // The user is put on hold after the tokenisation of their investment
+    let expiry = chrono::Utc::now().timestamp() + WEEK;
+    let instruction1 = set_compliance_hold(&compliance, &user, 42, expiry)?;
+    env.execute_transaction(&[instruction1], &["Compliance"])
+        .await?;
+
+    let instruction2 = claim_position(&user, &user, &user, &position_mint)?;
+    let res2 = env.execute_transaction(&[instruction2], &["User"]).await;
+    assert!(
+        res2.is_err_and(|err| err == BangkError::ReleasesOnHold),
+        "a position was claimed during a hold"
+    );
+    let instruction3 = transfer_position(&user, &user, &recipient, &user, &position_mint)?;
+    let res3 = env.execute_transaction(&[instruction3], &["User"]).await;
+    assert!(
+        res3.is_err_and(|err| err == BangkError::ReleasesOnHold),
+        "a position was transferred during a hold"
+    );
+    assert_eq!(
+        env.get_token_amount(&get_ata(&user, &position_mint)).await,
+        Some(1)
+    );
+
+    // Another investment PDA can't be given to dodge the hold
+    let instruction4 = claim_position(&user, &user, &recipient, &position_mint)?;
+    let res4 = env.execute_transaction(&[instruction4], &["User"]).await;
+    assert!(
+        res4.is_err_and(|err| err == BangkError::InvalidPdaAddress),
+        "the hold of the position was ignored"
+    );
+
+    // Once the hold is lifted, the position can be claimed
+    let instruction5 = clear_compliance_hold(&compliance, &user)?;
+    env.execute_transaction(&[instruction5], &["Compliance"])
+        .await?;
+    let instruction6 = claim_position(&user, &user, &user, &position_mint)?;
+    env.execute_transaction(&[instruction6], &["User"]).await?;
+
+    let (mint_address, _mint_bump) = Pubkey::find_program_address(&[b"Mint", b"BGK"], &PROGRAM_ID);
+    let target = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+    assert_eq!(
+        env.get_token_amount(&get_ata(&user, &mint_address)).await,
+        Some(target)
+    );
+
+    // The account of the user keeps the hold as long as the position isn't fully released
+    let instruction7 = close_user_investment(&api, &user, &api)?;
+    let res7 = env.execute_transaction(&[instruction7], &["API"]).await;
+    assert!(
+        res7.is_err_and(|err| err == BangkError::InvestmentNotFullyReleased),
+        "the investments of a user with an outstanding position were closed"
+    );
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    assert!(env
+        .from_account::<UserInvestmentPda>(&investment_pda)
+        .await
+        .is_some_and(|pda| pda.positions == 1));
+
+    // The hold of the holder also applies
+    let instruction8 = transfer_position(&user, &user, &recipient, &user, &position_mint)?;
+    env.execute_transaction(&[instruction8], &["User"]).await?;
+    let instruction9 = set_compliance_hold(&compliance, &recipient, 43, expiry)?;
+    env.execute_transaction(&[instruction9], &["Compliance"])
+        .await?;
+    let instruction10 = claim_position(&recipient, &recipient, &user, &position_mint)?;
+    let res10 = env
+        .execute_transaction(&[instruction10], &["Recipient"])
+        .await;
+    assert!(
+        res10.is_err_and(|err| err == BangkError::ReleasesOnHold),
+        "a position was claimed during a hold of its holder"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn positions_follow_clawback_and_acceleration() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let instruction = tokenize_position(&api, &user, 0, 0)?;
+    env.execute_transaction(&[instruction], &["API", "User"])
+        .await?;
+    let (position_mint, _position_mint_bump) = get_position_mint_pda(&user, 0);
+    let (position_pda, _position_bump) =
+        VestingPositionPda::get_address(position_mint, &PROGRAM_ID);
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+
+    // The tokenised investment can be accelerated
+    let instruction1 = accelerate_positions(
+        &api,
+        &admin2,
+        &admin4,
+        &[position_mint],
+        None,
+        Acceleration::Extra(10_000),
+    )?;
+    env.execute_transaction(&instruction1, &["API", "Admin 2", "Admin 4"])
+        .await?;
+    assert!(env
+        .from_account::<VestingPositionPda>(&position_pda)
+        .await
+        .is_some_and(|position| position.investment.accelerated == 10_000));
+
+    // It can't be left out of a clawback
+    let date = chrono::Utc::now().timestamp();
+    let instruction2 = queue_clawback(
+        &api,
+        &admin2,
+        &admin4,
+        &user,
+        None,
+        date,
+        WalletType::Reserve,
+    )?;
+    env.execute_transaction(&[instruction2], &["API", "Admin 2", "Admin 4"])
+        .await?;
+    sleep(Duration::from_secs(TIMELOCK_DELAY as u64));
+    let instruction3 = execute_clawback(&api, &user, None, date, WalletType::Reserve)?;
+    let res3 = env.execute_transaction(&[instruction3], &["API"]).await;
+    assert!(
+        res3.is_err_and(|err| err == BangkError::InvalidOperation),
+        "a vesting position escaped the clawback"
+    );
+    let instruction4 = execute_clawback_with_positions(
+        &api,
+        &user,
+        None,
+        date,
+        WalletType::Reserve,
+        &[position_mint],
+    )?;
+    env.execute_transaction(&[instruction4], &["API"]).await?;
+    assert!(env
+        .from_account::<VestingPositionPda>(&position_pda)
+        .await
+        .is_some_and(|position| position.investment.fully_vested
+            && position.investment.amount_bought < INVESTED_AMOUNT));
+
+    Ok(())
+}
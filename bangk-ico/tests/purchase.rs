@@ -0,0 +1,204 @@
+// File: bangk-ico/tests/purchase.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 13:17:15
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+use std::{error, result};
+
+use bangk_ico::{
+    get_sale_treasury_pda, initialize_sale_treasury, purchase, ConfigurationPda, UnvestingType,
+    UserInvestmentPda,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{init_stablecoin_sale, issue_kyc, PROGRAM_ID, TOTAL_ICO_TOKENS};
+use solana_program_test::tokio;
+use solana_sdk::signer::Signer as _;
+use tests_utilities::onchain::SPL_TOKEN_ID;
+
+pub mod common;
+
+// 0.05 USD per BGK
+const PRICE: u64 = 50_000;
+const AMOUNT: u64 = 1_000_000_000;
+const COST: u64 = 50_000_000;
+const FUNDS: u64 = 1_000_000_000;
+
+#[tokio::test]
+async fn purchase_with_stablecoin() -> Result<()> {
+    for token_program in &[SPL_TOKEN_ID, spl_token_2022::ID] {
+        let (mut env, mint) = init_stablecoin_sale(token_program, PRICE).await?;
+        let buyer = env.add_wallet("Buyer").await;
+        issue_kyc(&mut env, &buyer).await?;
+        let buyer_account = env
+            .mint_mock_stablecoin(&mint, token_program, &buyer, FUNDS)
+            .await;
+        let (treasury, _treasury_bump) = get_sale_treasury_pda(&mint);
+        let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+        let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(buyer, &PROGRAM_ID);
+
+        let instruction = purchase(
+            &buyer,
+            &mint,
+            token_program,
+            UnvestingType::PublicSells1,
+            AMOUNT,
+        )?;
+        env.execute_transaction(&[instruction], &["Buyer"]).await?;
+
+        assert_eq!(env.get_token_amount(&treasury).await, Some(COST));
+        assert_eq!(
+            env.get_token_amount(&buyer_account).await,
+            Some(FUNDS - COST)
+        );
+
+        let investment: UserInvestmentPda = env
+            .from_account(&investment_pda)
+            .await
+            .ok_or("could not load the user's investment")?;
+        assert_eq!(investment.investment.user, buyer);
+        assert_eq!(investment.investment.investments.len(), 1);
+        assert!(investment
+            .investment
+            .investments
+            .first()
+            .is_some_and(|invest| invest.kind == UnvestingType::PublicSells1
+                && invest.amount_bought == AMOUNT));
+
+        let config: ConfigurationPda = env
+            .from_account(&config_pda)
+            .await
+            .ok_or("could not load the configuration")?;
+        assert_eq!(config.amount_invested, AMOUNT);
+        assert_eq!(config.payment_mint, Some(mint));
+        assert_eq!(
+            config.sale_phases.first().map(|phase| phase.sold),
+            Some(AMOUNT)
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn treasury_already_initialized() -> Result<()> {
+    let (mut env, _mint) = init_stablecoin_sale(&spl_token_2022::ID, PRICE).await?;
+
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let other = env
+        .create_mock_stablecoin("Other USD", &spl_token_2022::ID, 6)
+        .await;
+    let instruction =
+        initialize_sale_treasury(&admin1, &admin2, &admin3, &other, &spl_token_2022::ID)?;
+    let res = env
+        .execute_transaction(&[instruction], &["Admin 1", "Admin 2", "Admin 3"])
+        .await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::UniqueOperationAlreadyExecuted),
+        "the sale treasury was initialized twice"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_purchases() -> Result<()> {
+    let (mut env, mint) = init_stablecoin_sale(&spl_token_2022::ID, PRICE).await?;
+    let buyer = env.add_wallet("Buyer").await;
+    issue_kyc(&mut env, &buyer).await?;
+    env.mint_mock_stablecoin(&mint, &spl_token_2022::ID, &buyer, FUNDS)
+        .await;
+    let other = env
+        .create_mock_stablecoin("Other USD", &spl_token_2022::ID, 6)
+        .await;
+    env.mint_mock_stablecoin(&other, &spl_token_2022::ID, &buyer, FUNDS)
+        .await;
+
+    let invalid = [
+        (
+            other,
+            UnvestingType::PublicSells1,
+            AMOUNT,
+            BangkError::UnknownCurrency,
+        ),
+        (
+            mint,
+            UnvestingType::TeamFounders,
+            AMOUNT,
+            BangkError::InvalidOperation,
+        ),
+        (
+            mint,
+            UnvestingType::PublicSells2,
+            AMOUNT,
+            BangkError::NoActiveSalePhase,
+        ),
+        (
+            mint,
+            UnvestingType::PublicSells1,
+            0,
+            BangkError::InvalidAmount,
+        ),
+        (
+            mint,
+            UnvestingType::PublicSells1,
+            TOTAL_ICO_TOKENS + 1,
+            BangkError::InvalidAmount,
+        ),
+    ];
+    for (currency, kind, amount, expected) in invalid {
+        let instruction = purchase(&buyer, &currency, &spl_token_2022::ID, kind, amount)?;
+        let res = env.execute_transaction(&[instruction], &["Buyer"]).await;
+        assert!(
+            res.is_err_and(|err| err == expected),
+            "an invalid purchase was accepted"
+        );
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn insufficient_funds() -> Result<()> {
+    let (mut env, mint) = init_stablecoin_sale(&spl_token_2022::ID, PRICE).await?;
+    let buyer = env.add_wallet("Buyer").await;
+    issue_kyc(&mut env, &buyer).await?;
+    let buyer_account = env
+        .mint_mock_stablecoin(&mint, &spl_token_2022::ID, &buyer, COST - 1)
+        .await;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(buyer, &PROGRAM_ID);
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+
+    let instruction = purchase(
+        &buyer,
+        &mint,
+        &spl_token_2022::ID,
+        UnvestingType::PublicSells1,
+        AMOUNT,
+    )?;
+    let res = env.execute_transaction(&[instruction], &["Buyer"]).await;
+    assert!(res.is_err(), "the purchase succeeded without enough funds");
+
+    // Nothing was recorded
+    assert!(env.get_account(&investment_pda).await.is_none());
+    assert_eq!(env.get_token_amount(&buyer_account).await, Some(COST - 1));
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration")?;
+    assert_eq!(config.amount_invested, 0);
+
+    Ok(())
+}
@@ -0,0 +1,240 @@
+// File: bangk-ico/tests/reassign.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:11:44
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+#![allow(clippy::unwrap_used)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+pub mod common;
+
+use std::thread::sleep;
+use std::time::Duration;
+use std::{error, result};
+
+use bangk_ico::{
+    claim_vested, enable_release_history, execute_reassign_investment, queue_reassign_investment,
+    vesting_release, ReleaseHistoryPda, UnvestingType, UserInvestmentPda, TIMELOCK_DELAY,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, get_unvesting_def, launch_tokens, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer};
+
+const INVEST_TYPE: UnvestingType = UnvestingType::TeamFounders;
+const INVESTED_AMOUNT: u64 = 1_000_000_000_000;
+const WEEK: i64 = 7 * 86_400;
+
+#[tokio::test]
+async fn invalid_reassignment() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = Pubkey::new_unique();
+    let new_user = Pubkey::new_unique();
+
+    // The user has no investment
+    let instruction1 = queue_reassign_investment(&api, &admin2, &admin4, &user, &new_user)?;
+    let res1 = env
+        .execute_transaction(&[instruction1], &["API", "Admin 2", "Admin 4"])
+        .await;
+    assert!(
+        res1.is_err_and(|err| err == BangkError::InvestmentDoesNotExist),
+        "investments that do not exist were reassigned"
+    );
+
+    // The investments must go to another wallet
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let instruction2 = queue_reassign_investment(&api, &admin2, &admin4, &user, &user)?;
+    let res2 = env
+        .execute_transaction(&[instruction2], &["API", "Admin 2", "Admin 4"])
+        .await;
+    assert!(
+        res2.is_err_and(|err| err == BangkError::InvalidOperation),
+        "investments were reassigned to the same wallet"
+    );
+
+    // The operation is critical
+    let instruction3 = queue_reassign_investment(&api, &admin2, &admin2, &user, &new_user)?;
+    let res3 = env
+        .execute_transaction(&[instruction3], &["API", "Admin 2"])
+        .await;
+    assert!(
+        res3.is_err_and(|err| err == BangkError::InvalidSigner),
+        "the reassignment was queued without enough signers"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reassign_to_new_wallet() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = Pubkey::new_unique();
+    let new_user = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let (new_investment_pda, _new_investment_bump) =
+        UserInvestmentPda::get_address(new_user, &PROGRAM_ID);
+
+    let instruction1 = queue_reassign_investment(&api, &admin2, &admin4, &user, &new_user)?;
+    env.execute_transaction(&[instruction1], &["API", "Admin 2", "Admin 4"])
+        .await?;
+
+    // Executing it right away fails
+    let instruction2 = execute_reassign_investment(&api, &user, &new_user, &api, &user)?;
+    let res = env.execute_transaction(&[instruction2], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::QueuedInstructionNotReady),
+        "there was an unexpected error in the instruction"
+    );
+
+    // Wait for the timeout
+    sleep(Duration::from_secs(TIMELOCK_DELAY as u64));
+    let instruction3 = execute_reassign_investment(&api, &user, &new_user, &api, &user)?;
+    env.execute_transaction(&[instruction3], &["API"]).await?;
+
+    assert!(env.get_account(&investment_pda).await.is_none());
+    let pda: UserInvestmentPda = env
+        .from_account(&new_investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert_eq!(pda.investment.user, new_user);
+    assert_eq!(pda.investment.investments.len(), 1);
+    assert_eq!(pda.investment.investments[0].amount_bought, INVESTED_AMOUNT);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn merge_with_existing_investments() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = Pubkey::new_unique();
+    let new_user = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    add_investment(&mut env, &new_user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(user, &PROGRAM_ID);
+    let (new_investment_pda, _new_investment_bump) =
+        UserInvestmentPda::get_address(new_user, &PROGRAM_ID);
+
+    // Part of the old wallet's tokens were already released
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let release = vesting_release(&api, &user)?;
+    env.execute_transaction(&[release], &["API"]).await?;
+    let released = INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000;
+
+    let instruction1 = queue_reassign_investment(&api, &admin2, &admin4, &user, &new_user)?;
+    env.execute_transaction(&[instruction1], &["API", "Admin 2", "Admin 4"])
+        .await?;
+    sleep(Duration::from_secs(TIMELOCK_DELAY as u64));
+
+    // The rent of the old record goes back to whoever paid for it
+    let instruction2 = execute_reassign_investment(&api, &user, &new_user, &user, &user)?;
+    let res = env.execute_transaction(&[instruction2], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::AccountOwnerMismatch),
+        "the rent was refunded to the wrong wallet"
+    );
+
+    let instruction3 = execute_reassign_investment(&api, &user, &new_user, &api, &user)?;
+    env.execute_transaction(&[instruction3], &["API"]).await?;
+
+    assert!(env.get_account(&investment_pda).await.is_none());
+    let pda: UserInvestmentPda = env
+        .from_account(&new_investment_pda)
+        .await
+        .ok_or("could not load the investment PDA")?;
+    assert_eq!(pda.investment.investments.len(), 2);
+    assert_eq!(pda.investment.investments[0].amount_released, 0);
+    assert_eq!(pda.investment.investments[1].amount_released, released);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn move_release_history() -> Result<()> {
+    let scheme = get_unvesting_def()
+        .iter()
+        .find(|rule| rule.kind == INVEST_TYPE)
+        .copied()
+        .unwrap();
+
+    let mut env = common::init_with_mint().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let user = env.add_wallet("User").await;
+    let new_user = Pubkey::new_unique();
+    add_investment(&mut env, &user, INVESTED_AMOUNT, INVEST_TYPE, None).await?;
+    launch_tokens(
+        &mut env,
+        chrono::Utc::now().timestamp() - i64::from(scheme.start) * WEEK - 3600,
+    )
+    .await?;
+    let (history_pda, _history_bump) = ReleaseHistoryPda::get_address(user, &PROGRAM_ID);
+    let (new_history_pda, _new_history_bump) =
+        ReleaseHistoryPda::get_address(new_user, &PROGRAM_ID);
+
+    // The old wallet has a release history
+    let instruction1 = enable_release_history(&user, &user)?;
+    env.execute_transaction(&[instruction1], &["User"]).await?;
+    let instruction2 = claim_vested(&user, &user)?;
+    env.execute_transaction(&[instruction2], &["User"]).await?;
+
+    let instruction3 = queue_reassign_investment(&api, &admin2, &admin4, &user, &new_user)?;
+    env.execute_transaction(&[instruction3], &["API", "Admin 2", "Admin 4"])
+        .await?;
+    sleep(Duration::from_secs(TIMELOCK_DELAY as u64));
+    // The rent of the history goes back to the wallet that paid for it
+    let instruction4 = execute_reassign_investment(&api, &user, &new_user, &api, &api)?;
+    let res = env.execute_transaction(&[instruction4], &["API"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::AccountOwnerMismatch),
+        "the rent of the history was refunded to the wrong wallet"
+    );
+    let instruction5 = execute_reassign_investment(&api, &user, &new_user, &api, &user)?;
+    env.execute_transaction(&[instruction5], &["API"]).await?;
+
+    // It follows the investments
+    assert!(env.get_account(&history_pda).await.is_none());
+    let history: ReleaseHistoryPda = env
+        .from_account(&new_history_pda)
+        .await
+        .ok_or("could not load the release history PDA")?;
+    assert_eq!(history.user, new_user);
+    assert_eq!(history.payer, api);
+    assert_eq!(history.records.len(), 1);
+    assert_eq!(
+        history.records[0].amount,
+        INVESTED_AMOUNT * u64::from(scheme.initial_unvesting) / 100_000
+    );
+
+    Ok(())
+}
@@ -0,0 +1,309 @@
+// File: bangk-ico/tests/referral.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:36:50
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+#![allow(clippy::integer_division)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+use std::{error, result};
+
+use bangk_ico::{
+    cancel_investment, cancel_referred_investment, fund_referral_rewards, referred_user_investment,
+    set_referral_program, ConfigurationPda, ReferralProgram, ReferrerStatsPda, UnvestingScheme,
+    UnvestingType, UserInvestmentPda, WalletType,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{issue_kyc, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer as _};
+use tests_utilities::onchain::Environment;
+
+pub mod common;
+
+const INVESTED_AMOUNT: u64 = 1_000_000_000;
+// 5% of the invested amount
+const REWARD: u64 = 50_000_000;
+const REFERRER_CAP: u64 = 60_000_000;
+
+const REWARDS_UNVESTING: UnvestingScheme = UnvestingScheme {
+    kind: UnvestingType::AdvisersPartners,
+    start: 4,
+    duration: 13,
+    initial_unvesting: 20_000,
+    weekly_unvesting: 10_000,
+    final_unvesting: 0,
+};
+
+const fn referral_program(source: WalletType) -> ReferralProgram {
+    ReferralProgram {
+        source,
+        rate: 5_000,
+        referrer_cap: REFERRER_CAP,
+        total_cap: 10 * REFERRER_CAP,
+        unvesting: REWARDS_UNVESTING,
+        rewarded: 0,
+        funded: 0,
+    }
+}
+
+async fn start_referral_program(env: &mut Environment) -> Result<()> {
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let instruction = set_referral_program(
+        &admin1,
+        &admin2,
+        &admin3,
+        Some(referral_program(WalletType::Marketing)),
+    )?;
+    env.execute_transaction(&[instruction], &["Admin 1", "Admin 2", "Admin 3"])
+        .await?;
+    Ok(())
+}
+
+/// Invest for a new user brought by the referrer.
+async fn referred_investment(env: &mut Environment, referrer: &Pubkey) -> Result<()> {
+    let api = env.wallets["API"].pubkey();
+    let user = Pubkey::new_unique();
+    issue_kyc(env, &user).await?;
+    let instruction = referred_user_investment(
+        &api,
+        &user,
+        UnvestingType::PublicSells1,
+        None,
+        INVESTED_AMOUNT,
+        referrer,
+    )?;
+    env.execute_transaction(&[instruction], &["API"]).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn referral_rewards() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    start_referral_program(&mut env).await?;
+    let referrer = Pubkey::new_unique();
+    let (stats_pda, _stats_bump) = ReferrerStatsPda::get_address(referrer, &PROGRAM_ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(referrer, &PROGRAM_ID);
+
+    referred_investment(&mut env, &referrer).await?;
+    let investment: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the referrer's investment")?;
+    assert!(investment
+        .investment
+        .investments
+        .first()
+        .is_some_and(|invest| invest.amount_bought == REWARD
+            && invest.custom_rule == Some(REWARDS_UNVESTING)));
+
+    // The second reward is capped, the third one is not granted
+    referred_investment(&mut env, &referrer).await?;
+    referred_investment(&mut env, &referrer).await?;
+    let stats: ReferrerStatsPda = env
+        .from_account(&stats_pda)
+        .await
+        .ok_or("could not load the referrer's statistics")?;
+    assert_eq!(stats.referrals, 3);
+    assert_eq!(stats.referred_amount, 3 * INVESTED_AMOUNT);
+    assert_eq!(stats.rewarded, REFERRER_CAP);
+    let capped: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the referrer's investment")?;
+    assert_eq!(capped.investment.investments.len(), 2);
+    assert!(capped
+        .investment
+        .investments
+        .last()
+        .is_some_and(|invest| invest.amount_bought == REFERRER_CAP - REWARD));
+
+    // The rewards are counted in the allocation of the ICO wallet, from which they are released
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration")?;
+    assert_eq!(config.amount_invested, 3 * INVESTED_AMOUNT + REFERRER_CAP);
+    assert_eq!(
+        config
+            .invested_per_kind
+            .get(&REWARDS_UNVESTING.kind)
+            .copied(),
+        Some(REFERRER_CAP)
+    );
+
+    // The rewards are transferred to the ICO wallet
+    let api = env.wallets["API"].pubkey();
+    let marketing = WalletType::Marketing.get_pda().0;
+    let ico = WalletType::Ico.get_pda().0;
+    let marketing_amount = env
+        .get_token_amount(&marketing)
+        .await
+        .ok_or("missing marketing wallet")?;
+    let ico_amount = env
+        .get_token_amount(&ico)
+        .await
+        .ok_or("missing ICO wallet")?;
+    let instruction = fund_referral_rewards(&api, WalletType::Marketing)?;
+    env.execute_transaction(&[instruction], &["API"]).await?;
+    assert_eq!(
+        env.get_token_amount(&marketing).await,
+        Some(marketing_amount - REFERRER_CAP)
+    );
+    assert_eq!(
+        env.get_token_amount(&ico).await,
+        Some(ico_amount + REFERRER_CAP)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn referral_reward_cancellation() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    start_referral_program(&mut env).await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let referrer = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let (stats_pda, _stats_bump) = ReferrerStatsPda::get_address(referrer, &PROGRAM_ID);
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(referrer, &PROGRAM_ID);
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+    issue_kyc(&mut env, &user).await?;
+    let instruction = referred_user_investment(
+        &api,
+        &user,
+        UnvestingType::PublicSells1,
+        None,
+        INVESTED_AMOUNT,
+        &referrer,
+    )?;
+    env.execute_transaction(&[instruction], &["API"]).await?;
+
+    // The referrer's accounts are needed to cancel the reward along with the investment
+    let unlinked = cancel_investment(
+        &api,
+        &admin2,
+        &user,
+        UnvestingType::PublicSells1,
+        INVESTED_AMOUNT / 2,
+    )?;
+    let res = env
+        .execute_transaction(&[unlinked], &["API", "Admin 2"])
+        .await;
+    assert!(res.is_err(), "the referrer's reward was left in place");
+
+    let partial = cancel_referred_investment(
+        &api,
+        &admin2,
+        &user,
+        UnvestingType::PublicSells1,
+        INVESTED_AMOUNT / 2,
+        &referrer,
+    )?;
+    env.execute_transaction(&[partial], &["API", "Admin 2"])
+        .await?;
+    let investment: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the referrer's investment")?;
+    assert!(investment
+        .investment
+        .investments
+        .first()
+        .is_some_and(|invest| invest.amount_bought == REWARD / 2));
+    let stats: ReferrerStatsPda = env
+        .from_account(&stats_pda)
+        .await
+        .ok_or("could not load the referrer's statistics")?;
+    assert_eq!(stats.referred_amount, INVESTED_AMOUNT / 2);
+    assert_eq!(stats.rewarded, REWARD / 2);
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration")?;
+    assert_eq!(config.amount_invested, (INVESTED_AMOUNT + REWARD) / 2);
+    assert_eq!(
+        config.referral.map(|program| program.rewarded),
+        Some(REWARD / 2)
+    );
+
+    // The reward disappears with the investment
+    let remaining = cancel_referred_investment(
+        &api,
+        &admin2,
+        &user,
+        UnvestingType::PublicSells1,
+        INVESTED_AMOUNT / 2,
+        &referrer,
+    )?;
+    env.execute_transaction(&[remaining], &["API", "Admin 2"])
+        .await?;
+    assert!(env
+        .from_account::<UserInvestmentPda>(&investment_pda)
+        .await
+        .is_none());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_referrals() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let api = env.wallets["API"].pubkey();
+    let user = Pubkey::new_unique();
+    issue_kyc(&mut env, &user).await?;
+
+    // No referral program yet
+    let referrer = Pubkey::new_unique();
+    let res = referred_investment(&mut env, &referrer).await;
+    assert!(res.is_err(), "a referral was accepted without a program");
+
+    // Invalid definition
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let invalid = set_referral_program(
+        &admin1,
+        &admin2,
+        &admin3,
+        Some(referral_program(WalletType::Reserve)),
+    )?;
+    let res_invalid = env
+        .execute_transaction(&[invalid], &["Admin 1", "Admin 2", "Admin 3"])
+        .await;
+    assert!(
+        res_invalid.is_err_and(|err| err == BangkError::InvalidOperation),
+        "an invalid referral program was accepted"
+    );
+
+    // Users can't refer themselves
+    start_referral_program(&mut env).await?;
+    let instruction = referred_user_investment(
+        &api,
+        &user,
+        UnvestingType::PublicSells1,
+        None,
+        INVESTED_AMOUNT,
+        &user,
+    )?;
+    let res_self = env.execute_transaction(&[instruction], &["API"]).await;
+    assert!(
+        res_self.is_err_and(|err| err == BangkError::InvalidOperation),
+        "a user referred themselves"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,315 @@
+// File: bangk-ico/tests/refund.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 17:11:44
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+use std::thread::sleep;
+use std::time::Duration;
+use std::{error, result};
+
+use bangk_ico::{
+    abort_sale, cancel_investment, cancel_investment_entry, execute_reassign_investment,
+    get_purchase_reference, get_sale_treasury_pda, launch_bgk, purchase, queue_reassign_investment,
+    refund, ConfigurationPda, InvestmentSelector, UnvestingType, UserInvestmentPda,
+    UserPurchasesPda, TIMELOCK_DELAY,
+};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, init_stablecoin_sale, issue_kyc, PROGRAM_ID};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer as _};
+use tests_utilities::onchain::Environment;
+
+pub mod common;
+
+// 0.05 USD per BGK
+const PRICE: u64 = 50_000;
+const AMOUNT: u64 = 1_000_000_000;
+const COST: u64 = 50_000_000;
+const FUNDS: u64 = 1_000_000_000;
+const CANCELLED: u64 = 400_000_000;
+const REFUNDED: u64 = 20_000_000;
+
+/// Get the number of tokens sold during the (only) sale phase.
+async fn sold(env: &mut Environment) -> Result<u64> {
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration")?;
+    Ok(config
+        .sale_phases
+        .first()
+        .ok_or("no sale phase is defined")?
+        .sold)
+}
+
+/// Set up a sale in which the user "Buyer" purchased `AMOUNT` tokens.
+async fn init_purchase() -> Result<(Environment, Pubkey, Pubkey, Pubkey)> {
+    let (mut env, mint) = init_stablecoin_sale(&spl_token_2022::ID, PRICE).await?;
+    let buyer = env.add_wallet("Buyer").await;
+    issue_kyc(&mut env, &buyer).await?;
+    let buyer_account = env
+        .mint_mock_stablecoin(&mint, &spl_token_2022::ID, &buyer, FUNDS)
+        .await;
+    let instruction = purchase(
+        &buyer,
+        &mint,
+        &spl_token_2022::ID,
+        UnvestingType::PublicSells1,
+        AMOUNT,
+    )?;
+    env.execute_transaction(&[instruction], &["Buyer"]).await?;
+
+    Ok((env, mint, buyer, buyer_account))
+}
+
+#[tokio::test]
+async fn refund_cancelled_purchase() -> Result<()> {
+    let (mut env, mint, buyer, buyer_account) = init_purchase().await?;
+    let (treasury, _treasury_bump) = get_sale_treasury_pda(&mint);
+    let (purchases_pda, _purchases_bump) = UserPurchasesPda::get_address(buyer, &PROGRAM_ID);
+
+    // Nothing to refund before the cancellation
+    let early = refund(&buyer, &buyer, &mint, &spl_token_2022::ID)?;
+    let res = env.execute_transaction(&[early], &["Buyer"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::NothingToRefund),
+        "a purchase that was not cancelled has been refunded"
+    );
+
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let cancel = cancel_investment(
+        &api,
+        &admin2,
+        &buyer,
+        UnvestingType::PublicSells1,
+        CANCELLED,
+    )?;
+    env.execute_transaction(&[cancel], &["API", "Admin 2"])
+        .await?;
+    // The cancelled tokens can be sold again
+    assert_eq!(sold(&mut env).await?, AMOUNT - CANCELLED);
+
+    let instruction = refund(&buyer, &buyer, &mint, &spl_token_2022::ID)?;
+    env.execute_transaction(&[instruction], &["Buyer"]).await?;
+    assert_eq!(
+        env.get_token_amount(&buyer_account).await,
+        Some(FUNDS - COST - REFUNDED)
+    );
+    assert_eq!(sold(&mut env).await?, AMOUNT - CANCELLED);
+    assert_eq!(env.get_token_amount(&treasury).await, Some(COST - REFUNDED));
+
+    let purchases: UserPurchasesPda = env
+        .from_account(&purchases_pda)
+        .await
+        .ok_or("could not load the user's purchases")?;
+    assert!(purchases
+        .purchases
+        .first()
+        .is_some_and(|record| record.cancelled == CANCELLED && record.refunded == REFUNDED));
+
+    // The same cancellation can't be refunded twice
+    let again = refund(&buyer, &buyer, &mint, &spl_token_2022::ID)?;
+    let twice = env.execute_transaction(&[again], &["Buyer"]).await;
+    assert!(
+        twice.is_err_and(|err| err == BangkError::NothingToRefund),
+        "a cancelled purchase has been refunded twice"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn refund_by_admin() -> Result<()> {
+    let (mut env, mint, buyer, buyer_account) = init_purchase().await?;
+
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let cancel = cancel_investment(&api, &admin2, &buyer, UnvestingType::PublicSells1, AMOUNT)?;
+    env.execute_transaction(&[cancel], &["API", "Admin 2"])
+        .await?;
+
+    // Someone else can't trigger the refund
+    let stranger = env.add_wallet("Stranger").await;
+    let unauthorized = refund(&stranger, &buyer, &mint, &spl_token_2022::ID)?;
+    let res = env
+        .execute_transaction(&[unauthorized], &["Stranger"])
+        .await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidSigner),
+        "the refund was triggered by an unauthorized wallet"
+    );
+
+    let instruction = refund(&api, &buyer, &mint, &spl_token_2022::ID)?;
+    env.execute_transaction(&[instruction], &["API"]).await?;
+    assert_eq!(env.get_token_amount(&buyer_account).await, Some(FUNDS));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancel_off_chain_investment() -> Result<()> {
+    let (mut env, mint, buyer, _buyer_account) = init_purchase().await?;
+
+    // An investment paid off-chain is recorded after the purchase
+    add_investment(&mut env, &buyer, AMOUNT, UnvestingType::PublicSells1, None).await?;
+
+    // Cancelling the investment paid off-chain leaves nothing to refund
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let cancel =
+        cancel_investment_entry(&api, &admin2, &buyer, InvestmentSelector::Index(1), None)?;
+    env.execute_transaction(&[cancel], &["API", "Admin 2"])
+        .await?;
+
+    let instruction = refund(&buyer, &buyer, &mint, &spl_token_2022::ID)?;
+    let res = env.execute_transaction(&[instruction], &["Buyer"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::NothingToRefund),
+        "an investment paid off-chain has been refunded"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn cancel_purchased_investment() -> Result<()> {
+    let (mut env, mint, buyer, buyer_account) = init_purchase().await?;
+    add_investment(&mut env, &buyer, AMOUNT, UnvestingType::PublicSells1, None).await?;
+
+    // The investment created by the purchase is linked to it
+    let (purchases_pda, _purchases_bump) = UserPurchasesPda::get_address(buyer, &PROGRAM_ID);
+    let purchases: UserPurchasesPda = env
+        .from_account(&purchases_pda)
+        .await
+        .ok_or("could not load the user's purchases")?;
+    let reference = purchases
+        .purchases
+        .first()
+        .ok_or("the purchase was not recorded")?
+        .reference;
+    assert_eq!(reference, get_purchase_reference(&buyer, 0));
+    let (investment_pda, _investment_bump) = UserInvestmentPda::get_address(buyer, &PROGRAM_ID);
+    let investments: UserInvestmentPda = env
+        .from_account(&investment_pda)
+        .await
+        .ok_or("could not load the user's investments")?;
+    assert!(investments.investment.has_reference(&reference));
+
+    // It is refunded even though as many tokens paid off-chain are still invested
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let cancel = cancel_investment_entry(
+        &api,
+        &admin2,
+        &buyer,
+        InvestmentSelector::Reference(reference),
+        None,
+    )?;
+    env.execute_transaction(&[cancel], &["API", "Admin 2"])
+        .await?;
+
+    let instruction = refund(&buyer, &buyer, &mint, &spl_token_2022::ID)?;
+    env.execute_transaction(&[instruction], &["Buyer"]).await?;
+    assert_eq!(env.get_token_amount(&buyer_account).await, Some(FUNDS));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn aborted_sale() -> Result<()> {
+    let (mut env, mint, buyer, buyer_account) = init_purchase().await?;
+    let (treasury, _treasury_bump) = get_sale_treasury_pda(&mint);
+
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let abort = abort_sale(&admin1, &admin2, &admin3)?;
+    env.execute_transaction(&[abort], &["Admin 1", "Admin 2", "Admin 3"])
+        .await?;
+
+    // No more purchases and no launch
+    let late = purchase(
+        &buyer,
+        &mint,
+        &spl_token_2022::ID,
+        UnvestingType::PublicSells1,
+        AMOUNT,
+    )?;
+    let res = env.execute_transaction(&[late], &["Buyer"]).await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::SaleAborted),
+        "a purchase was made after the sale was aborted"
+    );
+    let launch = launch_bgk(&admin1, &admin2, &admin3, chrono::Utc::now().timestamp())?;
+    let launched = env
+        .execute_transaction(&[launch], &["Admin 1", "Admin 2", "Admin 3"])
+        .await;
+    assert!(
+        launched.is_err_and(|err| err == BangkError::SaleAborted),
+        "the BGK was launched after the sale was aborted"
+    );
+
+    // Everything is refunded
+    let instruction = refund(&buyer, &buyer, &mint, &spl_token_2022::ID)?;
+    env.execute_transaction(&[instruction], &["Buyer"]).await?;
+    assert_eq!(env.get_token_amount(&buyer_account).await, Some(FUNDS));
+    assert_eq!(env.get_token_amount(&treasury).await, Some(0));
+    assert_eq!(sold(&mut env).await?, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn reassigned_purchases() -> Result<()> {
+    let (mut env, _mint, buyer, _buyer_account) = init_purchase().await?;
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin4 = env.wallets["Admin 4"].pubkey();
+    let new_owner = Pubkey::new_unique();
+    let (purchases_pda, _purchases_bump) = UserPurchasesPda::get_address(buyer, &PROGRAM_ID);
+    let (new_purchases_pda, _new_purchases_bump) =
+        UserPurchasesPda::get_address(new_owner, &PROGRAM_ID);
+    let cancel = cancel_investment(
+        &api,
+        &admin2,
+        &buyer,
+        UnvestingType::PublicSells1,
+        CANCELLED,
+    )?;
+    env.execute_transaction(&[cancel], &["API", "Admin 2"])
+        .await?;
+
+    let queue = queue_reassign_investment(&api, &admin2, &admin4, &buyer, &new_owner)?;
+    env.execute_transaction(&[queue], &["API", "Admin 2", "Admin 4"])
+        .await?;
+    sleep(Duration::from_secs(TIMELOCK_DELAY as u64));
+    let instruction = execute_reassign_investment(&api, &buyer, &new_owner, &buyer, &buyer)?;
+    env.execute_transaction(&[instruction], &["API"]).await?;
+
+    // The purchases follow the investments, to be refunded to the new wallet
+    assert!(env.get_account(&purchases_pda).await.is_none());
+    let purchases: UserPurchasesPda = env
+        .from_account(&new_purchases_pda)
+        .await
+        .ok_or("could not load the new owner's purchases")?;
+    assert_eq!(purchases.user, new_owner);
+    assert!(purchases
+        .purchases
+        .first()
+        .is_some_and(|record| record.amount == AMOUNT && record.cancelled == CANCELLED));
+
+    Ok(())
+}
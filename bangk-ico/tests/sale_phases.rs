@@ -0,0 +1,243 @@
+// File: bangk-ico/tests/sale_phases.rs
+// Project: bangk-onchain
+// Creation date: Sunday 18 October 2026
+// Author: Vincent Berthier <vincent.berthier@bangk.app>
+// -----
+// Last modified: Sunday 18 October 2026 @ 13:17:15
+// Modified by: Vincent Berthier
+// -----
+// Copyright © 2024 <Bangk> - All rights reserved
+
+#![allow(clippy::tests_outside_test_module)]
+#![allow(clippy::panic)]
+
+type Error = Box<dyn error::Error>;
+type Result<T> = result::Result<T, Error>;
+
+use std::{error, result};
+
+use bangk_ico::{set_sale_phases, user_investment, ConfigurationPda, SalePhase, UnvestingType};
+use bangk_onchain_common::Error as BangkError;
+use common::{add_investment, issue_kyc, launch_tokens, update_sale_phases};
+use solana_program_test::tokio;
+use solana_sdk::{pubkey::Pubkey, signer::Signer as _};
+use tests_utilities::onchain::Environment;
+
+use crate::common::PROGRAM_ID;
+
+pub mod common;
+
+const DAY: i64 = 86400;
+
+const fn phase(kind: UnvestingType, start: i64, end: i64, cap: u64) -> SalePhase {
+    SalePhase {
+        kind,
+        start,
+        end,
+        price: 50_000,
+        cap,
+        sold: 0,
+    }
+}
+
+async fn invest(
+    env: &mut Environment,
+    user: &Pubkey,
+    amount: u64,
+    kind: UnvestingType,
+) -> Result<result::Result<(), BangkError>> {
+    issue_kyc(env, user).await?;
+    let api = env.wallets["API"].pubkey();
+    let instruction = user_investment(&api, user, kind, None, amount)?;
+    Ok(env.execute_transaction(&[instruction], &["API"]).await)
+}
+
+async fn try_set_phases(
+    env: &mut Environment,
+    phases: Vec<SalePhase>,
+) -> Result<result::Result<(), BangkError>> {
+    let admin1 = env.wallets["Admin 1"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let admin3 = env.wallets["Admin 3"].pubkey();
+    let instruction = set_sale_phases(&admin1, &admin2, &admin3, phases)?;
+    Ok(env
+        .execute_transaction(&[instruction], &["Admin 1", "Admin 2", "Admin 3"])
+        .await)
+}
+
+#[tokio::test]
+async fn outside_of_phase() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let now = chrono::Utc::now().timestamp();
+    update_sale_phases(
+        &mut env,
+        vec![
+            phase(UnvestingType::PrivateSells, now - 2 * DAY, now - DAY, 1_000),
+            phase(UnvestingType::PublicSells1, now + DAY, now + 2 * DAY, 1_000),
+        ],
+    )
+    .await?;
+
+    let user = env.add_wallet("User").await;
+    for kind in [
+        UnvestingType::PrivateSells,
+        UnvestingType::PublicSells1,
+        UnvestingType::PublicSells2,
+    ] {
+        let res = invest(&mut env, &user, 100, kind).await?;
+        assert!(
+            res.is_err_and(|err| err == BangkError::NoActiveSalePhase),
+            "an investment was accepted outside of its sale phase"
+        );
+    }
+
+    // Types of investment that are not sold are not bound to the sale phases
+    add_investment(&mut env, &user, 100, UnvestingType::TeamFounders, None).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn phase_cap() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let now = chrono::Utc::now().timestamp();
+    let (config_pda, _config_bump) = ConfigurationPda::get_address(&PROGRAM_ID);
+    update_sale_phases(
+        &mut env,
+        vec![
+            phase(UnvestingType::PublicSells1, now - 2 * DAY, now - DAY, 1_000),
+            phase(UnvestingType::PublicSells1, now - DAY, now + DAY, 1_000),
+        ],
+    )
+    .await?;
+
+    let user = env.add_wallet("User").await;
+    add_investment(&mut env, &user, 600, UnvestingType::PublicSells1, None).await?;
+    let res = invest(&mut env, &user, 500, UnvestingType::PublicSells1).await?;
+    assert!(
+        res.is_err_and(|err| err == BangkError::SalePhaseCapExceeded),
+        "the sale phase's cap was exceeded"
+    );
+    add_investment(&mut env, &user, 400, UnvestingType::PublicSells1, None).await?;
+
+    let config: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration")?;
+    let sold = config
+        .sale_phases
+        .iter()
+        .map(|sale| sale.sold)
+        .collect::<Vec<_>>();
+    assert_eq!(sold, vec![0, 1_000]);
+
+    // Updating the phases keeps track of what was already sold
+    update_sale_phases(
+        &mut env,
+        vec![phase(
+            UnvestingType::PublicSells1,
+            now - DAY,
+            now + DAY,
+            2_000,
+        )],
+    )
+    .await?;
+    let updated: ConfigurationPda = env
+        .from_account(&config_pda)
+        .await
+        .ok_or("could not load the configuration")?;
+    assert_eq!(updated.sale_phases.len(), 1);
+    assert_eq!(
+        updated.sale_phases.first().map(|sale| sale.sold),
+        Some(1_000)
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_phases() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let now = chrono::Utc::now().timestamp();
+
+    let invalid = [
+        vec![
+            phase(UnvestingType::PublicSells1, now, now + 2 * DAY, 1_000),
+            phase(UnvestingType::PublicSells1, now + DAY, now + 3 * DAY, 1_000),
+        ],
+        vec![phase(UnvestingType::PublicSells2, now + DAY, now, 1_000)],
+        vec![phase(UnvestingType::PublicSells2, now, now + DAY, 0)],
+        vec![phase(UnvestingType::TeamFounders, now, now + DAY, 1_000)],
+    ];
+    for phases in invalid {
+        let res = try_set_phases(&mut env, phases).await?;
+        assert!(
+            res.is_err_and(|err| err == BangkError::InvalidSalePhase),
+            "an invalid sale phase definition was accepted"
+        );
+    }
+
+    // Overlapping phases are fine if they are not for the same type of investment
+    update_sale_phases(
+        &mut env,
+        vec![
+            phase(UnvestingType::PublicSells1, now, now + 2 * DAY, 1_000),
+            phase(UnvestingType::PublicSells2, now + DAY, now + 3 * DAY, 1_000),
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn not_enough_signers() -> Result<()> {
+    let mut env = common::init_default().await?;
+    let now = chrono::Utc::now().timestamp();
+
+    let api = env.wallets["API"].pubkey();
+    let admin2 = env.wallets["Admin 2"].pubkey();
+    let instruction = set_sale_phases(
+        &api,
+        &admin2,
+        &admin2,
+        vec![phase(UnvestingType::PublicSells1, now, now + DAY, 1_000)],
+    )?;
+    let res = env
+        .execute_transaction(&[instruction], &["API", "Admin 2"])
+        .await;
+    assert!(
+        res.is_err_and(|err| err == BangkError::InvalidSigner),
+        "the sale phases were updated without enough signers"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn after_launch() -> Result<()> {
+    let mut env = common::init_with_mint().await?;
+    let now = chrono::Utc::now().timestamp();
+
+    add_investment(
+        &mut env,
+        &Pubkey::new_unique(),
+        1_000,
+        UnvestingType::PublicSells1,
+        None,
+    )
+    .await?;
+    launch_tokens(&mut env, now).await?;
+
+    let res = try_set_phases(
+        &mut env,
+        vec![phase(UnvestingType::PublicSells1, now, now + DAY, 1_000)],
+    )
+    .await?;
+    assert!(
+        res.is_err_and(|err| err == BangkError::BGKTokenAlreadyLaunched),
+        "the sale phases were updated after the launch"
+    );
+
+    Ok(())
+}
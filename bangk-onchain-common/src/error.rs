@@ -3,7 +3,7 @@
 // Creation date: Sunday 09 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Wednesday 21 August 2024 @ 19:33:07
+// Last modified: Sunday 18 October 2026 @ 17:59:42
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -21,6 +21,9 @@ use solana_program::{
 pub type Result<T> = result::Result<T, Error>;
 
 /// Custom error that can occur in a Bangk On-Chain Program
+///
+/// The code of an error is its position in the enum, and is relied upon by the clients: new
+/// variants must be added at the end.
 #[derive(Clone, Debug, Display, Eq, PartialEq)]
 pub enum Error {
     /// If attempting to create an account that already exists.
@@ -173,6 +176,66 @@ pub enum Error {
     /// Tried to write on a non-existing PDA instead of creating it
     #[display("tried to write on a non-existing PDA instead of creating it")]
     WriteInsteadOfCreatePda,
+    /// Invalid sale phases definition (overlapping or incoherent phases).
+    #[display("invalid sale phase definition")]
+    InvalidSalePhase,
+    /// There is no sale phase running for this type of investment.
+    #[display("no sale phase is running for this type of investment")]
+    NoActiveSalePhase,
+    /// The number of tokens sold during a sale phase would exceed its cap.
+    #[display("the sale phase's cap would be exceeded")]
+    SalePhaseCapExceeded,
+    /// There is nothing left to refund to the user.
+    #[display("there is nothing to refund")]
+    NothingToRefund,
+    /// The token sale has been aborted.
+    #[display("the token sale has been aborted")]
+    SaleAborted,
+    /// Invalid KYC attestation definition (null level, past expiry or malformed jurisdiction).
+    #[display("invalid KYC attestation")]
+    InvalidKycAttestation,
+    /// The user's KYC attestation has expired.
+    #[display("the user's KYC attestation has expired")]
+    KycAttestationExpired,
+    /// The user has no KYC attestation.
+    #[display("the user has no KYC attestation")]
+    MissingKycAttestation,
+    /// The wallet is on the sanctions denylist.
+    #[display("the wallet is on the sanctions denylist")]
+    DenylistedWallet,
+    /// Tried to claim an airdrop twice.
+    #[display("the airdrop has already been claimed by this recipient")]
+    AirdropAlreadyClaimed,
+    /// Tried to claim an airdrop after its deadline.
+    #[display("the airdrop's claim period has ended")]
+    AirdropClosed,
+    /// Tried to reclaim the tokens of an airdrop before its deadline.
+    #[display("the airdrop's claim period is still running")]
+    AirdropNotClosed,
+    /// The Merkle proof given for a claim is invalid.
+    #[display("invalid Merkle proof")]
+    InvalidMerkleProof,
+    /// Invalid bonus rule definition.
+    #[display("invalid bonus rule definition")]
+    InvalidBonusRule,
+    /// The investment would exceed the cap of its type (overall or for a single user).
+    #[display("the investment cap would be exceeded")]
+    InvestmentCapExceeded,
+    /// An investment with the same idempotency key was already recorded for the user.
+    #[display("an investment with the same reference was already recorded")]
+    DuplicateInvestmentReference,
+    /// Some tokens of the investments have not been released yet.
+    #[display("the investments are not fully released")]
+    InvestmentNotFullyReleased,
+    /// The releases of the user's tokens are on hold for a compliance investigation.
+    #[display("the releases are on hold for a compliance investigation")]
+    ReleasesOnHold,
+    /// The user has reached the maximum number of investment entries, even after compaction.
+    #[display("too many investment entries for the user")]
+    TooManyInvestmentEntries,
+    /// The vesting position cannot be transferred (transfers disabled or position still locked).
+    #[display("the vesting position cannot be transferred")]
+    PositionNotTransferable,
 }
 
 impl From<Error> for ProgramError {
@@ -240,6 +303,33 @@ impl From<u32> for Error {
             x if x == Self::InvalidPdaType as u32 => Self::InvalidPdaType,
             x if x == Self::InvalidProgramId as u32 => Self::InvalidProgramId,
             x if x == Self::InvalidProjectStatus as u32 => Self::InvalidProjectStatus,
+            x if x == Self::InvalidSalePhase as u32 => Self::InvalidSalePhase,
+            x if x == Self::NoActiveSalePhase as u32 => Self::NoActiveSalePhase,
+            x if x == Self::SalePhaseCapExceeded as u32 => Self::SalePhaseCapExceeded,
+            x if x == Self::NothingToRefund as u32 => Self::NothingToRefund,
+            x if x == Self::SaleAborted as u32 => Self::SaleAborted,
+            x if x == Self::InvalidKycAttestation as u32 => Self::InvalidKycAttestation,
+            x if x == Self::KycAttestationExpired as u32 => Self::KycAttestationExpired,
+            x if x == Self::MissingKycAttestation as u32 => Self::MissingKycAttestation,
+            x if x == Self::DenylistedWallet as u32 => Self::DenylistedWallet,
+            x if x == Self::AirdropAlreadyClaimed as u32 => Self::AirdropAlreadyClaimed,
+            x if x == Self::AirdropClosed as u32 => Self::AirdropClosed,
+            x if x == Self::AirdropNotClosed as u32 => Self::AirdropNotClosed,
+            x if x == Self::InvalidMerkleProof as u32 => Self::InvalidMerkleProof,
+            x if x == Self::InvalidBonusRule as u32 => Self::InvalidBonusRule,
+            x if x == Self::InvestmentCapExceeded as u32 => Self::InvestmentCapExceeded,
+            x if x == Self::DuplicateInvestmentReference as u32 => {
+                Self::DuplicateInvestmentReference
+            }
+            x if x == Self::InvestmentNotFullyReleased as u32 => Self::InvestmentNotFullyReleased,
+            x if x == Self::ReleasesOnHold as u32 => Self::ReleasesOnHold,
+            x if x == Self::TooManyInvestmentEntries as u32 => Self::TooManyInvestmentEntries,
+            x if x == Self::PositionNotTransferable as u32 => Self::PositionNotTransferable,
+            x if x == Self::Clock as u32 => Self::Clock,
+            x if x == Self::InvalidAtaData as u32 => Self::InvalidAtaData,
+            x if x == Self::InvalidInvestedAmount as u32 => Self::InvalidInvestedAmount,
+            x if x == Self::InvestmentDoesNotExist as u32 => Self::InvestmentDoesNotExist,
+            x if x == Self::WriteInsteadOfCreatePda as u32 => Self::WriteInsteadOfCreatePda,
             _ => Self::UnknownError,
         }
     }
@@ -261,3 +351,18 @@ impl PrintProgramError for Error {
         msg!("BangkError: {}", self);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn released_error_codes() {
+        // Codes of the v1.0.0, which must not change
+        assert_eq!(Error::AccountAlreadyExists as u32, 0);
+        assert_eq!(Error::InvestmentDoesNotExist as u32, 32);
+        assert_eq!(Error::WriteInsteadOfCreatePda as u32, 49);
+        assert_eq!(Error::InvalidSalePhase as u32, 50);
+        assert_eq!(Error::from(32), Error::InvestmentDoesNotExist);
+    }
+}
@@ -3,7 +3,7 @@
 // Creation date: Thursday 25 July 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Thursday 22 August 2024 @ 12:52:36
+// Last modified: Sunday 18 October 2026 @ 17:59:42
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -41,6 +41,22 @@ pub enum PdaType {
     TimelockInstruction,
     /// A Bangk internal wallet
     Wallet,
+    /// Record of a user's on-chain ICO purchases.
+    IcoPurchase,
+    /// Attestation that a user passed the KYC procedure.
+    KycAttestation,
+    /// A shard of the sanctions denylist.
+    Denylist,
+    /// Definition of a Merkle airdrop.
+    Airdrop,
+    /// Bitmap of the claims made on an airdrop.
+    AirdropClaims,
+    /// Statistics of a referrer.
+    ReferrerStats,
+    /// History of the releases of a user's tokens.
+    ReleaseHistory,
+    /// Vesting position represented by an NFT.
+    VestingPosition,
 }
 
 /// Common properties of a Bangk PDA
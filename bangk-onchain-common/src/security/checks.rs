@@ -3,7 +3,7 @@
 // Creation date: Thursday 25 July 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Thursday 25 July 2024 @ 20:48:08
+// Last modified: Sunday 18 October 2026 @ 16:21:26
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -34,9 +34,25 @@ macro_rules! check_signers {
             $crate::security::OperationSecurityLevel::Routine
         );
     };
-    // Otherwise we need to validate the multisig
+    // Otherwise we need to validate the multisig, which is the admin one unless told otherwise
     ($accounts:expr, $multisig:expr, $level:path) => {
+        check_signers!(
+            $accounts,
+            $multisig,
+            $level,
+            $crate::security::MultiSigType::Admin
+        );
+    };
+    ($accounts:expr, $multisig:expr, $level:path, $sig_type:path) => {
         let pda = $crate::security::MultiSigPda::from_account($multisig)?;
+        if pda.multisig.sig_type != $sig_type {
+            $crate::debug!(
+                "{} is not a {:?} MultiSig",
+                stringify!($multisig),
+                $sig_type
+            );
+            return Err($crate::Error::InvalidPdaAddress.into());
+        }
         pda.multisig.validate($accounts, $level)?;
     };
 }
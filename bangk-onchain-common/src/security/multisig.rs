@@ -3,7 +3,7 @@
 // Creation date: Thursday 25 July 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Monday 12 August 2024 @ 16:45:53
+// Last modified: Sunday 18 October 2026 @ 13:17:15
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -27,6 +27,8 @@ pub enum MultiSigType {
     Admin,
     /// Contains the list of keys allowed to freeze or unfreeze ATAs.
     Freeze,
+    /// Contains the list of keys allowed to issue or revoke KYC attestations.
+    Compliance,
 }
 
 impl From<MultiSigType> for u8 {
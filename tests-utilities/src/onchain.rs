@@ -3,7 +3,7 @@
 // Creation date: Sunday 09 June 2024
 // Author: Vincent Berthier <vincent.berthier@bangk.app>
 // -----
-// Last modified: Wednesday 21 August 2024 @ 19:33:07
+// Last modified: Sunday 18 October 2026 @ 13:07:08
 // Modified by: Vincent Berthier
 // -----
 // Copyright © 2024 <Bangk> - All rights reserved
@@ -13,7 +13,12 @@ use std::{collections::HashMap, fmt::Debug, iter::once};
 use bangk_onchain_common::{pda::BangkPda, Error};
 use borsh::BorshDeserialize;
 use solana_program::{
-    hash::Hash, instruction::Instruction, pubkey::Pubkey, system_instruction::transfer,
+    hash::Hash,
+    instruction::Instruction,
+    program_pack::Pack as _,
+    pubkey,
+    pubkey::Pubkey,
+    system_instruction::{create_account, transfer},
     system_program,
 };
 use solana_program_runtime::invoke_context::BuiltinFunctionWithContext;
@@ -26,11 +31,16 @@ use solana_sdk::{
     signer::Signer,
     transaction::{Transaction, TransactionError},
 };
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
 use spl_token_2022::{
     extension::{
         metadata_pointer::MetadataPointer, BaseStateWithExtensions as _, Extension,
         StateWithExtensions,
     },
+    instruction::{initialize_mint2, mint_to},
     solana_zk_token_sdk::instruction::Pod,
     state::{self, Mint},
 };
@@ -43,6 +53,9 @@ const API_KEY: [u8; 64] = [
     112, 153, 240, 58,
 ];
 
+/// Address of the (legacy) SPL Token program.
+pub const SPL_TOKEN_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
 /// Environment used for On-Chain tests
 pub struct Environment {
     /// Public key of the program
@@ -309,4 +322,85 @@ impl Environment {
 
         key
     }
+
+    /// Creates the mint of a mock stablecoin, with the API key as the mint authority.
+    ///
+    /// The mint's keypair is added to the wallets under the given name.
+    ///
+    /// # Parameters
+    /// * `name` - Name of the stablecoin,
+    /// * `token_program` - Token program owning the mint (SPL Token or SPL Token 2022),
+    /// * `decimals` - Number of decimals of the stablecoin.
+    ///
+    /// # Panics
+    /// If the mint couldn't be created.
+    pub async fn create_mock_stablecoin(
+        &mut self,
+        name: &str,
+        token_program: &Pubkey,
+        decimals: u8,
+    ) -> Pubkey {
+        println!("creating mock stablecoin '{name}'");
+        let keypair = keypair_from_seed_phrase_and_passphrase(name, "passphrase").unwrap();
+        let mint = keypair.pubkey();
+        self.wallets.insert(name.into(), keypair);
+        let api = self.wallets["API"].pubkey();
+        let rent = self
+            .client
+            .get_rent()
+            .await
+            .unwrap()
+            .minimum_balance(Mint::LEN);
+
+        let instructions = [
+            create_account(&api, &mint, rent, Mint::LEN as u64, token_program),
+            initialize_mint2(token_program, &mint, &api, None, decimals).unwrap(),
+        ];
+        assert!(
+            self.execute_transaction(&instructions, &["API", name])
+                .await
+                .is_ok(),
+            "could not create the mock stablecoin {name}"
+        );
+
+        mint
+    }
+
+    /// Mints mock stablecoins to a wallet, creating its ATA if necessary.
+    ///
+    /// # Parameters
+    /// * `mint` - Mint of the mock stablecoin,
+    /// * `token_program` - Token program owning the mint,
+    /// * `owner` - Wallet receiving the stablecoins,
+    /// * `amount` - Amount of stablecoins to mint.
+    ///
+    /// # Returns
+    /// The address of the wallet's ATA.
+    ///
+    /// # Panics
+    /// If the tokens couldn't be minted.
+    pub async fn mint_mock_stablecoin(
+        &mut self,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> Pubkey {
+        println!("minting {amount} mock stablecoins to {owner}");
+        let api = self.wallets["API"].pubkey();
+        let ata = get_associated_token_address_with_program_id(owner, mint, token_program);
+
+        let instructions = [
+            create_associated_token_account_idempotent(&api, owner, mint, token_program),
+            mint_to(token_program, mint, &ata, &api, &[], amount).unwrap(),
+        ];
+        assert!(
+            self.execute_transaction(&instructions, &["API"])
+                .await
+                .is_ok(),
+            "could not mint the mock stablecoins"
+        );
+
+        ata
+    }
 }